@@ -9,9 +9,11 @@ use async_graphql_value::ConstValue;
 use criterion::Criterion;
 use hyper::body::Bytes;
 use reqwest::Request;
+use tailcall::core::cache::InMemoryCache;
 use tailcall::core::config::Batch;
 use tailcall::core::http::{DataLoaderRequest, HttpDataLoader, Response};
 use tailcall::core::ir::model::IoId;
+use tailcall::core::rate_limit::InMemoryRateLimiter;
 use tailcall::core::runtime::TargetRuntime;
 use tailcall::core::{cache, EnvIO, FileIO, HttpIO};
 
@@ -84,11 +86,13 @@ pub fn benchmark_data_loader(c: &mut Criterion) {
                     env: Arc::new(Env {}),
                     file: Arc::new(File {}),
                     cache: Arc::new(Cache {}),
+                    persisted_queries: Arc::new(InMemoryCache::default()),
+                    rate_limiter: Arc::new(InMemoryRateLimiter::default()),
                     extensions: Arc::new(vec![]),
                     cmd_worker: None,
                     worker: None,
                 };
-                let loader = HttpDataLoader::new(rt, None, false);
+                let loader = HttpDataLoader::new(rt, None, false, None, Default::default(), None);
                 let loader = loader.to_data_loader(Batch::default().delay(1));
 
                 let request1 = reqwest::Request::new(
@@ -40,7 +40,7 @@ pub fn benchmark_handle_request(c: &mut Criterion) {
                     .body(hyper::Body::from(QUERY))
                     .unwrap();
 
-                let _ = handle_request::<GraphQLRequest>(req, server_config.app_ctx.clone())
+                let _ = handle_request::<GraphQLRequest>(req, server_config.app_ctx())
                     .await
                     .unwrap();
             });
@@ -64,7 +64,7 @@ pub fn benchmark_handle_request(c: &mut Criterion) {
                     .body(hyper::Body::from(QUERY))
                     .unwrap();
 
-                let _ = handle_request::<GraphQLRequest>(req, server_config.app_ctx.clone())
+                let _ = handle_request::<GraphQLRequest>(req, server_config.app_ctx())
                     .await
                     .unwrap();
             });
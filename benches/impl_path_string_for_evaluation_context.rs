@@ -18,6 +18,7 @@ use tailcall::core::cache::InMemoryCache;
 use tailcall::core::http::{RequestContext, Response};
 use tailcall::core::ir::{EvalContext, ResolverContextLike, SelectionField};
 use tailcall::core::path::PathString;
+use tailcall::core::rate_limit::InMemoryRateLimiter;
 use tailcall::core::runtime::TargetRuntime;
 use tailcall::core::{EnvIO, FileIO, HttpIO};
 use tailcall_http_cache::HttpCacheManager;
@@ -248,6 +249,8 @@ fn request_context() -> RequestContext {
         env: Arc::new(Env {}),
         file: Arc::new(File {}),
         cache: Arc::new(InMemoryCache::default()),
+        persisted_queries: Arc::new(InMemoryCache::default()),
+        rate_limiter: Arc::new(InMemoryRateLimiter::default()),
         extensions: Arc::new(vec![]),
         cmd_worker: None,
         worker: None,
@@ -32,6 +32,11 @@ pub enum Command {
         /// production)
         #[arg(short, long, action = clap::ArgAction::Set, default_value_t = true)]
         verify_ssl: bool,
+
+        /// Watches the configuration files, including the ones linked with
+        /// @link, and reloads the server without a restart when they change
+        #[arg(short, long, default_value_t = false)]
+        watch: bool,
     },
 
     /// Validate a composition spec
@@ -0,0 +1,566 @@
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::Display;
+use std::fs::File;
+use std::hash::Hash;
+use std::io::{self, ErrorKind, Read};
+use std::marker::PhantomData;
+use std::num::NonZeroU64;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use once_cell::sync::Lazy;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+
+use super::StoredValue;
+use crate::core::cache::error::{Error, Result};
+use crate::core::{blueprint, Cache};
+
+/// The stores opened by the process, so that the clients and the cache
+/// writing to the same directory account for the same files. Opening a store
+/// again applies the size of the configuration it's opened with.
+static STORES: Lazy<Mutex<HashMap<PathBuf, Arc<DiskStore>>>> = Lazy::new(Default::default);
+
+/// Prefix of the keys of the values cached with `@cache`
+const VALUE_PREFIX: &str = "value:";
+
+/// Prefix of the keys of the responses of the HTTP cache
+const HTTP_PREFIX: &str = "http:";
+
+/// The size of the expiry and of the length of the key at the start of every
+/// file
+const HEADER_SIZE: usize = 12;
+
+/// The longest key of an entry. Files with a longer key are corrupt and read as
+/// a miss, so that their header can't trigger a huge allocation.
+const MAX_KEY_SIZE: usize = 64 * 1024;
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |now| now.as_millis() as u64)
+}
+
+/// Entries are stored in files named after the hash of their key
+fn file_name(key: &str) -> String {
+    Sha256::digest(key.as_bytes())
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect()
+}
+
+fn encode(key: &str, value: &[u8], expires_at: u64) -> Vec<u8> {
+    let mut content = Vec::with_capacity(HEADER_SIZE + key.len() + value.len());
+    content.extend_from_slice(&expires_at.to_be_bytes());
+    content.extend_from_slice(&(key.len() as u32).to_be_bytes());
+    content.extend_from_slice(key.as_bytes());
+    content.extend_from_slice(value);
+    content
+}
+
+/// Reads the expiry and the key of a file
+fn read_header(path: &Path) -> io::Result<(u64, String)> {
+    let mut file = File::open(path)?;
+    let mut header = [0; HEADER_SIZE];
+    file.read_exact(&mut header)?;
+
+    let expires_at = u64::from_be_bytes(header[..8].try_into().unwrap());
+    let key_len = u32::from_be_bytes(header[8..].try_into().unwrap()) as usize;
+    if key_len > MAX_KEY_SIZE {
+        return Err(io::Error::new(ErrorKind::InvalidData, "key too long"));
+    }
+    let mut key = vec![0; key_len];
+    file.read_exact(&mut key)?;
+
+    let key = String::from_utf8(key).map_err(|e| io::Error::new(ErrorKind::InvalidData, e))?;
+    Ok((expires_at, key))
+}
+
+/// Returns the value of a file, unless it belongs to another key with the
+/// same hash
+fn decode(key: &str, content: &[u8]) -> Option<Vec<u8>> {
+    let key_len = u32::from_be_bytes(content.get(8..HEADER_SIZE)?.try_into().ok()?) as usize;
+    let value_start = HEADER_SIZE + key_len;
+    if content.get(HEADER_SIZE..value_start)? != key.as_bytes() {
+        return None;
+    }
+    Some(content[value_start..].to_vec())
+}
+
+struct Entry {
+    key: String,
+    size: u64,
+    expires_at: u64,
+    /// The tick of the last read or write, the entries with the lowest being
+    /// evicted first
+    used: u64,
+}
+
+#[derive(Default)]
+struct Index {
+    entries: HashMap<String, Entry>,
+    /// The names of the entries ordered by their last use
+    lru: BTreeMap<u64, String>,
+    size: u64,
+    clock: u64,
+}
+
+impl Index {
+    fn tick(&mut self) -> u64 {
+        self.clock += 1;
+        self.clock
+    }
+
+    fn insert(&mut self, name: String, entry: Entry) {
+        self.size += entry.size;
+        self.lru.insert(entry.used, name.clone());
+        if let Some(previous) = self.entries.insert(name, entry) {
+            self.size -= previous.size;
+            self.lru.remove(&previous.used);
+        }
+    }
+
+    fn remove(&mut self, name: &str) -> Option<Entry> {
+        let entry = self.entries.remove(name)?;
+        self.size -= entry.size;
+        self.lru.remove(&entry.used);
+        Some(entry)
+    }
+
+    /// Marks an entry as used, returning it
+    fn touch(&mut self, name: &str) -> Option<&Entry> {
+        let used = self.tick();
+        let entry = self.entries.get_mut(name)?;
+        self.lru.remove(&entry.used);
+        self.lru.insert(used, name.to_string());
+        entry.used = used;
+        Some(entry)
+    }
+
+    /// Removes the least recently used entries until the size is below the
+    /// limit, returning the files to delete
+    fn evict(&mut self, max_size: u64) -> Vec<String> {
+        let mut evicted = Vec::new();
+        while self.size > max_size {
+            let Some(name) = self.lru.values().next().cloned() else {
+                break;
+            };
+            self.remove(&name);
+            evicted.push(name);
+        }
+        evicted
+    }
+}
+
+/// Entries stored as files of a directory, bounded by their total size. The
+/// index of the files is kept in memory and rebuilt from the directory when
+/// the store is opened.
+pub struct DiskStore {
+    dir: PathBuf,
+    max_size: AtomicU64,
+    index: Mutex<Index>,
+}
+
+impl DiskStore {
+    /// Opens the store of the directory, shared with the other users of the
+    /// same directory in the process
+    pub fn open(disk: &blueprint::Disk) -> io::Result<Arc<Self>> {
+        let mut stores = STORES.lock().unwrap();
+        let dir = PathBuf::from(&disk.path);
+        if let Some(store) = stores.get(&dir) {
+            store.resize(disk.max_size);
+            return Ok(store.clone());
+        }
+
+        let store = Arc::new(Self::load(dir.clone(), disk.max_size)?);
+        stores.insert(dir, store.clone());
+        Ok(store)
+    }
+
+    fn load(dir: PathBuf, max_size: u64) -> io::Result<Self> {
+        std::fs::create_dir_all(&dir)?;
+
+        let now = now();
+        let mut files = Vec::new();
+        for file in std::fs::read_dir(&dir)? {
+            let file = file?;
+            let name = file.file_name().to_string_lossy().to_string();
+            // leftovers of writes interrupted by the end of the process
+            if name.ends_with(".tmp") {
+                let _ = std::fs::remove_file(file.path());
+                continue;
+            }
+            if name.len() != 64 || !name.chars().all(|c| c.is_ascii_hexdigit()) {
+                continue;
+            }
+
+            let metadata = file.metadata()?;
+            match read_header(&file.path()) {
+                Ok((expires_at, key)) if expires_at > now => {
+                    let modified = metadata.modified().unwrap_or(UNIX_EPOCH);
+                    files.push((modified, name, key, metadata.len(), expires_at));
+                }
+                _ => {
+                    let _ = std::fs::remove_file(file.path());
+                }
+            }
+        }
+
+        // the files written last are considered the most recently used
+        files.sort_by_key(|(modified, ..)| *modified);
+        let mut index = Index::default();
+        for (_, name, key, size, expires_at) in files {
+            let used = index.tick();
+            index.insert(name, Entry { key, size, expires_at, used });
+        }
+        for name in index.evict(max_size) {
+            let _ = std::fs::remove_file(dir.join(name));
+        }
+
+        Ok(Self {
+            dir,
+            max_size: AtomicU64::new(max_size),
+            index: Mutex::new(index),
+        })
+    }
+
+    /// Changes the largest size of the store, evicting the least recently used
+    /// entries beyond it
+    fn resize(&self, max_size: u64) {
+        if self.max_size.swap(max_size, Ordering::Relaxed) == max_size {
+            return;
+        }
+
+        let evicted = self.index.lock().unwrap().evict(max_size);
+        for name in evicted {
+            let _ = std::fs::remove_file(self.dir.join(name));
+        }
+    }
+
+    pub async fn get(&self, key: &str) -> io::Result<Option<Vec<u8>>> {
+        let name = file_name(key);
+        let expired = {
+            let mut index = self.index.lock().unwrap();
+            match index.touch(&name) {
+                Some(entry) => entry.expires_at <= now(),
+                None => return Ok(None),
+            }
+        };
+        if expired {
+            self.remove_file(&name).await?;
+            return Ok(None);
+        }
+
+        match tokio::fs::read(self.dir.join(&name)).await {
+            Ok(content) => Ok(decode(key, &content)),
+            // evicted by a concurrent write
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Writes an entry, evicting the least recently used ones when the store
+    /// gets too large. Entries larger than the store, or with a key longer
+    /// than `MAX_KEY_SIZE`, are skipped.
+    pub async fn set(&self, key: &str, value: &[u8], ttl: Option<Duration>) -> io::Result<()> {
+        let expires_at = ttl.map_or(u64::MAX, |ttl| now().saturating_add(ttl.as_millis() as u64));
+        let content = encode(key, value, expires_at);
+        let size = content.len() as u64;
+        let max_size = self.max_size.load(Ordering::Relaxed);
+        if size > max_size || key.len() > MAX_KEY_SIZE {
+            return Ok(());
+        }
+
+        let name = file_name(key);
+        let used = self.index.lock().unwrap().tick();
+
+        // the file is renamed once written so that it's never read partially
+        let tmp = self.dir.join(format!("{}.{}.tmp", name, used));
+        tokio::fs::write(&tmp, &content).await?;
+        tokio::fs::rename(&tmp, self.dir.join(&name)).await?;
+
+        let evicted = {
+            let mut index = self.index.lock().unwrap();
+            let entry = Entry { key: key.to_string(), size, expires_at, used };
+            index.insert(name, entry);
+            index.evict(max_size)
+        };
+        for name in evicted {
+            self.delete_file(&name).await?;
+        }
+
+        Ok(())
+    }
+
+    pub async fn remove(&self, key: &str) -> io::Result<()> {
+        self.remove_file(&file_name(key)).await
+    }
+
+    /// Removes the entries with a key starting with the prefix
+    pub async fn clear(&self, prefix: &str) -> io::Result<()> {
+        let removed = {
+            let mut index = self.index.lock().unwrap();
+            let names = index
+                .entries
+                .iter()
+                .filter(|(_, entry)| entry.key.starts_with(prefix))
+                .map(|(name, _)| name.clone())
+                .collect::<Vec<_>>();
+            for name in &names {
+                index.remove(name);
+            }
+            names
+        };
+        for name in removed {
+            self.delete_file(&name).await?;
+        }
+
+        Ok(())
+    }
+
+    async fn remove_file(&self, name: &str) -> io::Result<()> {
+        self.index.lock().unwrap().remove(name);
+        self.delete_file(name).await
+    }
+
+    async fn delete_file(&self, name: &str) -> io::Result<()> {
+        match tokio::fs::remove_file(self.dir.join(name)).await {
+            Err(e) if e.kind() != ErrorKind::NotFound => Err(e),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// The responses of the HTTP cache are kept without expiry, their freshness
+/// being decided by their cache policy
+#[async_trait::async_trait]
+impl tailcall_http_cache::Persistence for DiskStore {
+    async fn load(&self, key: &str) -> tailcall_http_cache::Result<Option<Vec<u8>>> {
+        Ok(self.get(&format!("{}{}", HTTP_PREFIX, key)).await?)
+    }
+
+    async fn store(&self, key: &str, value: Vec<u8>) -> tailcall_http_cache::Result<()> {
+        Ok(self
+            .set(&format!("{}{}", HTTP_PREFIX, key), &value, None)
+            .await?)
+    }
+
+    async fn remove(&self, key: &str) -> tailcall_http_cache::Result<()> {
+        Ok(DiskStore::remove(self, &format!("{}{}", HTTP_PREFIX, key)).await?)
+    }
+
+    async fn clear(&self) -> tailcall_http_cache::Result<()> {
+        Ok(DiskStore::clear(self, HTTP_PREFIX).await?)
+    }
+}
+
+/// A cache kept on the disk, surviving the restarts of the process. The
+/// values are stored as JSON.
+pub struct DiskCache<K, V> {
+    store: Arc<DiskStore>,
+    hits: AtomicUsize,
+    miss: AtomicUsize,
+    _marker: PhantomData<fn() -> (K, V)>,
+}
+
+impl<K, V> DiskCache<K, V> {
+    pub fn new(store: Arc<DiskStore>) -> Self {
+        Self {
+            store,
+            hits: AtomicUsize::new(0),
+            miss: AtomicUsize::new(0),
+            _marker: PhantomData,
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl<K, V> Cache for DiskCache<K, V>
+where
+    K: Display + Hash + Eq + Send + Sync,
+    V: Serialize + DeserializeOwned + Send + Sync,
+{
+    type Key = K;
+    type Value = V;
+
+    async fn set<'a>(&'a self, key: K, value: V, ttl: NonZeroU64) -> Result<()> {
+        let value = serde_json::to_vec(&StoredValue::new(value))?;
+        self.store
+            .set(
+                &format!("{}{}", VALUE_PREFIX, key),
+                &value,
+                Some(Duration::from_millis(ttl.get())),
+            )
+            .await
+            .map_err(|e| Error::Kv(e.to_string()))
+    }
+
+    async fn get<'a>(&'a self, key: &'a K) -> Result<Option<V>> {
+        Ok(self.get_entry(key).await?.map(|(value, _)| value))
+    }
+
+    async fn get_entry<'a>(&'a self, key: &'a K) -> Result<Option<(V, u64)>> {
+        let value = self
+            .store
+            .get(&format!("{}{}", VALUE_PREFIX, key))
+            .await
+            .map_err(|e| Error::Kv(e.to_string()))?;
+
+        match value {
+            Some(value) => {
+                self.hits.fetch_add(1, Ordering::Relaxed);
+                let value: StoredValue<V> = serde_json::from_slice(&value)?;
+                Ok(Some(value.into_entry()))
+            }
+            None => {
+                self.miss.fetch_add(1, Ordering::Relaxed);
+                Ok(None)
+            }
+        }
+    }
+
+    fn hit_rate(&self) -> Option<f64> {
+        let hits = self.hits.load(Ordering::Relaxed);
+        let misses = self.miss.load(Ordering::Relaxed);
+
+        if hits + misses > 0 {
+            return Some(hits as f64 / (hits + misses) as f64);
+        }
+
+        None
+    }
+
+    async fn clear<'a>(&'a self) -> Result<()> {
+        self.store
+            .clear(VALUE_PREFIX)
+            .await
+            .map_err(|e| Error::Kv(e.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use async_graphql::ConstValue;
+
+    use super::*;
+    use crate::core::ir::model::IoId;
+
+    fn store(dir: &Path, max_size: u64) -> DiskStore {
+        DiskStore::load(dir.to_path_buf(), max_size).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_disk_cache_set_get() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = DiskCache::<IoId, ConstValue>::new(Arc::new(store(dir.path(), 1024)));
+        let value = ConstValue::from_json(serde_json::json!({"id": 1, "name": "Leanne"})).unwrap();
+
+        cache
+            .set(IoId::new(1), value.clone(), NonZeroU64::new(60000).unwrap())
+            .await
+            .unwrap();
+
+        assert_eq!(cache.get(&IoId::new(1)).await.unwrap(), Some(value));
+        assert_eq!(cache.get(&IoId::new(2)).await.unwrap(), None);
+        assert_eq!(cache.hit_rate(), Some(0.5));
+    }
+
+    #[tokio::test]
+    async fn test_survives_reopening() {
+        let dir = tempfile::tempdir().unwrap();
+        store(dir.path(), 1024)
+            .set("key", b"value", None)
+            .await
+            .unwrap();
+
+        let store = store(dir.path(), 1024);
+        assert_eq!(store.get("key").await.unwrap(), Some(b"value".to_vec()));
+    }
+
+    #[tokio::test]
+    async fn test_expiry() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = store(dir.path(), 1024);
+        store
+            .set("key", b"value", Some(Duration::from_millis(1)))
+            .await
+            .unwrap();
+
+        tokio::time::sleep(Duration::from_millis(10)).await;
+
+        assert_eq!(store.get("key").await.unwrap(), None);
+        assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_evicts_least_recently_used() {
+        let dir = tempfile::tempdir().unwrap();
+        // room for two entries of 100 bytes
+        let store = store(dir.path(), 250);
+        let value = [0; 100 - HEADER_SIZE - 1];
+
+        store.set("a", &value, None).await.unwrap();
+        store.set("b", &value, None).await.unwrap();
+        store.get("a").await.unwrap();
+        store.set("c", &value, None).await.unwrap();
+
+        assert!(store.get("a").await.unwrap().is_some());
+        assert!(store.get("b").await.unwrap().is_none());
+        assert!(store.get("c").await.unwrap().is_some());
+        assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_reopening_resizes() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().to_string_lossy().to_string();
+        let value = [0; 100 - HEADER_SIZE - 1];
+        let store =
+            DiskStore::open(&blueprint::Disk { path: path.clone(), max_size: 1024 }).unwrap();
+        store.set("a", &value, None).await.unwrap();
+        store.set("b", &value, None).await.unwrap();
+
+        // like a reload of the configuration with a smaller size
+        let reopened = DiskStore::open(&blueprint::Disk { path, max_size: 150 }).unwrap();
+
+        assert!(Arc::ptr_eq(&store, &reopened));
+        assert!(store.get("a").await.unwrap().is_none());
+        assert!(store.get("b").await.unwrap().is_some());
+        assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_clear_prefix() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = store(dir.path(), 1024);
+        store.set("http:a", b"a", None).await.unwrap();
+        store.set("value:b", b"b", None).await.unwrap();
+
+        store.clear(HTTP_PREFIX).await.unwrap();
+
+        assert_eq!(store.get("http:a").await.unwrap(), None);
+        assert_eq!(store.get("value:b").await.unwrap(), Some(b"b".to_vec()));
+    }
+
+    #[tokio::test]
+    async fn test_corrupt_key_length() {
+        let dir = tempfile::tempdir().unwrap();
+        store(dir.path(), 1024)
+            .set("key", b"value", None)
+            .await
+            .unwrap();
+
+        // a header announcing a key of 4GB
+        let path = dir.path().join(file_name("key"));
+        let mut content = std::fs::read(&path).unwrap();
+        content[8..HEADER_SIZE].copy_from_slice(&u32::MAX.to_be_bytes());
+        std::fs::write(&path, content).unwrap();
+
+        let store = store(dir.path(), 1024);
+        assert_eq!(store.get("key").await.unwrap(), None);
+        assert!(!path.exists());
+    }
+}
@@ -1,6 +1,10 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
 use std::time::Duration;
 
 use anyhow::Result;
+use futures_util::stream::{self, BoxStream};
+use futures_util::StreamExt;
 use http_cache_reqwest::{Cache, CacheMode, HttpCache, HttpCacheOptions};
 use hyper::body::Bytes;
 use once_cell::sync::Lazy;
@@ -16,9 +20,10 @@ use reqwest_middleware::{ClientBuilder, ClientWithMiddleware};
 use tailcall_http_cache::HttpCacheManager;
 use tracing_opentelemetry::OpenTelemetrySpanExt;
 
+use super::disk::DiskStore;
 use super::HttpIO;
 use crate::core::blueprint::telemetry::Telemetry;
-use crate::core::blueprint::Upstream;
+use crate::core::blueprint::{CacheStorage, Tls, Upstream};
 use crate::core::http::Response;
 
 static HTTP_CLIENT_REQUEST_COUNT: Lazy<Counter<u64>> = Lazy::new(|| {
@@ -70,60 +75,109 @@ fn get_response_status(response: &reqwest_middleware::Result<reqwest::Response>)
 #[derive(Clone)]
 pub struct NativeHttp {
     client: ClientWithMiddleware,
+    stream_client: Client,
     http2_only: bool,
     enable_telemetry: bool,
+    upstream: Upstream,
+    /// The responses cached by the client, when the cache is enabled
+    cache: Option<HttpCacheManager>,
+    /// Clients of the resolvers with their own TLS settings
+    tls_clients: Arc<Mutex<HashMap<Tls, Arc<NativeHttp>>>>,
 }
 
 impl Default for NativeHttp {
     fn default() -> Self {
         Self {
             client: ClientBuilder::new(Client::new()).build(),
+            stream_client: Client::new(),
             http2_only: false,
             enable_telemetry: false,
+            upstream: Upstream::default(),
+            cache: None,
+            tls_clients: Default::default(),
         }
     }
 }
 
+fn client_builder(upstream: &Upstream) -> Result<reqwest::ClientBuilder> {
+    let mut builder = Client::builder()
+        .tcp_keepalive(Some(Duration::from_secs(upstream.tcp_keep_alive)))
+        .connect_timeout(Duration::from_secs(upstream.connect_timeout))
+        .http2_keep_alive_interval(Some(Duration::from_secs(upstream.keep_alive_interval)))
+        .http2_keep_alive_timeout(Duration::from_secs(upstream.keep_alive_timeout))
+        .http2_keep_alive_while_idle(upstream.keep_alive_while_idle)
+        .pool_idle_timeout(Some(Duration::from_secs(upstream.pool_idle_timeout)))
+        .pool_max_idle_per_host(upstream.pool_max_idle_per_host)
+        .user_agent(upstream.user_agent.clone())
+        .danger_accept_invalid_certs(!upstream.verify_ssl);
+
+    // Add Http2 Prior Knowledge
+    if upstream.http2_only {
+        builder = builder.http2_prior_knowledge();
+    }
+
+    // Add Http Proxy
+    if let Some(ref proxy) = upstream.proxy {
+        builder = builder.proxy(
+            reqwest::Proxy::http(proxy.url.clone()).expect("Failed to set proxy in http client"),
+        );
+    }
+
+    // Add client certificate and certificate authorities
+    if let Some(ref tls) = upstream.tls {
+        builder = tls.configure(builder)?;
+    }
+
+    Ok(builder)
+}
+
 impl NativeHttp {
     pub fn init(upstream: &Upstream, telemetry: &Telemetry) -> Self {
-        let mut builder = Client::builder()
-            .tcp_keepalive(Some(Duration::from_secs(upstream.tcp_keep_alive)))
-            .timeout(Duration::from_secs(upstream.timeout))
-            .connect_timeout(Duration::from_secs(upstream.connect_timeout))
-            .http2_keep_alive_interval(Some(Duration::from_secs(upstream.keep_alive_interval)))
-            .http2_keep_alive_timeout(Duration::from_secs(upstream.keep_alive_timeout))
-            .http2_keep_alive_while_idle(upstream.keep_alive_while_idle)
-            .pool_idle_timeout(Some(Duration::from_secs(upstream.pool_idle_timeout)))
-            .pool_max_idle_per_host(upstream.pool_max_idle_per_host)
-            .user_agent(upstream.user_agent.clone())
-            .danger_accept_invalid_certs(!upstream.verify_ssl);
-
-        // Add Http2 Prior Knowledge
-        if upstream.http2_only {
-            builder = builder.http2_prior_knowledge();
-        }
-
-        // Add Http Proxy
-        if let Some(ref proxy) = upstream.proxy {
-            builder = builder.proxy(
-                reqwest::Proxy::http(proxy.url.clone())
-                    .expect("Failed to set proxy in http client"),
-            );
-        }
+        Self::build(upstream, telemetry.export.is_some())
+    }
 
+    fn build(upstream: &Upstream, enable_telemetry: bool) -> Self {
+        // the TLS settings are checked by the validation of the blueprint
+        let builder = client_builder(upstream)
+            .expect("Failed to build client")
+            .timeout(Duration::from_secs(upstream.timeout));
         let mut client = ClientBuilder::new(builder.build().expect("Failed to build client"));
 
+        let mut cache = None;
         if upstream.http_cache > 0 {
+            let mut manager = HttpCacheManager::new(upstream.http_cache);
+            // the responses are kept on the disk along with the values of `@cache`
+            if let Some(CacheStorage::Disk(disk)) = &upstream.cache {
+                match DiskStore::open(disk) {
+                    Ok(store) => manager = manager.with_persistence(store),
+                    Err(e) => {
+                        tracing::warn!("Failed to open the disk cache at {}: {}", disk.path, e)
+                    }
+                }
+            }
+
+            cache = Some(manager.clone());
             client = client.with(Cache(HttpCache {
                 mode: CacheMode::Default,
-                manager: HttpCacheManager::new(upstream.http_cache),
+                manager,
                 options: HttpCacheOptions::default(),
             }))
         }
+
+        // Streaming responses are long-lived and never cached, so they are
+        // executed on a client without the request timeout and cache layer.
+        let stream_client = client_builder(upstream)
+            .and_then(|builder| Ok(builder.build()?))
+            .expect("Failed to build client");
+
         Self {
             client: client.build(),
+            stream_client,
             http2_only: upstream.http2_only,
-            enable_telemetry: telemetry.export.is_some(),
+            enable_telemetry,
+            upstream: upstream.clone(),
+            cache,
+            tls_clients: Default::default(),
         }
     }
 }
@@ -183,6 +237,70 @@ impl HttpIO for NativeHttp {
         )
         .await?)
     }
+
+    async fn execute_stream(
+        &self,
+        mut request: reqwest::Request,
+    ) -> Result<BoxStream<'static, Result<Bytes>>> {
+        if self.http2_only {
+            *request.version_mut() = reqwest::Version::HTTP_2;
+        }
+
+        tracing::info!(
+            "{} {} {:?}",
+            request.method(),
+            request.url(),
+            request.version()
+        );
+        let response = self
+            .stream_client
+            .execute(request)
+            .await?
+            .error_for_status()
+            .map_err(|err| err.without_url())?;
+
+        let chunks = stream::unfold(Some(response), |response| async move {
+            let mut response = response?;
+            match response.chunk().await {
+                Ok(Some(chunk)) => Some((Ok(chunk), Some(response))),
+                Ok(None) => None,
+                Err(err) => Some((Err(err.into()), None)),
+            }
+        });
+
+        Ok(chunks.boxed())
+    }
+
+    fn supports_tls(&self) -> bool {
+        true
+    }
+
+    fn with_tls(&self, tls: &Tls) -> Option<Arc<dyn HttpIO>> {
+        let mut tls_clients = self.tls_clients.lock().unwrap();
+        let client = tls_clients.entry(tls.clone()).or_insert_with(|| {
+            let upstream = self.upstream.clone().tls(Some(tls.clone()));
+            Arc::new(Self::build(&upstream, self.enable_telemetry))
+        });
+
+        Some(client.clone())
+    }
+
+    async fn clear_cache(&self) -> anyhow::Result<()> {
+        if let Some(cache) = &self.cache {
+            cache.clear().await.map_err(|e| anyhow::anyhow!(e))?;
+        }
+        let tls_clients = self
+            .tls_clients
+            .lock()
+            .unwrap()
+            .values()
+            .cloned()
+            .collect::<Vec<_>>();
+        for client in tls_clients {
+            client.clear_cache().await?;
+        }
+        Ok(())
+    }
 }
 
 #[cfg(test)]
@@ -282,4 +400,53 @@ mod tests {
         let resp = make_request(&url1, &native_http).await;
         assert_eq!(resp.headers.get("x-cache-lookup").unwrap(), "MISS");
     }
+
+    #[tokio::test]
+    async fn test_native_http_clear_cache() {
+        let server = start_mock_server();
+
+        server.mock(|when, then| {
+            when.method(httpmock::Method::GET).path("/test");
+            then.status(200).body("Hello");
+        });
+
+        let upstream = Upstream { http_cache: 2, ..Default::default() };
+        let native_http = NativeHttp::init(&upstream, &Default::default());
+        let url = format!("http://localhost:{}/test", server.port());
+
+        make_request(&url, &native_http).await;
+        let resp = make_request(&url, &native_http).await;
+        assert_eq!(resp.headers.get("x-cache-lookup").unwrap(), "HIT");
+
+        native_http.clear_cache().await.unwrap();
+
+        let resp = make_request(&url, &native_http).await;
+        assert_eq!(resp.headers.get("x-cache-lookup").unwrap(), "MISS");
+    }
+
+    #[tokio::test]
+    async fn test_native_http_execute_stream() {
+        let server = start_mock_server();
+
+        server.mock(|when, then| {
+            when.method(httpmock::Method::GET).path("/events");
+            then.status(200)
+                .header("content-type", "text/event-stream")
+                .body("data: 1\n\ndata: 2\n\n");
+        });
+
+        let native_http = NativeHttp::init(&Default::default(), &Default::default());
+        let request_url = format!("http://localhost:{}/events", server.port());
+        let request = reqwest::Request::new(Method::GET, request_url.parse().unwrap());
+
+        let chunks = native_http
+            .execute_stream(request)
+            .await
+            .unwrap()
+            .map(|chunk| chunk.unwrap())
+            .collect::<Vec<_>>()
+            .await;
+
+        assert_eq!(chunks.concat(), b"data: 1\n\ndata: 2\n\n".to_vec());
+    }
 }
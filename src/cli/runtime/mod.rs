@@ -1,6 +1,8 @@
+mod disk;
 mod env;
 mod file;
 mod http;
+mod redis;
 
 use std::fs;
 use std::hash::Hash;
@@ -8,12 +10,16 @@ use std::sync::Arc;
 
 pub use http::NativeHttp;
 use inquire::{Confirm, Select};
+use serde::{Deserialize, Serialize};
 
-use crate::core::blueprint::Blueprint;
+use crate::core::blueprint::{Blueprint, CacheStorage};
 use crate::core::cache::InMemoryCache;
+use crate::core::circuit_breaker::{CircuitBreaker, CircuitBreakerHttp};
+use crate::core::persisted_query::PERSISTED_QUERY_CAPACITY;
+use crate::core::rate_limit::InMemoryRateLimiter;
 use crate::core::runtime::TargetRuntime;
 use crate::core::worker::{Command, Event};
-use crate::core::{blueprint, EnvIO, FileIO, HttpIO, WorkerIO};
+use crate::core::{blueprint, EntityCache, EnvIO, FileIO, HttpIO, WorkerIO};
 
 // Provides access to env in native rust environment
 fn init_env() -> Arc<dyn EnvIO> {
@@ -50,35 +56,98 @@ fn init_resolver_worker_io(
 }
 
 // Provides access to http in native rust environment
-fn init_http(blueprint: &Blueprint) -> Arc<dyn HttpIO> {
-    Arc::new(http::NativeHttp::init(
+fn init_http(
+    blueprint: &Blueprint,
+    circuit_breaker: Option<Arc<CircuitBreaker>>,
+) -> Arc<dyn HttpIO> {
+    let http = Arc::new(http::NativeHttp::init(
         &blueprint.upstream,
         &blueprint.telemetry,
-    ))
+    ));
+    with_circuit_breaker(http, circuit_breaker)
 }
 
 // Provides access to http in native rust environment
-fn init_http2_only(blueprint: &Blueprint) -> Arc<dyn HttpIO> {
-    Arc::new(http::NativeHttp::init(
+fn init_http2_only(
+    blueprint: &Blueprint,
+    circuit_breaker: Option<Arc<CircuitBreaker>>,
+) -> Arc<dyn HttpIO> {
+    let http = Arc::new(http::NativeHttp::init(
         &blueprint.upstream.clone().http2_only(true),
         &blueprint.telemetry,
-    ))
+    ));
+    with_circuit_breaker(http, circuit_breaker)
+}
+
+fn with_circuit_breaker(
+    http: Arc<dyn HttpIO>,
+    circuit_breaker: Option<Arc<CircuitBreaker>>,
+) -> Arc<dyn HttpIO> {
+    match circuit_breaker {
+        Some(circuit_breaker) => Arc::new(CircuitBreakerHttp::new(http, circuit_breaker)),
+        None => http,
+    }
 }
 
 fn init_in_memory_cache<K: Hash + Eq, V: Clone>() -> InMemoryCache<K, V> {
     InMemoryCache::default()
 }
 
+/// A value of the caches kept outside of the process, stored along with the
+/// time it was set at so that its age can be told
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct StoredValue<V> {
+    set_at: u64,
+    value: V,
+}
+
+impl<V> StoredValue<V> {
+    fn new(value: V) -> Self {
+        Self { set_at: chrono::Utc::now().timestamp_millis() as u64, value }
+    }
+
+    /// Returns the value along with its age in milliseconds
+    fn into_entry(self) -> (V, u64) {
+        let now = chrono::Utc::now().timestamp_millis() as u64;
+        (self.value, now.saturating_sub(self.set_at))
+    }
+}
+
+// Provides the cache of `@cache`, in memory unless a shared storage is set
+fn init_cache(blueprint: &Blueprint) -> Arc<EntityCache> {
+    match &blueprint.upstream.cache {
+        Some(CacheStorage::Redis(redis)) => Arc::new(redis::RedisCache::new(redis.clone())),
+        Some(CacheStorage::Disk(disk)) => match disk::DiskStore::open(disk) {
+            Ok(store) => Arc::new(disk::DiskCache::new(store)),
+            Err(e) => {
+                tracing::warn!("Failed to open the disk cache at {}: {}", disk.path, e);
+                Arc::new(init_in_memory_cache())
+            }
+        },
+        None => Arc::new(init_in_memory_cache()),
+    }
+}
+
 pub fn init(blueprint: &Blueprint) -> TargetRuntime {
     #[cfg(not(feature = "js"))]
     tracing::warn!("JS capabilities are disabled in this build");
 
+    // both clients share the circuits of the upstream origins
+    let circuit_breaker = blueprint
+        .upstream
+        .circuit_breaker
+        .as_ref()
+        .map(|circuit_breaker| Arc::new(CircuitBreaker::new(circuit_breaker.into())));
+
     TargetRuntime {
-        http: init_http(blueprint),
-        http2_only: init_http2_only(blueprint),
+        http: init_http(blueprint, circuit_breaker.clone()),
+        http2_only: init_http2_only(blueprint, circuit_breaker),
         env: init_env(),
         file: init_file(),
-        cache: Arc::new(init_in_memory_cache()),
+        cache: init_cache(blueprint),
+        persisted_queries: Arc::new(InMemoryCache::new(PERSISTED_QUERY_CAPACITY)),
+        rate_limiter: Arc::new(InMemoryRateLimiter::default()),
         extensions: Arc::new(vec![]),
         cmd_worker: init_http_worker_io(blueprint.server.script.clone()),
         worker: init_resolver_worker_io(blueprint.server.script.clone()),
@@ -0,0 +1,562 @@
+use std::fmt::Display;
+use std::hash::Hash;
+use std::marker::PhantomData;
+use std::num::NonZeroU64;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use anyhow::{anyhow, bail};
+use async_recursion::async_recursion;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use tokio::io::{AsyncBufRead, AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+use tokio::net::TcpStream;
+use tokio::sync::Semaphore;
+use tokio::time::timeout;
+
+use super::StoredValue;
+use crate::core::cache::error::{Error, Result};
+use crate::core::{blueprint, Cache};
+
+/// Connections kept open between the commands
+const MAX_IDLE_CONNECTIONS: usize = 16;
+
+/// Connections open at the same time, the commands beyond wait for one of
+/// them to be free
+const MAX_CONNECTIONS: usize = 64;
+
+/// The longest time to wait for a connection to Redis to be established
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// The longest time to wait for the reply to a command by default, so that
+/// an unresponsive Redis doesn't hold the requests
+const COMMAND_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// A reply of the RESP protocol
+#[derive(Debug, PartialEq)]
+enum Reply {
+    Status(String),
+    Error(String),
+    Integer(i64),
+    Bulk(Option<Vec<u8>>),
+    Array(Option<Vec<Reply>>),
+}
+
+/// Encodes a command as an array of bulk strings
+fn encode(args: &[&[u8]]) -> Vec<u8> {
+    let mut buf = format!("*{}\r\n", args.len()).into_bytes();
+    for arg in args {
+        buf.extend_from_slice(format!("${}\r\n", arg.len()).as_bytes());
+        buf.extend_from_slice(arg);
+        buf.extend_from_slice(b"\r\n");
+    }
+    buf
+}
+
+async fn read_line<R: AsyncBufRead + Unpin>(reader: &mut R) -> anyhow::Result<String> {
+    let mut line = String::new();
+    if reader.read_line(&mut line).await? == 0 {
+        bail!("The connection was closed by Redis");
+    }
+    Ok(line.trim_end_matches("\r\n").to_owned())
+}
+
+#[async_recursion]
+async fn read_reply<R: AsyncBufRead + Unpin + Send>(reader: &mut R) -> anyhow::Result<Reply> {
+    let line = read_line(reader).await?;
+    let value = line.get(1..).unwrap_or_default();
+
+    let reply = match line.as_bytes().first() {
+        Some(b'+') => Reply::Status(value.to_owned()),
+        Some(b'-') => Reply::Error(value.to_owned()),
+        Some(b':') => Reply::Integer(value.parse()?),
+        Some(b'$') => match usize::try_from(value.parse::<i64>()?) {
+            Ok(len) => {
+                // the content is followed by a CRLF. The buffer grows as the
+                // content arrives rather than trusting the announced length.
+                let mut data = Vec::new();
+                (&mut *reader)
+                    .take(len as u64 + 2)
+                    .read_to_end(&mut data)
+                    .await?;
+                if data.len() != len + 2 {
+                    bail!("The connection was closed by Redis");
+                }
+                data.truncate(len);
+                Reply::Bulk(Some(data))
+            }
+            Err(_) => Reply::Bulk(None),
+        },
+        Some(b'*') => match usize::try_from(value.parse::<i64>()?) {
+            Ok(len) => {
+                let mut items = Vec::new();
+                for _ in 0..len {
+                    items.push(read_reply(reader).await?);
+                }
+                Reply::Array(Some(items))
+            }
+            Err(_) => Reply::Array(None),
+        },
+        _ => bail!("Invalid reply from Redis: {}", line),
+    };
+
+    Ok(reply)
+}
+
+struct Connection {
+    stream: BufReader<TcpStream>,
+    command_timeout: Duration,
+}
+
+impl Connection {
+    async fn connect(redis: &blueprint::Redis, command_timeout: Duration) -> anyhow::Result<Self> {
+        let stream = timeout(
+            CONNECT_TIMEOUT,
+            TcpStream::connect((redis.host.as_str(), redis.port)),
+        )
+        .await
+        .map_err(|_| anyhow!("Timed out connecting to Redis"))??;
+        let mut connection = Self { stream: BufReader::new(stream), command_timeout };
+
+        if let Some(password) = &redis.password {
+            match &redis.username {
+                Some(username) => {
+                    connection
+                        .command(&[b"AUTH", username.as_bytes(), password.as_bytes()])
+                        .await?
+                }
+                None => connection.command(&[b"AUTH", password.as_bytes()]).await?,
+            };
+        }
+        if let Some(database) = redis.database {
+            connection
+                .command(&[b"SELECT", database.to_string().as_bytes()])
+                .await?;
+        }
+
+        Ok(connection)
+    }
+
+    async fn command(&mut self, args: &[&[u8]]) -> anyhow::Result<Reply> {
+        let reply = timeout(self.command_timeout, async {
+            self.stream.get_mut().write_all(&encode(args)).await?;
+            read_reply(&mut self.stream).await
+        })
+        .await
+        .map_err(|_| anyhow!("Timed out waiting for a reply from Redis"))??;
+
+        match reply {
+            Reply::Error(error) => bail!("Redis replied with an error: {}", error),
+            reply => Ok(reply),
+        }
+    }
+}
+
+/// A cache stored in Redis, shared by all the instances connected to it. The
+/// values are stored as JSON and expire with the TTL of the entries.
+pub struct RedisCache<K, V> {
+    redis: blueprint::Redis,
+    idle: Mutex<Vec<Connection>>,
+    connections: Semaphore,
+    command_timeout: Duration,
+    hits: AtomicUsize,
+    miss: AtomicUsize,
+    _marker: PhantomData<fn() -> (K, V)>,
+}
+
+impl<K, V> RedisCache<K, V> {
+    pub fn new(redis: blueprint::Redis) -> Self {
+        Self {
+            redis,
+            idle: Mutex::new(Vec::new()),
+            connections: Semaphore::new(MAX_CONNECTIONS),
+            command_timeout: COMMAND_TIMEOUT,
+            hits: AtomicUsize::new(0),
+            miss: AtomicUsize::new(0),
+            _marker: PhantomData,
+        }
+    }
+
+    /// Sets the longest time to wait for the reply to a command
+    pub fn command_timeout(mut self, command_timeout: Duration) -> Self {
+        self.command_timeout = command_timeout;
+        self
+    }
+
+    async fn command(&self, args: &[&[u8]]) -> anyhow::Result<Reply> {
+        let _permit = self.connections.acquire().await?;
+        let connection = self.idle.lock().unwrap().pop();
+        let mut connection = match connection {
+            Some(connection) => connection,
+            None => Connection::connect(&self.redis, self.command_timeout).await?,
+        };
+
+        let reply = connection.command(args).await;
+
+        // a connection that failed might be left in the middle of a reply
+        if reply.is_ok() {
+            let mut idle = self.idle.lock().unwrap();
+            if idle.len() < MAX_IDLE_CONNECTIONS {
+                idle.push(connection);
+            }
+        }
+
+        reply
+    }
+}
+
+impl<K: Display, V> RedisCache<K, V> {
+    fn key(&self, key: &K) -> String {
+        format!("{}{}", self.redis.prefix, key)
+    }
+}
+
+/// The pattern matching the keys that start with the prefix
+fn prefix_pattern(prefix: &str) -> String {
+    let mut pattern = String::with_capacity(prefix.len() + 1);
+    for c in prefix.chars() {
+        if matches!(c, '*' | '?' | '[' | ']' | '\\') {
+            pattern.push('\\');
+        }
+        pattern.push(c);
+    }
+    pattern.push('*');
+    pattern
+}
+
+#[async_trait::async_trait]
+impl<K, V> Cache for RedisCache<K, V>
+where
+    K: Display + Hash + Eq + Send + Sync,
+    V: Serialize + DeserializeOwned + Send + Sync,
+{
+    type Key = K;
+    type Value = V;
+
+    async fn set<'a>(&'a self, key: K, value: V, ttl: NonZeroU64) -> Result<()> {
+        let value = serde_json::to_vec(&StoredValue::new(value))?;
+        self.command(&[
+            b"SET",
+            self.key(&key).as_bytes(),
+            &value,
+            b"PX",
+            ttl.get().to_string().as_bytes(),
+        ])
+        .await
+        .map_err(|e| Error::Kv(e.to_string()))?;
+
+        Ok(())
+    }
+
+    async fn get<'a>(&'a self, key: &'a K) -> Result<Option<V>> {
+        Ok(self.get_entry(key).await?.map(|(value, _)| value))
+    }
+
+    async fn get_entry<'a>(&'a self, key: &'a K) -> Result<Option<(V, u64)>> {
+        let reply = self
+            .command(&[b"GET", self.key(key).as_bytes()])
+            .await
+            .map_err(|e| Error::Kv(e.to_string()))?;
+
+        match reply {
+            Reply::Bulk(Some(value)) => {
+                self.hits.fetch_add(1, Ordering::Relaxed);
+                let value: StoredValue<V> = serde_json::from_slice(&value)?;
+                Ok(Some(value.into_entry()))
+            }
+            Reply::Bulk(None) => {
+                self.miss.fetch_add(1, Ordering::Relaxed);
+                Ok(None)
+            }
+            reply => Err(Error::Kv(format!(
+                "Unexpected reply from Redis: {:?}",
+                reply
+            ))),
+        }
+    }
+
+    fn hit_rate(&self) -> Option<f64> {
+        let hits = self.hits.load(Ordering::Relaxed);
+        let misses = self.miss.load(Ordering::Relaxed);
+
+        if hits + misses > 0 {
+            return Some(hits as f64 / (hits + misses) as f64);
+        }
+
+        None
+    }
+
+    /// Deletes the keys starting with the prefix, scanning them by batches
+    /// so that Redis isn't blocked
+    async fn clear<'a>(&'a self) -> Result<()> {
+        let pattern = prefix_pattern(&self.redis.prefix);
+        let mut cursor = b"0".to_vec();
+        loop {
+            let reply = self
+                .command(&[
+                    b"SCAN",
+                    cursor.as_slice(),
+                    b"MATCH",
+                    pattern.as_bytes(),
+                    b"COUNT",
+                    b"1000",
+                ])
+                .await
+                .map_err(|e| Error::Kv(e.to_string()))?;
+
+            let keys = match reply {
+                Reply::Array(Some(mut items)) if items.len() == 2 => {
+                    match (items.pop(), items.pop()) {
+                        (Some(Reply::Array(Some(keys))), Some(Reply::Bulk(Some(next)))) => {
+                            cursor = next;
+                            keys
+                        }
+                        _ => return Err(Error::Kv("Unexpected reply from Redis to SCAN".into())),
+                    }
+                }
+                reply => {
+                    return Err(Error::Kv(format!(
+                        "Unexpected reply from Redis: {:?}",
+                        reply
+                    )))
+                }
+            };
+
+            let keys = keys
+                .into_iter()
+                .filter_map(|key| match key {
+                    Reply::Bulk(key) => key,
+                    _ => None,
+                })
+                .collect::<Vec<_>>();
+            if !keys.is_empty() {
+                let mut args = vec![b"DEL".as_slice()];
+                args.extend(keys.iter().map(Vec::as_slice));
+                self.command(&args)
+                    .await
+                    .map_err(|e| Error::Kv(e.to_string()))?;
+            }
+
+            if cursor == b"0" {
+                return Ok(());
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::sync::Arc;
+    use std::time::{Duration, Instant};
+
+    use async_graphql::ConstValue;
+    use tokio::net::TcpListener;
+
+    use super::*;
+    use crate::core::ir::model::IoId;
+
+    type Store = Arc<Mutex<HashMap<Vec<u8>, (Vec<u8>, Instant)>>>;
+
+    /// Answers the commands used by the cache like Redis does
+    fn respond(store: &Store, args: Vec<Vec<u8>>) -> Vec<u8> {
+        let mut store = store.lock().unwrap();
+        match args.first().map(Vec::as_slice) {
+            Some(b"AUTH" | b"SELECT") => b"+OK\r\n".to_vec(),
+            Some(b"SET") => {
+                let ttl: u64 = String::from_utf8_lossy(&args[4]).parse().unwrap();
+                let expires_at = Instant::now() + Duration::from_millis(ttl);
+                store.insert(args[1].clone(), (args[2].clone(), expires_at));
+                b"+OK\r\n".to_vec()
+            }
+            Some(b"GET") => match store.get(&args[1]) {
+                Some((value, expires_at)) if *expires_at > Instant::now() => {
+                    let mut reply = format!("${}\r\n", value.len()).into_bytes();
+                    reply.extend_from_slice(value);
+                    reply.extend_from_slice(b"\r\n");
+                    reply
+                }
+                _ => b"$-1\r\n".to_vec(),
+            },
+            // every key is returned at once, along with the final cursor
+            Some(b"SCAN") => {
+                let prefix = args[3].strip_suffix(b"*").unwrap();
+                let keys = store
+                    .keys()
+                    .filter(|key| key.starts_with(prefix))
+                    .collect::<Vec<_>>();
+                let mut reply = format!("*2\r\n$1\r\n0\r\n*{}\r\n", keys.len()).into_bytes();
+                for key in keys {
+                    reply.extend_from_slice(format!("${}\r\n", key.len()).as_bytes());
+                    reply.extend_from_slice(key);
+                    reply.extend_from_slice(b"\r\n");
+                }
+                reply
+            }
+            Some(b"DEL") => {
+                let deleted = args[1..]
+                    .iter()
+                    .filter(|key| store.remove(*key).is_some())
+                    .count();
+                format!(":{}\r\n", deleted).into_bytes()
+            }
+            _ => b"-ERR unknown command\r\n".to_vec(),
+        }
+    }
+
+    /// Starts a server speaking enough of the Redis protocol for the cache
+    async fn start_redis(store: Store) -> blueprint::Redis {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+
+        tokio::spawn(async move {
+            loop {
+                let (stream, _) = listener.accept().await.unwrap();
+                let store = store.clone();
+                tokio::spawn(async move {
+                    let mut stream = BufReader::new(stream);
+                    while let Ok(Reply::Array(Some(items))) = read_reply(&mut stream).await {
+                        let args = items
+                            .into_iter()
+                            .filter_map(|item| match item {
+                                Reply::Bulk(arg) => arg,
+                                _ => None,
+                            })
+                            .collect();
+                        let reply = respond(&store, args);
+                        stream.get_mut().write_all(&reply).await.unwrap();
+                    }
+                });
+            }
+        });
+
+        blueprint::Redis {
+            host: "127.0.0.1".to_string(),
+            port,
+            username: None,
+            password: Some("secret".to_string()),
+            database: Some(1),
+            prefix: "tailcall:".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_encode() {
+        assert_eq!(
+            encode(&[b"GET", b"tailcall:1"]),
+            b"*2\r\n$3\r\nGET\r\n$10\r\ntailcall:1\r\n".to_vec()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_read_reply() {
+        let mut input: &[u8] = b"*3\r\n+OK\r\n:42\r\n$-1\r\n";
+        let reply = read_reply(&mut input).await.unwrap();
+        assert_eq!(
+            reply,
+            Reply::Array(Some(vec![
+                Reply::Status("OK".to_string()),
+                Reply::Integer(42),
+                Reply::Bulk(None),
+            ]))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_read_reply_truncated() {
+        // the lengths are announced by the server and can't be trusted
+        let mut input: &[u8] = b"*9223372036854775807\r\n$9223372036854775805\r\nabc";
+        assert!(read_reply(&mut input).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_redis_cache_set_get() {
+        let store = Store::default();
+        let cache = RedisCache::<IoId, ConstValue>::new(start_redis(store.clone()).await);
+        let value = ConstValue::from_json(serde_json::json!({"id": 1, "name": "Leanne"})).unwrap();
+
+        cache
+            .set(IoId::new(1), value.clone(), NonZeroU64::new(60000).unwrap())
+            .await
+            .unwrap();
+
+        assert_eq!(cache.get(&IoId::new(1)).await.unwrap(), Some(value));
+        assert_eq!(cache.get(&IoId::new(2)).await.unwrap(), None);
+        assert_eq!(cache.hit_rate(), Some(0.5));
+        assert!(store.lock().unwrap().contains_key(b"tailcall:1".as_slice()));
+    }
+
+    #[test]
+    fn test_prefix_pattern() {
+        assert_eq!(prefix_pattern("tailcall:"), "tailcall:*");
+        assert_eq!(prefix_pattern("app[1]*"), "app\\[1\\]\\**");
+    }
+
+    #[tokio::test]
+    async fn test_redis_cache_clear() {
+        let store = Store::default();
+        let cache = RedisCache::<IoId, ConstValue>::new(start_redis(store.clone()).await);
+        let ttl = NonZeroU64::new(60000).unwrap();
+        cache
+            .set(IoId::new(1), ConstValue::from("Leanne"), ttl)
+            .await
+            .unwrap();
+        let other = Duration::from_secs(60);
+        store
+            .lock()
+            .unwrap()
+            .insert(b"other:1".to_vec(), (b"1".to_vec(), Instant::now() + other));
+
+        cache.clear().await.unwrap();
+
+        assert_eq!(cache.get(&IoId::new(1)).await.unwrap(), None);
+        assert!(store.lock().unwrap().contains_key(b"other:1".as_slice()));
+    }
+
+    #[tokio::test]
+    async fn test_redis_cache_unreachable() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        drop(listener);
+
+        let cache = RedisCache::<IoId, ConstValue>::new(blueprint::Redis {
+            host: "127.0.0.1".to_string(),
+            port,
+            username: None,
+            password: None,
+            database: None,
+            prefix: "tailcall:".to_string(),
+        });
+
+        assert!(matches!(cache.get(&IoId::new(1)).await, Err(Error::Kv(_))));
+    }
+
+    #[tokio::test]
+    async fn test_redis_cache_unresponsive() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        // the connections are accepted but the commands are never replied to
+        tokio::spawn(async move {
+            let mut streams = Vec::new();
+            while let Ok((stream, _)) = listener.accept().await {
+                streams.push(stream);
+            }
+        });
+
+        let command_timeout = Duration::from_millis(100);
+        let cache = RedisCache::<IoId, ConstValue>::new(blueprint::Redis {
+            host: "127.0.0.1".to_string(),
+            port,
+            username: None,
+            password: None,
+            database: None,
+            prefix: "tailcall:".to_string(),
+        })
+        .command_timeout(command_timeout);
+
+        let started = Instant::now();
+        assert!(matches!(cache.get(&IoId::new(1)).await, Err(Error::Kv(_))));
+        assert!(started.elapsed() < command_timeout * 10);
+    }
+}
@@ -0,0 +1,371 @@
+use std::future::Future;
+use std::io::{Read, Write};
+
+use hyper::body::HttpBody;
+use hyper::header::{HeaderValue, ACCEPT_ENCODING, CONTENT_ENCODING, CONTENT_LENGTH, VARY};
+use hyper::{Body, Request, Response, StatusCode};
+
+use crate::core::config::{Compression, CompressionAlgorithm};
+
+/// Name of the algorithm in the `Accept-Encoding` and `Content-Encoding`
+/// headers
+fn name(algorithm: CompressionAlgorithm) -> &'static str {
+    match algorithm {
+        CompressionAlgorithm::Gzip => "gzip",
+        CompressionAlgorithm::Brotli => "br",
+        CompressionAlgorithm::Zstd => "zstd",
+    }
+}
+
+fn from_name(name: &str) -> Option<CompressionAlgorithm> {
+    match name.trim().to_lowercase().as_str() {
+        "gzip" | "x-gzip" => Some(CompressionAlgorithm::Gzip),
+        "br" => Some(CompressionAlgorithm::Brotli),
+        "zstd" => Some(CompressionAlgorithm::Zstd),
+        _ => None,
+    }
+}
+
+fn compress(algorithm: CompressionAlgorithm, bytes: &[u8]) -> std::io::Result<Vec<u8>> {
+    match algorithm {
+        CompressionAlgorithm::Gzip => {
+            let mut encoder =
+                flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+            encoder.write_all(bytes)?;
+            encoder.finish()
+        }
+        CompressionAlgorithm::Brotli => {
+            // a lower quality than the maximum keeps the compression fast enough
+            // for responses generated on every request
+            let mut encoder = brotli::CompressorWriter::new(Vec::new(), 4096, 5, 22);
+            encoder.write_all(bytes)?;
+            Ok(encoder.into_inner())
+        }
+        CompressionAlgorithm::Zstd => zstd::stream::encode_all(bytes, 3),
+    }
+}
+
+/// Decompresses the bytes, reading at most `max_size` bytes of output. Returns
+/// `None` when the decompressed content is larger, so that a small body can't
+/// be inflated without bounds.
+fn decompress(
+    algorithm: CompressionAlgorithm,
+    bytes: &[u8],
+    max_size: u64,
+) -> std::io::Result<Option<Vec<u8>>> {
+    let decoder: Box<dyn Read> = match algorithm {
+        CompressionAlgorithm::Gzip => Box::new(flate2::read::MultiGzDecoder::new(bytes)),
+        CompressionAlgorithm::Brotli => Box::new(brotli::Decompressor::new(bytes, 4096)),
+        CompressionAlgorithm::Zstd => Box::new(zstd::stream::read::Decoder::new(bytes)?),
+    };
+
+    let mut decompressed = Vec::new();
+    decoder
+        .take(max_size.saturating_add(1))
+        .read_to_end(&mut decompressed)?;
+
+    Ok((decompressed.len() as u64 <= max_size).then_some(decompressed))
+}
+
+/// Picks the algorithm with the highest quality in the `Accept-Encoding`
+/// header, the order of the algorithms breaking the ties.
+fn negotiate(
+    accept_encoding: &str,
+    algorithms: &[CompressionAlgorithm],
+) -> Option<CompressionAlgorithm> {
+    let qualities = accept_encoding
+        .split(',')
+        .filter_map(|item| {
+            let mut parts = item.split(';');
+            let coding = parts.next()?.trim().to_lowercase();
+            let quality = parts
+                .find_map(|param| param.trim().strip_prefix("q="))
+                .map_or(Some(1.0), |quality| quality.trim().parse::<f32>().ok())?;
+            Some((coding, quality))
+        })
+        .collect::<Vec<_>>();
+
+    let quality = |algorithm: CompressionAlgorithm| {
+        let find = |coding: &str| {
+            qualities
+                .iter()
+                .find(|(name, _)| name == coding)
+                .map(|(_, quality)| *quality)
+        };
+        find(name(algorithm)).or_else(|| find("*")).unwrap_or(0.0)
+    };
+
+    algorithms
+        .iter()
+        .map(|algorithm| (*algorithm, quality(*algorithm)))
+        .filter(|(_, quality)| *quality > 0.0)
+        .fold(None, |best, (algorithm, quality)| match best {
+            Some((_, best_quality)) if best_quality >= quality => best,
+            _ => Some((algorithm, quality)),
+        })
+        .map(|(algorithm, _)| algorithm)
+}
+
+fn error_response(status: StatusCode, message: String) -> Response<Body> {
+    let mut response = Response::new(Body::from(message));
+    *response.status_mut() = status;
+    response
+}
+
+/// Replaces a body compressed with one of the supported algorithms with its
+/// decompressed content. Fails with the response to send back when the
+/// encoding isn't supported, the body can't be decompressed or it's larger than
+/// the maximum size once decompressed.
+async fn decompress_request(
+    req: Request<Body>,
+    compression: &Compression,
+) -> Result<Request<Body>, Response<Body>> {
+    let Some(encoding) = req.headers().get(CONTENT_ENCODING) else {
+        return Ok(req);
+    };
+    let encoding = encoding.to_str().unwrap_or_default().trim().to_lowercase();
+    if encoding.is_empty() || encoding == "identity" {
+        return Ok(req);
+    }
+    let Some(algorithm) = from_name(&encoding) else {
+        return Err(error_response(
+            StatusCode::UNSUPPORTED_MEDIA_TYPE,
+            format!("Unsupported Content-Encoding: {}", encoding),
+        ));
+    };
+
+    let max_size = compression.get_max_decompressed_size();
+    let (mut parts, body) = req.into_parts();
+    let body = hyper::body::to_bytes(body)
+        .await
+        .map_err(|e| error_response(StatusCode::BAD_REQUEST, e.to_string()))?;
+    let body = tokio::task::spawn_blocking(move || decompress(algorithm, &body, max_size))
+        .await
+        .map_err(|e| error_response(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        .map_err(|e| {
+            error_response(
+                StatusCode::BAD_REQUEST,
+                format!("Failed to decompress the body: {}", e),
+            )
+        })?
+        .ok_or_else(|| {
+            error_response(
+                StatusCode::PAYLOAD_TOO_LARGE,
+                format!("The decompressed body is larger than {} bytes", max_size),
+            )
+        })?;
+
+    parts.headers.remove(CONTENT_ENCODING);
+    parts.headers.remove(CONTENT_LENGTH);
+
+    Ok(Request::from_parts(parts, Body::from(body)))
+}
+
+/// Compresses the body of the response with the algorithm negotiated with
+/// the client. Streamed bodies, like the events of subscriptions, and bodies
+/// smaller than the minimum size are sent as they are.
+async fn compress_response(
+    response: Response<Body>,
+    accept_encoding: Option<&str>,
+    compression: &Compression,
+) -> anyhow::Result<Response<Body>> {
+    let Some(size) = response.body().size_hint().exact() else {
+        return Ok(response);
+    };
+    if size < compression.get_min_size() as u64 || response.headers().contains_key(CONTENT_ENCODING)
+    {
+        return Ok(response);
+    }
+
+    let (mut parts, body) = response.into_parts();
+    parts
+        .headers
+        .append(VARY, HeaderValue::from_static("accept-encoding"));
+
+    let algorithm = accept_encoding
+        .and_then(|accept_encoding| negotiate(accept_encoding, &compression.get_algorithms()));
+    let Some(algorithm) = algorithm else {
+        return Ok(Response::from_parts(parts, body));
+    };
+
+    let body = hyper::body::to_bytes(body).await?;
+    let body = tokio::task::spawn_blocking(move || compress(algorithm, &body)).await??;
+
+    parts
+        .headers
+        .insert(CONTENT_ENCODING, HeaderValue::from_static(name(algorithm)));
+    parts.headers.remove(CONTENT_LENGTH);
+
+    Ok(Response::from_parts(parts, Body::from(body)))
+}
+
+/// When `compression` is set, runs the handler with the body of the request
+/// decompressed and compresses the body of its response. Otherwise the request
+/// and the response are left as they are.
+pub async fn handle<F, Fut>(
+    req: Request<Body>,
+    compression: Option<&Compression>,
+    handler: F,
+) -> anyhow::Result<Response<Body>>
+where
+    F: FnOnce(Request<Body>) -> Fut,
+    Fut: Future<Output = anyhow::Result<Response<Body>>>,
+{
+    let Some(compression) = compression else {
+        return handler(req).await;
+    };
+
+    let req = match decompress_request(req, compression).await {
+        Ok(req) => req,
+        Err(response) => return Ok(response),
+    };
+    let accept_encoding = req
+        .headers()
+        .get(ACCEPT_ENCODING)
+        .and_then(|value| value.to_str().ok())
+        .map(str::to_owned);
+
+    let response = handler(req).await?;
+
+    compress_response(response, accept_encoding.as_deref(), compression).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ALGORITHMS: [CompressionAlgorithm; 3] = [
+        CompressionAlgorithm::Zstd,
+        CompressionAlgorithm::Brotli,
+        CompressionAlgorithm::Gzip,
+    ];
+
+    fn compression(min_size: usize) -> Compression {
+        Compression { min_size: Some(min_size), ..Default::default() }
+    }
+
+    async fn echo(req: Request<Body>) -> anyhow::Result<Response<Body>> {
+        Ok(Response::new(req.into_body()))
+    }
+
+    #[test]
+    fn test_negotiate() {
+        assert_eq!(
+            negotiate("gzip, deflate, br", &ALGORITHMS),
+            Some(CompressionAlgorithm::Brotli)
+        );
+        assert_eq!(
+            negotiate("gzip;q=1.0, br;q=0.5", &ALGORITHMS),
+            Some(CompressionAlgorithm::Gzip)
+        );
+        assert_eq!(
+            negotiate("*;q=0.5, zstd;q=0", &ALGORITHMS),
+            Some(CompressionAlgorithm::Brotli)
+        );
+        assert_eq!(negotiate("deflate, identity", &ALGORITHMS), None);
+        assert_eq!(
+            negotiate("gzip, br", &[CompressionAlgorithm::Gzip]),
+            Some(CompressionAlgorithm::Gzip)
+        );
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let content = "tailcall ".repeat(100);
+        for algorithm in ALGORITHMS {
+            let compressed = compress(algorithm, content.as_bytes()).unwrap();
+            assert!(compressed.len() < content.len());
+
+            let decompressed = decompress(algorithm, &compressed, 1024).unwrap();
+            assert_eq!(decompressed.unwrap(), content.as_bytes());
+
+            let decompressed = decompress(algorithm, &compressed, 100).unwrap();
+            assert_eq!(decompressed, None);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_compresses_response() {
+        let content = "tailcall ".repeat(200);
+        let req = Request::builder()
+            .header(ACCEPT_ENCODING, "gzip")
+            .body(Body::from(content.clone()))
+            .unwrap();
+
+        let response = handle(req, Some(&compression(1024)), echo).await.unwrap();
+
+        assert_eq!(response.headers()[CONTENT_ENCODING], "gzip");
+        assert_eq!(response.headers()[VARY], "accept-encoding");
+        let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+        let body = decompress(CompressionAlgorithm::Gzip, &body, 2048).unwrap();
+        assert_eq!(body.unwrap(), content.as_bytes());
+    }
+
+    #[tokio::test]
+    async fn test_skips_small_responses() {
+        let req = Request::builder()
+            .header(ACCEPT_ENCODING, "gzip")
+            .body(Body::from("tailcall"))
+            .unwrap();
+
+        let response = handle(req, Some(&compression(1024)), echo).await.unwrap();
+
+        assert!(!response.headers().contains_key(CONTENT_ENCODING));
+        let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+        assert_eq!(body, "tailcall");
+    }
+
+    #[tokio::test]
+    async fn test_decompresses_request() {
+        let body = compress(CompressionAlgorithm::Zstd, b"tailcall").unwrap();
+        let req = Request::builder()
+            .header(CONTENT_ENCODING, "zstd")
+            .body(Body::from(body))
+            .unwrap();
+
+        let response = handle(req, Some(&compression(1024)), echo).await.unwrap();
+
+        let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+        assert_eq!(body, "tailcall");
+    }
+
+    #[tokio::test]
+    async fn test_rejects_large_request() {
+        let body = compress(CompressionAlgorithm::Gzip, &[0; 4096]).unwrap();
+        let req = Request::builder()
+            .header(CONTENT_ENCODING, "gzip")
+            .body(Body::from(body))
+            .unwrap();
+        let compression = Compression { max_decompressed_size: Some(1024), ..Default::default() };
+
+        let response = handle(req, Some(&compression), echo).await.unwrap();
+
+        assert_eq!(response.status(), StatusCode::PAYLOAD_TOO_LARGE);
+    }
+
+    #[tokio::test]
+    async fn test_ignores_request_without_compression() {
+        let body = compress(CompressionAlgorithm::Zstd, b"tailcall").unwrap();
+        let req = Request::builder()
+            .header(CONTENT_ENCODING, "zstd")
+            .body(Body::from(body.clone()))
+            .unwrap();
+
+        let response = handle(req, None, echo).await.unwrap();
+
+        let actual = hyper::body::to_bytes(response.into_body()).await.unwrap();
+        assert_eq!(actual, body);
+    }
+
+    #[tokio::test]
+    async fn test_rejects_unsupported_encoding() {
+        let req = Request::builder()
+            .header(CONTENT_ENCODING, "compress")
+            .body(Body::from("tailcall"))
+            .unwrap();
+
+        let response = handle(req, Some(&compression(1024)), echo).await.unwrap();
+
+        assert_eq!(response.status(), StatusCode::UNSUPPORTED_MEDIA_TYPE);
+    }
+}
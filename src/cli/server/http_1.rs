@@ -1,11 +1,13 @@
 use std::sync::Arc;
 
+use hyper::server::conn::AddrStream;
 use hyper::service::{make_service_fn, service_fn};
 use tokio::sync::oneshot;
 
 use super::server_config::ServerConfig;
+use super::{compression, websocket};
 use crate::core::async_graphql_hyper::{GraphQLBatchRequest, GraphQLRequest};
-use crate::core::http::handle_request;
+use crate::core::http::{handle_request, ClientIp};
 use crate::core::Errata;
 
 pub async fn start_http_1(
@@ -13,26 +15,38 @@ pub async fn start_http_1(
     server_up_sender: Option<oneshot::Sender<()>>,
 ) -> anyhow::Result<()> {
     let addr = sc.addr();
-    let make_svc_single_req = make_service_fn(|_conn| {
+    let make_svc = make_service_fn(|conn: &AddrStream| {
         let state = Arc::clone(&sc);
+        let client_ip = ClientIp(conn.remote_addr().ip());
         async move {
-            Ok::<_, anyhow::Error>(service_fn(move |req| {
-                handle_request::<GraphQLRequest>(req, state.app_ctx.clone())
-            }))
-        }
-    });
-
-    let make_svc_batch_req = make_service_fn(|_conn| {
-        let state = Arc::clone(&sc);
-        async move {
-            Ok::<_, anyhow::Error>(service_fn(move |req| {
-                handle_request::<GraphQLBatchRequest>(req, state.app_ctx.clone())
+            Ok::<_, anyhow::Error>(service_fn(move |mut req| {
+                // read for every request, so that a reload of the config applies
+                let app_ctx = state.app_ctx();
+                req.extensions_mut().insert(client_ip);
+                async move {
+                    if websocket::is_upgrade_request(&req, &app_ctx) {
+                        return websocket::upgrade(req, app_ctx);
+                    }
+
+                    let compression = app_ctx.blueprint.server.compression.clone();
+                    if app_ctx.blueprint.server.enable_batch_requests {
+                        compression::handle(req, compression.as_ref(), |req| {
+                            handle_request::<GraphQLBatchRequest>(req, app_ctx)
+                        })
+                        .await
+                    } else {
+                        compression::handle(req, compression.as_ref(), |req| {
+                            handle_request::<GraphQLRequest>(req, app_ctx)
+                        })
+                        .await
+                    }
+                }
             }))
         }
     });
     let builder = hyper::Server::try_bind(&addr)
         .map_err(Errata::from)?
-        .http1_pipeline_flush(sc.app_ctx.blueprint.server.pipeline_flush);
+        .http1_pipeline_flush(sc.pipeline_flush());
     super::log_launch(sc.as_ref());
 
     if let Some(sender) = server_up_sender {
@@ -41,14 +55,7 @@ pub async fn start_http_1(
             .or(Err(anyhow::anyhow!("Failed to send message")))?;
     }
 
-    let server: std::prelude::v1::Result<(), hyper::Error> =
-        if sc.blueprint.server.enable_batch_requests {
-            builder.serve(make_svc_batch_req).await
-        } else {
-            builder.serve(make_svc_single_req).await
-        };
-
-    let result = server.map_err(Errata::from);
+    let result = builder.serve(make_svc).await.map_err(Errata::from);
 
     Ok(result?)
 }
@@ -1,50 +1,141 @@
 #![allow(clippy::too_many_arguments)]
+use std::net::IpAddr;
 use std::sync::Arc;
+use std::time::Duration;
 
-use hyper::server::conn::AddrIncoming;
-use hyper::service::{make_service_fn, service_fn};
-use hyper::Server;
-use hyper_rustls::TlsAcceptor;
-use rustls_pki_types::CertificateDer;
+use hyper::server::conn::Http;
+use hyper::service::service_fn;
+use rustls_pki_types::{CertificateDer, UnixTime};
+use tokio::net::TcpListener;
 use tokio::sync::oneshot;
+use tokio_rustls::rustls::server::danger::ClientCertVerifier;
+use tokio_rustls::rustls::server::WebPkiClientVerifier;
+use tokio_rustls::rustls::{self, RootCertStore};
+use tokio_rustls::TlsAcceptor;
+use x509_parser::certificate::X509Certificate;
+use x509_parser::extensions::GeneralName;
+use x509_parser::prelude::FromDer;
 
+use super::compression;
 use super::server_config::ServerConfig;
 use crate::core::async_graphql_hyper::{GraphQLBatchRequest, GraphQLRequest};
 use crate::core::config::PrivateKey;
-use crate::core::http::handle_request;
-use crate::core::Errata;
+use crate::core::http::{handle_request, ClientCertificate, ClientIp};
+
+/// The longest time a client has to complete the TLS handshake
+const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// The time to wait before accepting connections again after a failure, like
+/// running out of file descriptors
+const ACCEPT_BACKOFF: Duration = Duration::from_millis(100);
+
+/// A CA that client certificates are verified against. The chains presented
+/// by the clients are verified against each CA on its own, to tell the
+/// providers which CAs issued them.
+struct Issuer {
+    cert: CertificateDer<'static>,
+    verifier: Arc<dyn ClientCertVerifier>,
+}
+
+fn issuers(client_ca: &[CertificateDer<'static>]) -> anyhow::Result<Vec<Issuer>> {
+    client_ca
+        .iter()
+        .map(|ca| {
+            let mut roots = RootCertStore::empty();
+            roots.add(ca.clone())?;
+            let verifier = WebPkiClientVerifier::builder(Arc::new(roots)).build()?;
+
+            Ok(Issuer { cert: ca.clone(), verifier })
+        })
+        .collect()
+}
+
+fn tls_config(
+    cert: Vec<CertificateDer<'static>>,
+    key: PrivateKey,
+    client_ca: Vec<CertificateDer<'static>>,
+) -> anyhow::Result<rustls::ServerConfig> {
+    let builder = rustls::ServerConfig::builder();
+    let builder = if client_ca.is_empty() {
+        builder.with_no_client_auth()
+    } else {
+        let mut roots = RootCertStore::empty();
+        for ca in client_ca {
+            roots.add(ca)?;
+        }
+        // clients without a certificate are still accepted, `@protected` decides
+        // which fields require one
+        let verifier = WebPkiClientVerifier::builder(Arc::new(roots))
+            .allow_unauthenticated()
+            .build()?;
+        builder.with_client_cert_verifier(verifier)
+    };
+
+    let mut config = builder.with_single_cert(cert, key.into_inner())?;
+    config.alpn_protocols = vec![b"h2".to_vec()];
+
+    Ok(config)
+}
+
+/// Reads the subject and the SANs of the certificate presented by a client,
+/// along with the CAs its chain is verified against
+fn client_certificate(
+    chain: &[CertificateDer<'static>],
+    issuers: &[Issuer],
+) -> Option<ClientCertificate> {
+    let (end_entity, intermediates) = chain.split_first()?;
+    let (_, cert) = X509Certificate::from_der(end_entity.as_ref()).ok()?;
+
+    let san = cert
+        .subject_alternative_name()
+        .ok()
+        .flatten()
+        .map(|extension| {
+            extension
+                .value
+                .general_names
+                .iter()
+                .filter_map(|name| match name {
+                    GeneralName::DNSName(name) => Some(name.to_string()),
+                    GeneralName::RFC822Name(email) => Some(email.to_string()),
+                    GeneralName::URI(uri) => Some(uri.to_string()),
+                    GeneralName::IPAddress(ip) => match ip.len() {
+                        4 => Some(IpAddr::from(<[u8; 4]>::try_from(*ip).ok()?).to_string()),
+                        16 => Some(IpAddr::from(<[u8; 16]>::try_from(*ip).ok()?).to_string()),
+                        _ => None,
+                    },
+                    _ => None,
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let now = UnixTime::now();
+    let issuers = issuers
+        .iter()
+        .filter(|issuer| {
+            issuer
+                .verifier
+                .verify_client_cert(end_entity, intermediates, now)
+                .is_ok()
+        })
+        .map(|issuer| issuer.cert.clone())
+        .collect();
+
+    Some(ClientCertificate { subject: cert.subject().to_string(), san, issuers })
+}
 
 pub async fn start_http_2(
     sc: Arc<ServerConfig>,
     cert: Vec<CertificateDer<'static>>,
     key: PrivateKey,
+    client_ca: Vec<CertificateDer<'static>>,
     server_up_sender: Option<oneshot::Sender<()>>,
 ) -> anyhow::Result<()> {
     let addr = sc.addr();
-    let incoming = AddrIncoming::bind(&addr)?;
-    let acceptor = TlsAcceptor::builder()
-        .with_single_cert(cert, key.into_inner())?
-        .with_http2_alpn()
-        .with_incoming(incoming);
-    let make_svc_single_req = make_service_fn(|_conn| {
-        let state = Arc::clone(&sc);
-        async move {
-            Ok::<_, anyhow::Error>(service_fn(move |req| {
-                handle_request::<GraphQLRequest>(req, state.app_ctx.clone())
-            }))
-        }
-    });
-
-    let make_svc_batch_req = make_service_fn(|_conn| {
-        let state = Arc::clone(&sc);
-        async move {
-            Ok::<_, anyhow::Error>(service_fn(move |req| {
-                handle_request::<GraphQLBatchRequest>(req, state.app_ctx.clone())
-            }))
-        }
-    });
-
-    let builder = Server::builder(acceptor).http2_only(true);
+    let listener = TcpListener::bind(&addr).await?;
+    let issuers = Arc::new(issuers(&client_ca)?);
+    let acceptor = TlsAcceptor::from(Arc::new(tls_config(cert, key, client_ca)?));
 
     super::log_launch(sc.as_ref());
 
@@ -54,14 +145,93 @@ pub async fn start_http_2(
             .or(Err(anyhow::anyhow!("Failed to send message")))?;
     }
 
-    let server: std::prelude::v1::Result<(), hyper::Error> =
-        if sc.blueprint.server.enable_batch_requests {
-            builder.serve(make_svc_batch_req).await
-        } else {
-            builder.serve(make_svc_single_req).await
+    loop {
+        let (stream, remote_addr) = match listener.accept().await {
+            Ok(connection) => connection,
+            Err(e) => {
+                tracing::warn!("Failed to accept a connection: {}", e);
+                tokio::time::sleep(ACCEPT_BACKOFF).await;
+                continue;
+            }
         };
+        let acceptor = acceptor.clone();
+        let issuers = issuers.clone();
+        let state = Arc::clone(&sc);
+
+        // the handshake runs on its own task so that a slow client doesn't hold
+        // the other connections
+        tokio::spawn(async move {
+            let stream =
+                match tokio::time::timeout(HANDSHAKE_TIMEOUT, acceptor.accept(stream)).await {
+                    Ok(Ok(stream)) => stream,
+                    Ok(Err(e)) => {
+                        tracing::debug!("TLS handshake with {} failed: {}", remote_addr, e);
+                        return;
+                    }
+                    Err(_) => {
+                        tracing::debug!("TLS handshake with {} timed out", remote_addr);
+                        return;
+                    }
+                };
 
-    let result = server.map_err(Errata::from);
+            let client_ip = ClientIp(remote_addr.ip());
+            let client_cert = stream
+                .get_ref()
+                .1
+                .peer_certificates()
+                .and_then(|chain| client_certificate(chain, &issuers));
 
-    Ok(result?)
+            let service = service_fn(move |mut req| {
+                req.extensions_mut().insert(client_ip);
+                if let Some(client_cert) = client_cert.clone() {
+                    req.extensions_mut().insert(client_cert);
+                }
+                let app_ctx = state.app_ctx();
+                async move {
+                    let compression = app_ctx.blueprint.server.compression.clone();
+                    if app_ctx.blueprint.server.enable_batch_requests {
+                        compression::handle(req, compression.as_ref(), |req| {
+                            handle_request::<GraphQLBatchRequest>(req, app_ctx)
+                        })
+                        .await
+                    } else {
+                        compression::handle(req, compression.as_ref(), |req| {
+                            handle_request::<GraphQLRequest>(req, app_ctx)
+                        })
+                        .await
+                    }
+                }
+            });
+
+            if let Err(e) = Http::new()
+                .http2_only(true)
+                .serve_connection(stream, service)
+                .await
+            {
+                tracing::debug!("Connection with {} failed: {}", remote_addr, e);
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_client_certificate() {
+        let pem = include_str!("../../../tests/server/config/example.crt");
+        let der = rustls_pemfile::certs(&mut pem.as_bytes())
+            .unwrap()
+            .remove(0);
+
+        let actual = client_certificate(&[CertificateDer::from(der)], &[]).unwrap();
+        let expected = ClientCertificate {
+            subject: "C=US, ST=Some-State, O=Internet Widgits Pty Ltd, CN=localhost".to_string(),
+            san: vec![],
+            issuers: vec![],
+        };
+
+        assert_eq!(actual, expected);
+    }
 }
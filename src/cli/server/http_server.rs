@@ -7,6 +7,7 @@ use tokio::sync::oneshot::{self};
 use super::http_1::start_http_1;
 use super::http_2::start_http_2;
 use super::server_config::ServerConfig;
+use super::watcher::ConfigWatcher;
 use crate::cli::telemetry::init_opentelemetry;
 use crate::core::blueprint::{Blueprint, Http};
 use crate::core::config::ConfigModule;
@@ -15,11 +16,18 @@ use crate::core::Errata;
 pub struct Server {
     config_module: ConfigModule,
     server_up_sender: Option<oneshot::Sender<()>>,
+    watcher: Option<ConfigWatcher>,
 }
 
 impl Server {
     pub fn new(config_module: ConfigModule) -> Self {
-        Self { config_module, server_up_sender: None }
+        Self { config_module, server_up_sender: None, watcher: None }
+    }
+
+    /// Reloads the server whenever the configuration files change
+    pub fn watch(mut self, watcher: ConfigWatcher) -> Self {
+        self.watcher = Some(watcher);
+        self
     }
 
     pub fn server_up_receiver(&mut self) -> oneshot::Receiver<()> {
@@ -36,11 +44,18 @@ impl Server {
         let endpoints = self.config_module.extensions().endpoint_set.clone();
         let server_config = Arc::new(ServerConfig::new(blueprint.clone(), endpoints).await?);
 
-        init_opentelemetry(blueprint.telemetry.clone(), &server_config.app_ctx.runtime)?;
+        init_opentelemetry(
+            blueprint.telemetry.clone(),
+            &server_config.app_ctx().runtime,
+        )?;
+
+        if let Some(watcher) = self.watcher {
+            tokio::spawn(watcher.run(server_config.clone()));
+        }
 
         match blueprint.server.http.clone() {
-            Http::HTTP2 { cert, key } => {
-                start_http_2(server_config, cert, key, self.server_up_sender).await
+            Http::HTTP2 { cert, key, client_ca } => {
+                start_http_2(server_config, cert, key, client_ca, self.server_up_sender).await
             }
             Http::HTTP1 => start_http_1(server_config, self.server_up_sender).await,
         }
@@ -1,8 +1,11 @@
+pub mod compression;
 pub mod http_1;
 pub mod http_2;
 pub mod http_server;
 pub mod playground;
 pub mod server_config;
+pub mod watcher;
+pub mod websocket;
 
 pub use http_server::Server;
 
@@ -16,7 +19,8 @@ fn log_launch(sc: &ServerConfig) {
         sc.http_version()
     );
 
-    let gql_slug = sc.app_ctx.blueprint.server.routes.graphql();
+    let app_ctx = sc.app_ctx();
+    let gql_slug = app_ctx.blueprint.server.routes.graphql();
 
     let graphiql_url = sc.graphiql_url() + gql_slug;
     let url = playground::build_url(&graphiql_url);
@@ -1,18 +1,21 @@
 use std::net::{IpAddr, Ipv4Addr, SocketAddr};
-use std::sync::Arc;
+use std::sync::{Arc, RwLock};
 
 use async_graphql_extension_apollo_tracing::ApolloTracing;
 
 use crate::cli::runtime::init;
 use crate::core::app_context::AppContext;
 use crate::core::blueprint::telemetry::TelemetryExporter;
-use crate::core::blueprint::{Blueprint, Http};
+use crate::core::blueprint::{self, Blueprint, Http};
 use crate::core::rest::{EndpointSet, Unchecked};
+use crate::core::runtime::TargetRuntime;
 use crate::core::schema_extension::SchemaExtension;
 
 pub struct ServerConfig {
-    pub blueprint: Blueprint,
-    pub app_ctx: Arc<AppContext>,
+    /// Settings of the server at startup, that the listener is bound with.
+    /// The rest of the blueprint is read from the current AppContext.
+    listener: blueprint::Server,
+    app_ctx: RwLock<Arc<AppContext>>,
 }
 
 impl ServerConfig {
@@ -20,8 +23,16 @@ impl ServerConfig {
         blueprint: Blueprint,
         endpoints: EndpointSet<Unchecked>,
     ) -> anyhow::Result<Self> {
-        let mut rt = init(&blueprint);
+        let app_ctx = Self::init_app_ctx(&blueprint, endpoints, init(&blueprint)).await?;
+
+        Ok(Self { listener: blueprint.server, app_ctx: RwLock::new(app_ctx) })
+    }
 
+    async fn init_app_ctx(
+        blueprint: &Blueprint,
+        endpoints: EndpointSet<Unchecked>,
+        mut rt: TargetRuntime,
+    ) -> anyhow::Result<Arc<AppContext>> {
         let mut extensions = vec![];
 
         if let Some(TelemetryExporter::Apollo(apollo)) = blueprint.telemetry.export.as_ref() {
@@ -36,19 +47,64 @@ impl ServerConfig {
         }
         rt.add_extensions(extensions);
 
-        let endpoints = endpoints.into_checked(&blueprint, rt.clone()).await?;
-        let app_context = Arc::new(AppContext::new(blueprint.clone(), rt, endpoints));
+        let endpoints = endpoints.into_checked(blueprint, rt.clone()).await?;
+
+        Ok(Arc::new(AppContext::new(blueprint.clone(), rt, endpoints)))
+    }
 
-        Ok(Self { app_ctx: app_context, blueprint })
+    /// The AppContext that incoming requests are currently served with
+    pub fn app_ctx(&self) -> Arc<AppContext> {
+        self.app_ctx.read().unwrap().clone()
+    }
+
+    /// Builds a new AppContext from the blueprint and swaps it with the current
+    /// one. Requests that are already being executed hold on to the previous
+    /// AppContext until they complete. The state of the runtime that doesn't
+    /// depend on the config, i.e. the persisted queries and the state of the
+    /// rate limits, is carried over to the new AppContext.
+    pub async fn reload(
+        &self,
+        blueprint: Blueprint,
+        endpoints: EndpointSet<Unchecked>,
+    ) -> anyhow::Result<()> {
+        let previous = self.app_ctx().runtime.clone();
+        let mut rt = init(&blueprint);
+        rt.persisted_queries = previous.persisted_queries;
+        rt.rate_limiter = previous.rate_limiter;
+
+        let app_ctx = Self::init_app_ctx(&blueprint, endpoints, rt).await?;
+        *self.app_ctx.write().unwrap() = app_ctx;
+
+        Ok(())
+    }
+
+    /// Tells if the settings of the server differ from the ones the listener
+    /// was bound with, which can't be changed without a restart
+    pub fn requires_restart(&self, server: &blueprint::Server) -> bool {
+        let tls = |server: &blueprint::Server| match &server.http {
+            Http::HTTP1 => None,
+            Http::HTTP2 { cert, key, client_ca } => {
+                Some((cert.clone(), key.clone(), client_ca.clone()))
+            }
+        };
+
+        server.hostname != self.listener.hostname
+            || server.port != self.listener.port
+            || server.pipeline_flush != self.listener.pipeline_flush
+            || tls(server) != tls(&self.listener)
     }
 
     pub fn addr(&self) -> SocketAddr {
-        (self.blueprint.server.hostname, self.blueprint.server.port).into()
+        (self.listener.hostname, self.listener.port).into()
+    }
+
+    pub fn pipeline_flush(&self) -> bool {
+        self.listener.pipeline_flush
     }
 
     pub fn http_version(&self) -> String {
-        match self.blueprint.server.http {
-            Http::HTTP2 { cert: _, key: _ } => "HTTP/2".to_string(),
+        match self.listener.http {
+            Http::HTTP2 { .. } => "HTTP/2".to_string(),
             _ => "HTTP/1.1".to_string(),
         }
     }
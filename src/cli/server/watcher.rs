@@ -0,0 +1,122 @@
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+
+use super::server_config::ServerConfig;
+use crate::core::blueprint::Blueprint;
+use crate::core::config::reader::ConfigReader;
+use crate::core::runtime::TargetRuntime;
+use crate::core::Errata;
+
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Modification time and size of a watched file. `None` if the file can't be
+/// read, so that deleting and recreating a file is noticed as well.
+type FileState = Option<(SystemTime, u64)>;
+
+/// Watches the local files that make up the configuration, including the ones
+/// reached through `@link`, and reloads the server whenever any of them
+/// changes. Invalid configurations are reported and the server keeps running
+/// with the last valid one.
+pub struct ConfigWatcher {
+    file_paths: Vec<String>,
+    files: Vec<String>,
+    runtime: TargetRuntime,
+}
+
+impl ConfigWatcher {
+    pub fn new(file_paths: Vec<String>, files: Vec<String>, runtime: TargetRuntime) -> Self {
+        Self { file_paths, files, runtime }
+    }
+
+    async fn state(files: &[String]) -> Vec<FileState> {
+        let mut state = Vec::with_capacity(files.len());
+        for file in files {
+            let metadata = tokio::fs::metadata(file).await.ok();
+            state.push(
+                metadata.and_then(|metadata| Some((metadata.modified().ok()?, metadata.len()))),
+            );
+        }
+        state
+    }
+
+    pub async fn run(mut self, server_config: Arc<ServerConfig>) {
+        tracing::info!("👀 Watching {} file(s) for changes", self.files.len());
+
+        let mut state = Self::state(&self.files).await;
+        let mut interval = tokio::time::interval(POLL_INTERVAL);
+
+        loop {
+            interval.tick().await;
+
+            let current = Self::state(&self.files).await;
+            if current == state {
+                continue;
+            }
+
+            match self.reload(&server_config).await {
+                Ok(files) => {
+                    tracing::info!("🔄 Configuration reloaded");
+                    self.files = files;
+                    state = Self::state(&self.files).await;
+                }
+                Err(err) => {
+                    let errata = Errata::from(err);
+                    tracing::error!(
+                        "Configuration was not reloaded, the previous one is still in use\n{}",
+                        errata.color(true)
+                    );
+                    state = current;
+                }
+            }
+        }
+    }
+
+    /// Reads the configuration again and swaps the AppContext of the server.
+    /// Returns the files that the new configuration is made of.
+    async fn reload(&self, server_config: &ServerConfig) -> anyhow::Result<Vec<String>> {
+        // A new reader is created every time so that nothing is served from
+        // the cache of the previous read
+        let reader = ConfigReader::init(self.runtime.clone());
+        let config_module = reader.read_all(&self.file_paths).await?;
+        let blueprint = Blueprint::try_from(&config_module).map_err(Errata::from)?;
+
+        if server_config.requires_restart(&blueprint.server) {
+            tracing::warn!(
+                "Changes to the hostname, the port, the HTTP version, the certificates, their \
+                 key or the pipelining require a restart"
+            );
+        }
+
+        let endpoints = config_module.extensions().endpoint_set.clone();
+        server_config.reload(blueprint, endpoints).await?;
+
+        Ok(reader.local_files())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_state_changes_with_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("config.graphql");
+        let files = vec![path.to_string_lossy().to_string()];
+
+        let missing = ConfigWatcher::state(&files).await;
+        assert_eq!(missing, vec![None]);
+
+        tokio::fs::write(&path, "schema { query: Query }")
+            .await
+            .unwrap();
+        let created = ConfigWatcher::state(&files).await;
+        assert!(created[0].is_some());
+
+        tokio::fs::write(&path, "schema { query: Query mutation: Mutation }")
+            .await
+            .unwrap();
+        let modified = ConfigWatcher::state(&files).await;
+        assert_ne!(created, modified);
+    }
+}
@@ -0,0 +1,117 @@
+use std::net::IpAddr;
+use std::sync::Arc;
+
+use futures_util::future::ready;
+use futures_util::{SinkExt, StreamExt};
+use hyper::header::{self, HeaderValue};
+use hyper::{Body, Method, Request, Response, StatusCode};
+use tokio_tungstenite::tungstenite::handshake::derive_accept_key;
+use tokio_tungstenite::tungstenite::protocol::frame::coding::CloseCode;
+use tokio_tungstenite::tungstenite::protocol::{CloseFrame, Role};
+use tokio_tungstenite::tungstenite::Message;
+use tokio_tungstenite::WebSocketStream;
+
+use crate::core::app_context::AppContext;
+use crate::core::http::{ClientIp, GraphQLWs, WsMessage, GRAPHQL_TRANSPORT_WS};
+
+/// Checks if the request is a websocket upgrade on the GraphQL route.
+pub fn is_upgrade_request(req: &Request<Body>, app_ctx: &AppContext) -> bool {
+    req.method() == Method::GET
+        && req.uri().path() == app_ctx.blueprint.server.routes.graphql()
+        && req
+            .headers()
+            .get(header::UPGRADE)
+            .and_then(|value| value.to_str().ok())
+            .is_some_and(|value| value.eq_ignore_ascii_case("websocket"))
+}
+
+fn bad_request(message: &'static str) -> anyhow::Result<Response<Body>> {
+    Ok(Response::builder()
+        .status(StatusCode::BAD_REQUEST)
+        .body(Body::from(message))?)
+}
+
+/// Completes the websocket handshake and serves subscriptions over the
+/// upgraded connection using the graphql-transport-ws protocol.
+pub fn upgrade(mut req: Request<Body>, app_ctx: Arc<AppContext>) -> anyhow::Result<Response<Body>> {
+    let supports_protocol = req
+        .headers()
+        .get_all(header::SEC_WEBSOCKET_PROTOCOL)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .any(|protocol| protocol.trim() == GRAPHQL_TRANSPORT_WS);
+
+    if !supports_protocol {
+        return bad_request("Unsupported websocket sub-protocol");
+    }
+
+    let Some(key) = req.headers().get(header::SEC_WEBSOCKET_KEY) else {
+        return bad_request("Missing Sec-WebSocket-Key header");
+    };
+    let accept = derive_accept_key(key.as_bytes());
+    let headers = req.headers().clone();
+    let client_ip = req.extensions().get::<ClientIp>().map(|ip| ip.0);
+
+    tokio::spawn(async move {
+        match hyper::upgrade::on(&mut req).await {
+            Ok(upgraded) => {
+                let socket = WebSocketStream::from_raw_socket(upgraded, Role::Server, None).await;
+                serve(socket, app_ctx, headers, client_ip).await;
+            }
+            Err(err) => tracing::error!("Failed to upgrade the connection: {}", err),
+        }
+    });
+
+    Ok(Response::builder()
+        .status(StatusCode::SWITCHING_PROTOCOLS)
+        .header(header::CONNECTION, HeaderValue::from_static("Upgrade"))
+        .header(header::UPGRADE, HeaderValue::from_static("websocket"))
+        .header(header::SEC_WEBSOCKET_ACCEPT, accept)
+        .header(
+            header::SEC_WEBSOCKET_PROTOCOL,
+            HeaderValue::from_static(GRAPHQL_TRANSPORT_WS),
+        )
+        .body(Body::empty())?)
+}
+
+async fn serve(
+    socket: WebSocketStream<hyper::upgrade::Upgraded>,
+    app_ctx: Arc<AppContext>,
+    headers: hyper::HeaderMap,
+    client_ip: Option<IpAddr>,
+) {
+    let (mut sink, stream) = socket.split();
+
+    let incoming = stream
+        .take_while(|message| ready(matches!(message, Ok(message) if !message.is_close())))
+        .filter_map(|message| {
+            ready(match message {
+                Ok(Message::Text(text)) => Some(text),
+                _ => None,
+            })
+        });
+
+    let mut outgoing = Box::pin(
+        GraphQLWs::new(app_ctx, headers, incoming)
+            .client_ip(client_ip)
+            .into_stream(),
+    );
+
+    while let Some(message) = outgoing.next().await {
+        let message = match message {
+            WsMessage::Text(text) => Message::Text(text),
+            WsMessage::Close(code, reason) => Message::Close(Some(CloseFrame {
+                code: CloseCode::from(code),
+                reason: reason.into(),
+            })),
+        };
+
+        if let Err(err) = sink.send(message).await {
+            tracing::debug!("Websocket connection closed: {}", err);
+            break;
+        }
+    }
+
+    let _ = sink.close().await;
+}
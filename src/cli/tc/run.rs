@@ -41,10 +41,11 @@ fn get_runtime_and_config_reader(verify_ssl: bool) -> (TargetRuntime, ConfigRead
 
 async fn run_command(cli: Cli) -> Result<()> {
     match cli.command {
-        Command::Start { file_paths, verify_ssl } => {
+        Command::Start { file_paths, verify_ssl, watch } => {
             let (runtime, config_reader) = get_runtime_and_config_reader(verify_ssl);
-            validate_rc_config_files(runtime, &file_paths).await;
-            start::start_command(file_paths, &config_reader).await?;
+            validate_rc_config_files(runtime.clone(), &file_paths).await;
+            let watch = watch.then_some(runtime);
+            start::start_command(file_paths, &config_reader, watch).await?;
         }
         Command::Check { file_paths, n_plus_one_queries, schema, format, verify_ssl } => {
             let (runtime, config_reader) = get_runtime_and_config_reader(verify_ssl);
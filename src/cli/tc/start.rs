@@ -2,17 +2,24 @@ use anyhow::Result;
 
 use super::helpers::log_endpoint_set;
 use crate::cli::fmt::Fmt;
+use crate::cli::server::watcher::ConfigWatcher;
 use crate::cli::server::Server;
 use crate::core::config::reader::ConfigReader;
+use crate::core::runtime::TargetRuntime;
 
 pub(super) async fn start_command(
     file_paths: Vec<String>,
     config_reader: &ConfigReader,
+    watch: Option<TargetRuntime>,
 ) -> Result<()> {
     let config_module = config_reader.read_all(&file_paths).await?;
     log_endpoint_set(&config_module.extensions().endpoint_set);
     Fmt::log_n_plus_one(false, config_module.config());
-    let server = Server::new(config_module);
+    let mut server = Server::new(config_module);
+    if let Some(runtime) = watch {
+        let files = config_reader.local_files();
+        server = server.watch(ConfigWatcher::new(file_paths, files, runtime));
+    }
     server.fork_start().await?;
     Ok(())
 }
@@ -14,7 +14,7 @@ use crate::core::grpc::data_loader::GrpcDataLoader;
 use crate::core::http::{DataLoaderRequest, HttpDataLoader};
 use crate::core::ir::model::{DataLoaderId, IoId, IO, IR};
 use crate::core::ir::Error;
-use crate::core::jit::{OPHash, OperationPlan};
+use crate::core::jit::{OPHash, OperationPlan, ResponseCache};
 use crate::core::rest::{Checked, EndpointSet};
 use crate::core::runtime::TargetRuntime;
 
@@ -28,8 +28,10 @@ pub struct AppContext {
     pub endpoints: EndpointSet<Checked>,
     pub dedupe_handler: Arc<DedupeResult<IoId, ConstValue, Error>>,
     pub dedupe_operation_handler: DedupeResult<OperationId, AnyResponse<Vec<u8>>, Error>,
+    pub dedupe_revalidation_handler: Arc<DedupeResult<IoId, ConstValue, Error>>,
     pub operation_plans: DashMap<OPHash, OperationPlan<async_graphql_value::Value>>,
     pub const_execution_cache: DashMap<OPHash, AnyResponse<Vec<u8>>>,
+    pub response_cache: ResponseCache,
 }
 
 impl AppContext {
@@ -55,14 +57,20 @@ impl AppContext {
                                     http_filter,
                                     is_list,
                                     dedupe,
+                                    retry,
+                                    timeout,
+                                    tls,
                                     ..
                                 } => {
                                     let is_list = *is_list;
                                     let dedupe = *dedupe;
                                     let data_loader = HttpDataLoader::new(
-                                        runtime.clone(),
+                                        runtime.with_tls(tls.as_ref()).into_owned(),
                                         group_by.clone(),
                                         is_list,
+                                        retry.clone(),
+                                        req_template.encoding.clone(),
+                                        req_template.protobuf.clone(),
                                     )
                                     .to_data_loader(upstream_batch.clone().unwrap_or_default());
 
@@ -73,6 +81,9 @@ impl AppContext {
                                         http_filter: http_filter.clone(),
                                         is_list,
                                         dedupe,
+                                        retry: retry.clone(),
+                                        timeout: *timeout,
+                                        tls: tls.clone(),
                                     }));
 
                                     http_data_loaders.push(data_loader);
@@ -80,13 +91,22 @@ impl AppContext {
                                     result
                                 }
 
-                                IO::GraphQL { req_template, field_name, batch, dedupe, .. } => {
+                                IO::GraphQL {
+                                    req_template,
+                                    field_name,
+                                    batch,
+                                    dedupe,
+                                    retry,
+                                    timeout,
+                                    ..
+                                } => {
                                     let dedupe = *dedupe;
-                                    let graphql_data_loader =
-                                        GraphqlDataLoader::new(runtime.clone(), *batch)
-                                            .into_data_loader(
-                                                upstream_batch.clone().unwrap_or_default(),
-                                            );
+                                    let graphql_data_loader = GraphqlDataLoader::new(
+                                        runtime.clone(),
+                                        *batch,
+                                        retry.clone(),
+                                    )
+                                    .into_data_loader(upstream_batch.clone().unwrap_or_default());
 
                                     let result = Some(IR::IO(IO::GraphQL {
                                         req_template: req_template.clone(),
@@ -94,6 +114,8 @@ impl AppContext {
                                         batch: *batch,
                                         dl_id: Some(DataLoaderId::new(gql_data_loaders.len())),
                                         dedupe,
+                                        retry: retry.clone(),
+                                        timeout: *timeout,
                                     }));
 
                                     gql_data_loaders.push(graphql_data_loader);
@@ -101,12 +123,21 @@ impl AppContext {
                                     result
                                 }
 
-                                IO::Grpc { req_template, group_by, dedupe, .. } => {
+                                IO::Grpc {
+                                    req_template,
+                                    group_by,
+                                    dedupe,
+                                    retry,
+                                    timeout,
+                                    tls,
+                                    ..
+                                } => {
                                     let dedupe = *dedupe;
                                     let data_loader = GrpcDataLoader {
-                                        runtime: runtime.clone(),
+                                        runtime: runtime.with_tls(tls.as_ref()).into_owned(),
                                         operation: req_template.operation.clone(),
                                         group_by: group_by.clone(),
+                                        retry: retry.clone(),
                                     };
                                     let data_loader = data_loader.into_data_loader(
                                         upstream_batch.clone().unwrap_or_default(),
@@ -117,6 +148,9 @@ impl AppContext {
                                         group_by: group_by.clone(),
                                         dl_id: Some(DataLoaderId::new(grpc_data_loaders.len())),
                                         dedupe,
+                                        retry: retry.clone(),
+                                        timeout: *timeout,
+                                        tls: tls.clone(),
                                     }));
 
                                     grpc_data_loaders.push(data_loader);
@@ -126,6 +160,7 @@ impl AppContext {
                                 IO::Js { name: method } => {
                                     Some(IR::IO(IO::Js { name: method.clone() }))
                                 }
+                                IO::Sse { .. } => None,
                             },
                             _ => None,
                         })
@@ -148,8 +183,10 @@ impl AppContext {
 
             dedupe_handler: Arc::new(DedupeResult::new(false)),
             dedupe_operation_handler: DedupeResult::new(false),
+            dedupe_revalidation_handler: Arc::new(DedupeResult::new(false)),
             operation_plans: DashMap::new(),
             const_execution_cache: DashMap::default(),
+            response_cache: ResponseCache::default(),
         }
     }
 
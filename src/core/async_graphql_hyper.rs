@@ -1,17 +1,23 @@
 use std::any::Any;
+use std::collections::{BTreeMap, HashMap};
 use std::hash::{Hash, Hasher};
 
-use anyhow::Result;
+use anyhow::{anyhow, Result};
 use async_graphql::parser::types::{ExecutableDocument, OperationType};
-use async_graphql::{BatchResponse, Executor, Value};
+use async_graphql::{BatchResponse, Executor, ServerError, Value};
+use futures_util::stream;
 use http::header::{HeaderMap, HeaderValue, CACHE_CONTROL, CONTENT_TYPE};
 use http::{Response, StatusCode};
+use hyper::body::HttpBody;
 use hyper::Body;
 use once_cell::sync::Lazy;
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
 use tailcall_hasher::TailcallHasher;
 
+use super::http::multipart::{set_path, Upload};
 use super::jit::{BatchResponse as JITBatchResponse, JITExecutor};
+use super::{persisted_query, PersistedQueryCache};
 
 #[derive(PartialEq, Eq, Clone, Hash, Debug)]
 pub struct OperationId(u64);
@@ -27,6 +33,18 @@ pub trait GraphQLRequestLike: Hash + Send {
 
     fn parse_query(&mut self) -> Option<&ExecutableDocument>;
 
+    /// Takes out the request when it holds a single operation, so that it can
+    /// be executed on its own.
+    fn into_single(self) -> Result<async_graphql::Request, Self>;
+
+    /// Resolves the documents of the requests made with Automatic Persisted
+    /// Queries. Fails with the response that should be sent back instead of
+    /// executing the request.
+    async fn resolve_persisted_query(
+        &mut self,
+        cache: &PersistedQueryCache,
+    ) -> Result<(), GraphQLResponse>;
+
     fn is_query(&mut self) -> bool {
         self.parse_query()
             .map(|a| {
@@ -90,8 +108,57 @@ impl GraphQLRequestLike for GraphQLBatchRequest {
         GraphQLResponse(executor.execute_batch(self.0).await)
     }
 
+    /// Parses the document of a single request, batches aren't parsed
     fn parse_query(&mut self) -> Option<&ExecutableDocument> {
-        None
+        match &mut self.0 {
+            async_graphql::BatchRequest::Single(request) => request.parsed_query().ok(),
+            async_graphql::BatchRequest::Batch(_) => None,
+        }
+    }
+
+    fn into_single(self) -> Result<async_graphql::Request, Self> {
+        match self.0 {
+            async_graphql::BatchRequest::Single(request) => Ok(request),
+            batch => Err(Self(batch)),
+        }
+    }
+
+    async fn resolve_persisted_query(
+        &mut self,
+        cache: &PersistedQueryCache,
+    ) -> Result<(), GraphQLResponse> {
+        let mut errors = Vec::new();
+        for request in self.0.iter_mut() {
+            errors.push(persisted_query::resolve(request, cache).await.err());
+        }
+
+        if errors.iter().all(Option::is_none) {
+            return Ok(());
+        }
+
+        // The batch is executed only if every document in it could be resolved
+        let responses = errors
+            .into_iter()
+            .map(|error| {
+                let error = match error {
+                    Some(error) => error.into(),
+                    None => ServerError::new(
+                        "Not executed because of another operation in the batch",
+                        None,
+                    ),
+                };
+                async_graphql::Response::from_errors(vec![error])
+            })
+            .collect::<Vec<_>>();
+
+        Err(match self.0 {
+            async_graphql::BatchRequest::Single(_) => {
+                GraphQLResponse(BatchResponse::Single(responses.into_iter().next().unwrap()))
+            }
+            async_graphql::BatchRequest::Batch(_) => {
+                GraphQLResponse(BatchResponse::Batch(responses))
+            }
+        })
     }
 }
 
@@ -132,6 +199,19 @@ impl GraphQLRequestLike for GraphQLRequest {
     fn parse_query(&mut self) -> Option<&ExecutableDocument> {
         self.0.parsed_query().ok()
     }
+
+    fn into_single(self) -> Result<async_graphql::Request, Self> {
+        Ok(self.0)
+    }
+
+    async fn resolve_persisted_query(
+        &mut self,
+        cache: &PersistedQueryCache,
+    ) -> Result<(), GraphQLResponse> {
+        persisted_query::resolve(&mut self.0, cache)
+            .await
+            .map_err(|error| async_graphql::Response::from_errors(vec![error.into()]).into())
+    }
 }
 
 // TODO: drop this type since we can use jit::response?
@@ -184,6 +264,78 @@ impl GraphQLQuery {
     }
 }
 
+/// Returns the boundary of requests sent with the
+/// [GraphQL multipart request spec](https://github.com/jaydenseric/graphql-multipart-request-spec).
+pub fn multipart_boundary(headers: &HeaderMap) -> Option<String> {
+    headers
+        .get(CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| multer::parse_boundary(value).ok())
+}
+
+/// A multipart request larger than the maximum size of the uploads
+#[derive(Debug, thiserror::Error)]
+#[error("The multipart request is larger than {0} bytes")]
+pub struct UploadTooLarge(pub u64);
+
+/// Parses a request sent with the GraphQL multipart request spec. The files are
+/// set as [Upload] values at the paths of the variables listed in its `map`.
+/// The body is read as it's parsed, and fails with [UploadTooLarge] past
+/// `max_size` bytes.
+pub async fn parse_multipart_request<T: DeserializeOwned>(
+    boundary: String,
+    body: Body,
+    max_size: u64,
+) -> Result<T> {
+    let body = stream::unfold(body, |mut body| async move {
+        body.data().await.map(|chunk| (chunk, body))
+    });
+    let constraints =
+        multer::Constraints::new().size_limit(multer::SizeLimit::new().whole_stream(max_size));
+    let mut multipart = multer::Multipart::with_constraints(body, boundary, constraints);
+    let too_large = |err: multer::Error| match err {
+        multer::Error::StreamSizeExceeded { .. } => anyhow::Error::from(UploadTooLarge(max_size)),
+        err => anyhow::Error::from(err),
+    };
+    let mut operations = None;
+    let mut map = BTreeMap::<String, Vec<String>>::new();
+    let mut files = HashMap::new();
+
+    while let Some(field) = multipart.next_field().await.map_err(too_large)? {
+        match field.name().map(str::to_string) {
+            Some(name) if name == "operations" => {
+                operations = Some(serde_json::from_slice::<serde_json::Value>(
+                    &field.bytes().await.map_err(too_large)?,
+                )?);
+            }
+            Some(name) if name == "map" => {
+                map = serde_json::from_slice(&field.bytes().await.map_err(too_large)?)?;
+            }
+            Some(name) => {
+                let filename = field.file_name().unwrap_or_default().to_string();
+                let content_type = field.content_type().map(|mime| mime.to_string());
+                let content = field.bytes().await.map_err(too_large)?;
+                files.insert(name, Upload::new(filename, content_type, &content));
+            }
+            None => {}
+        }
+    }
+
+    let mut operations =
+        operations.ok_or_else(|| anyhow!("Missing `operations` in the multipart request"))?;
+    for (name, paths) in map {
+        let upload = files
+            .remove(&name)
+            .ok_or_else(|| anyhow!("Missing file `{}` in the multipart request", name))?;
+        let upload = serde_json::to_value(upload)?;
+        for path in paths {
+            set_path(&mut operations, &path, upload.clone())?;
+        }
+    }
+
+    Ok(serde_json::from_value(operations)?)
+}
+
 static APPLICATION_JSON: Lazy<HeaderValue> =
     Lazy::new(|| HeaderValue::from_static("application/json"));
 
@@ -535,4 +687,37 @@ mod tests {
             Some("no-cache, private".to_string())
         );
     }
+
+    fn multipart_body(file: &str) -> Body {
+        let operations = r#"{"query": "mutation ($file: Upload!) { upload(file: $file) }", "variables": {"file": null}}"#;
+        Body::from(format!(
+            "--X\r\nContent-Disposition: form-data; name=\"operations\"\r\n\r\n{}\r\n\
+             --X\r\nContent-Disposition: form-data; name=\"map\"\r\n\r\n{{\"0\": [\"variables.file\"]}}\r\n\
+             --X\r\nContent-Disposition: form-data; name=\"0\"; filename=\"a.txt\"\r\n\r\n{}\r\n--X--\r\n",
+            operations, file
+        ))
+    }
+
+    #[tokio::test]
+    async fn test_parse_multipart_request() {
+        let request: serde_json::Value =
+            parse_multipart_request("X".to_string(), multipart_body("hello"), 1024)
+                .await
+                .unwrap();
+
+        assert_eq!(request["variables"]["file"]["filename"], "a.txt");
+    }
+
+    #[tokio::test]
+    async fn test_parse_multipart_request_too_large() {
+        let error = parse_multipart_request::<serde_json::Value>(
+            "X".to_string(),
+            multipart_body(&"a".repeat(2048)),
+            1024,
+        )
+        .await
+        .unwrap_err();
+
+        assert!(error.is::<UploadTooLarge>());
+    }
 }
@@ -0,0 +1,173 @@
+use serde_json::json;
+use sha2::{Digest, Sha256};
+
+use super::error::Error;
+use super::verification::Verification;
+use super::verify::Verify;
+use crate::core::blueprint;
+use crate::core::http::RequestContext;
+
+pub struct ApiKeyVerifier {
+    options: blueprint::ApiKeys,
+}
+
+impl ApiKeyVerifier {
+    pub fn new(options: blueprint::ApiKeys) -> Self {
+        Self { options }
+    }
+
+    /// Reads the key from the header, or else from the query parameter
+    fn resolve_key(&self, req_ctx: &RequestContext) -> Result<Option<String>, Error> {
+        if let Some(value) = self
+            .options
+            .header
+            .as_ref()
+            .and_then(|header| req_ctx.auth_headers.get(header.as_str()))
+        {
+            let key = value.to_str().map_err(|_| Error::Invalid)?;
+            return Ok(Some(key.to_owned()));
+        }
+
+        let key = self.options.query.as_ref().and_then(|query| {
+            let params = req_ctx.query.as_deref().unwrap_or_default();
+            url::form_urlencoded::parse(params.as_bytes())
+                .find(|(name, _)| name == query)
+                .map(|(_, key)| key.into_owned())
+        });
+
+        Ok(key)
+    }
+}
+
+#[async_trait::async_trait]
+impl Verify for ApiKeyVerifier {
+    /// Verify the key of the client against the hashes of the keys. On
+    /// success, the name of the client and the scopes of its key are made
+    /// available to the rest of the request as the `sub` and `scope` claims.
+    async fn verify(&self, req_ctx: &RequestContext) -> Verification {
+        let key = match self.resolve_key(req_ctx) {
+            Ok(Some(key)) => key,
+            Ok(None) => return Verification::fail(Error::Missing),
+            Err(err) => return Verification::fail(err),
+        };
+
+        let hash = format!("{:x}", Sha256::digest(key.as_bytes()));
+        let Some(api_key) = self.options.keys.get(&hash) else {
+            return Verification::fail(Error::Invalid);
+        };
+        if !self
+            .options
+            .scopes
+            .iter()
+            .all(|scope| api_key.scopes.contains(scope))
+        {
+            return Verification::fail(Error::Invalid);
+        }
+
+        *req_ctx.claims.lock().unwrap() = Some(json!({
+            "sub": api_key.name,
+            "scope": api_key.scopes.join(" "),
+        }));
+        Verification::succeed()
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use http::header::HeaderValue;
+
+    use super::*;
+    use crate::core::config::ApiKey;
+
+    // sha256 of `acme-key`
+    const ACME_KEY_HASH: &str = "afacab3575137afa4e00d9cbcafcb14c9ae25f779d964eb0ea5b2c4eb5dfd163";
+
+    impl blueprint::ApiKeys {
+        pub fn test_value() -> Self {
+            let key = ApiKey {
+                name: "acme".to_owned(),
+                hash: ACME_KEY_HASH.to_owned(),
+                scopes: vec!["orders:read".to_owned()],
+            };
+
+            Self {
+                keys: [(ACME_KEY_HASH.to_owned(), key)].into_iter().collect(),
+                header: Some("x-api-key".to_owned()),
+                query: Some("api_key".to_owned()),
+                scopes: vec![],
+            }
+        }
+    }
+
+    pub fn create_api_key_request(key: &str) -> RequestContext {
+        let mut req_ctx = RequestContext::default();
+
+        req_ctx
+            .auth_headers
+            .insert("x-api-key", HeaderValue::from_str(key).unwrap());
+
+        req_ctx
+    }
+
+    #[tokio::test]
+    async fn verify_missing_key() {
+        let verifier = ApiKeyVerifier::new(blueprint::ApiKeys::test_value());
+
+        assert_eq!(
+            verifier.verify(&RequestContext::default()).await,
+            Verification::fail(Error::Missing)
+        );
+    }
+
+    #[tokio::test]
+    async fn verify_header_key() {
+        let verifier = ApiKeyVerifier::new(blueprint::ApiKeys::test_value());
+
+        let req_ctx = create_api_key_request("acme-key");
+        assert_eq!(verifier.verify(&req_ctx).await, Verification::succeed());
+        let claims = req_ctx.claims.lock().unwrap().clone().unwrap();
+        assert_eq!(claims, json!({ "sub": "acme", "scope": "orders:read" }));
+
+        let req_ctx = create_api_key_request("wrong-key");
+        assert_eq!(
+            verifier.verify(&req_ctx).await,
+            Verification::fail(Error::Invalid)
+        );
+    }
+
+    #[tokio::test]
+    async fn verify_query_key() {
+        let verifier = ApiKeyVerifier::new(blueprint::ApiKeys::test_value());
+
+        let req_ctx = RequestContext::default().query(Some("a=1&api_key=acme-key".to_owned()));
+        assert_eq!(verifier.verify(&req_ctx).await, Verification::succeed());
+
+        let req_ctx = RequestContext::default().query(Some("key=acme-key".to_owned()));
+        assert_eq!(
+            verifier.verify(&req_ctx).await,
+            Verification::fail(Error::Missing)
+        );
+    }
+
+    #[tokio::test]
+    async fn verify_scopes() {
+        let verifier = |scopes: &[&str]| {
+            ApiKeyVerifier::new(blueprint::ApiKeys {
+                scopes: scopes.iter().map(|scope| scope.to_string()).collect(),
+                ..blueprint::ApiKeys::test_value()
+            })
+        };
+        let req_ctx = create_api_key_request("acme-key");
+
+        assert_eq!(
+            verifier(&["orders:read"]).verify(&req_ctx).await,
+            Verification::succeed()
+        );
+        assert_eq!(
+            verifier(&["orders:read", "orders:write"])
+                .verify(&req_ctx)
+                .await,
+            Verification::fail(Error::Invalid)
+        );
+    }
+}
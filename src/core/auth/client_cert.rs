@@ -0,0 +1,158 @@
+use regex::Regex;
+use rustls_pki_types::CertificateDer;
+
+use super::error::Error;
+use super::verification::Verification;
+use super::verify::Verify;
+use crate::core::blueprint;
+use crate::core::http::RequestContext;
+
+/// Compiles a pattern that has to match the whole value
+fn to_regex(pattern: &str) -> Regex {
+    Regex::new(&format!("^(?:{})$", pattern))
+        .expect("The patterns are validated when the link is read")
+}
+
+pub struct ClientCertVerifier {
+    certs: Vec<CertificateDer<'static>>,
+    subject: Option<Regex>,
+    san: Option<Regex>,
+}
+
+impl ClientCertVerifier {
+    pub fn new(options: blueprint::ClientCert) -> Self {
+        Self {
+            certs: options.certs,
+            subject: options.subject.as_deref().map(to_regex),
+            san: options.san.as_deref().map(to_regex),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl Verify for ClientCertVerifier {
+    /// Verify the certificate presented by the client. The server verified
+    /// its chain during the TLS handshake against the CAs of all the
+    /// providers, so it has to be issued by one of the CAs of this provider
+    /// and match the patterns.
+    async fn verify(&self, req_ctx: &RequestContext) -> Verification {
+        let Some(cert) = &req_ctx.client_cert else {
+            return Verification::fail(Error::Missing);
+        };
+
+        if !cert
+            .issuers
+            .iter()
+            .any(|issuer| self.certs.contains(issuer))
+        {
+            return Verification::fail(Error::Invalid);
+        }
+
+        let subject_matches = self
+            .subject
+            .as_ref()
+            .map_or(true, |subject| subject.is_match(&cert.subject));
+        let san_matches = self
+            .san
+            .as_ref()
+            .map_or(true, |san| cert.san.iter().any(|name| san.is_match(name)));
+
+        if subject_matches && san_matches {
+            Verification::succeed()
+        } else {
+            Verification::fail(Error::Invalid)
+        }
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use crate::core::http::ClientCertificate;
+
+    /// Stands for the DER of the CA of the provider in the tests
+    pub fn test_ca() -> CertificateDer<'static> {
+        CertificateDer::from(b"orders-ca".to_vec())
+    }
+
+    pub fn create_client_cert_request(subject: &str, san: &[&str]) -> RequestContext {
+        RequestContext::default().client_cert(Some(ClientCertificate {
+            subject: subject.to_owned(),
+            san: san.iter().map(|name| name.to_string()).collect(),
+            issuers: vec![test_ca()],
+        }))
+    }
+
+    fn verifier(subject: Option<&str>, san: Option<&str>) -> ClientCertVerifier {
+        ClientCertVerifier::new(blueprint::ClientCert {
+            certs: vec![test_ca()],
+            subject: subject.map(str::to_owned),
+            san: san.map(str::to_owned),
+        })
+    }
+
+    #[tokio::test]
+    async fn verify_missing_certificate() {
+        let verifier = verifier(None, None);
+        let req_ctx = RequestContext::default();
+
+        assert_eq!(
+            verifier.verify(&req_ctx).await,
+            Verification::fail(Error::Missing)
+        );
+    }
+
+    #[tokio::test]
+    async fn verify_any_certificate() {
+        let verifier = verifier(None, None);
+        let req_ctx = create_client_cert_request("CN=orders", &[]);
+
+        assert_eq!(verifier.verify(&req_ctx).await, Verification::succeed());
+    }
+
+    #[tokio::test]
+    async fn verify_issuer() {
+        let verifier = verifier(None, None);
+        // verified against the CA of another provider
+        let req_ctx = RequestContext::default().client_cert(Some(ClientCertificate {
+            subject: "CN=orders".to_owned(),
+            san: vec![],
+            issuers: vec![CertificateDer::from(b"billing-ca".to_vec())],
+        }));
+
+        assert_eq!(
+            verifier.verify(&req_ctx).await,
+            Verification::fail(Error::Invalid)
+        );
+    }
+
+    #[tokio::test]
+    async fn verify_subject() {
+        let verifier = verifier(Some("CN=orders"), None);
+
+        let req_ctx = create_client_cert_request("CN=orders", &[]);
+        assert_eq!(verifier.verify(&req_ctx).await, Verification::succeed());
+
+        // the pattern has to match the whole subject
+        let req_ctx = create_client_cert_request("CN=orders-evil", &[]);
+        assert_eq!(
+            verifier.verify(&req_ctx).await,
+            Verification::fail(Error::Invalid)
+        );
+    }
+
+    #[tokio::test]
+    async fn verify_san() {
+        let verifier = verifier(None, Some(r".*\.svc\.cluster\.local"));
+
+        let req_ctx =
+            create_client_cert_request("CN=orders", &["orders", "orders.svc.cluster.local"]);
+        assert_eq!(verifier.verify(&req_ctx).await, Verification::succeed());
+
+        let req_ctx = create_client_cert_request("CN=orders", &["orders.example.com"]);
+        assert_eq!(
+            verifier.verify(&req_ctx).await,
+            Verification::fail(Error::Invalid)
+        );
+    }
+}
@@ -1,6 +1,6 @@
 use headers::authorization::Bearer;
 use headers::{Authorization, HeaderMapExt};
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 
 use super::jwks::Jwks;
 use crate::core::auth::error::Error;
@@ -9,17 +9,22 @@ use crate::core::auth::verify::Verify;
 use crate::core::blueprint;
 use crate::core::http::RequestContext;
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Serialize)]
 #[serde(untagged)]
 pub enum OneOrMany<T> {
     One(T),
     Vec(Vec<T>),
 }
 
-#[derive(Debug, Default, Deserialize)]
+#[derive(Debug, Default, Deserialize, Serialize)]
 pub struct JwtClaim {
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub aud: Option<OneOrMany<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub iss: Option<String>,
+    /// The rest of the claims of the token
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
 }
 
 pub struct JwtVerifier {
@@ -46,10 +51,17 @@ impl JwtVerifier {
         Ok(value.map(|token| token.token().to_owned()))
     }
 
-    async fn validate_token(&self, token: &str) -> Verification {
+    async fn validate_token(&self, token: &str, request: &RequestContext) -> Verification {
         Verification::from_result(
             self.decoder.decode(token),
-            |claims| self.validate_claims(&claims),
+            |claims| {
+                let verification = self.validate_claims(&claims);
+                if verification == Verification::succeed() {
+                    // makes the claims available to the rest of the request
+                    *request.claims.lock().unwrap() = serde_json::to_value(claims).ok();
+                }
+                verification
+            },
             |err| Verification::fail(Error::Parse(err.to_string())),
         )
     }
@@ -74,7 +86,7 @@ impl Verify for JwtVerifier {
             return Verification::fail(Error::Missing);
         };
 
-        self.validate_token(&token).await
+        self.validate_token(&token, request).await
     }
 }
 
@@ -209,6 +221,19 @@ pub mod tests {
         assert_eq!(error, Verification::fail(Error::Invalid));
     }
 
+    #[tokio::test]
+    async fn stores_claims_of_valid_token() {
+        let jwt_provider = JwtVerifier::new(blueprint::Jwt::test_value());
+        let request = create_jwt_auth_request(JWT_VALID_TOKEN_WITH_KID);
+
+        let valid = jwt_provider.verify(&request).await;
+
+        assert_eq!(valid, Verification::succeed());
+        let claims = request.claims.lock().unwrap().clone().unwrap();
+        assert_eq!(claims["sub"], "you");
+        assert_eq!(claims["iss"], "me");
+    }
+
     #[tokio::test]
     async fn validate_token_aud() {
         let jwt_options = blueprint::Jwt::test_value();
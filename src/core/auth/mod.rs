@@ -1,5 +1,8 @@
+pub mod api_key;
 pub mod basic;
+pub mod client_cert;
 pub mod error;
+pub mod introspection;
 pub mod jwt;
 mod verification;
 pub mod verify;
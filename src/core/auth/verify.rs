@@ -1,6 +1,9 @@
 use futures_util::join;
 
+use super::api_key::ApiKeyVerifier;
 use super::basic::BasicVerifier;
+use super::client_cert::ClientCertVerifier;
+use super::introspection::IntrospectionVerifier;
 use super::jwt::jwt_verify::JwtVerifier;
 use super::verification::Verification;
 use crate::core::blueprint;
@@ -14,6 +17,9 @@ pub(crate) trait Verify {
 pub enum Verifier {
     Basic(BasicVerifier),
     Jwt(JwtVerifier),
+    ClientCert(ClientCertVerifier),
+    Introspection(IntrospectionVerifier),
+    ApiKey(ApiKeyVerifier),
 }
 
 pub enum AuthVerifier {
@@ -27,6 +33,13 @@ impl From<blueprint::Provider> for Verifier {
         match provider {
             blueprint::Provider::Basic(options) => Verifier::Basic(BasicVerifier::new(options)),
             blueprint::Provider::Jwt(options) => Verifier::Jwt(JwtVerifier::new(options)),
+            blueprint::Provider::ClientCert(options) => {
+                Verifier::ClientCert(ClientCertVerifier::new(options))
+            }
+            blueprint::Provider::OAuth2Introspection(options) => {
+                Verifier::Introspection(IntrospectionVerifier::new(options))
+            }
+            blueprint::Provider::ApiKeys(options) => Verifier::ApiKey(ApiKeyVerifier::new(options)),
         }
     }
 }
@@ -51,6 +64,9 @@ impl Verify for Verifier {
         match self {
             Verifier::Basic(basic) => basic.verify(req_ctx).await,
             Verifier::Jwt(jwt) => jwt.verify(req_ctx).await,
+            Verifier::ClientCert(client_cert) => client_cert.verify(req_ctx).await,
+            Verifier::Introspection(introspection) => introspection.verify(req_ctx).await,
+            Verifier::ApiKey(api_key) => api_key.verify(req_ctx).await,
         }
     }
 }
@@ -74,14 +90,16 @@ impl Verify for AuthVerifier {
 #[cfg(test)]
 mod tests {
     use super::AuthVerifier;
+    use crate::core::auth::api_key::tests::create_api_key_request;
     use crate::core::auth::basic::tests::create_basic_auth_request;
+    use crate::core::auth::client_cert::tests::{create_client_cert_request, test_ca};
     use crate::core::auth::error::Error;
     use crate::core::auth::jwt::jwt_verify::tests::{
         create_jwt_auth_request, JWT_VALID_TOKEN_WITH_KID,
     };
     use crate::core::auth::verification::Verification;
     use crate::core::auth::verify::Verify;
-    use crate::core::blueprint::{Auth, Basic, Jwt, Provider};
+    use crate::core::blueprint::{ApiKeys, Auth, Basic, ClientCert, Jwt, Provider};
     use crate::core::http::RequestContext;
 
     #[tokio::test]
@@ -133,6 +151,31 @@ mod tests {
         verify_and_assert(&verifier, &req_ctx, Verification::succeed()).await;
     }
 
+    #[tokio::test]
+    async fn verify_any_client_cert() {
+        let verifier = AuthVerifier::from(Auth::Or(
+            Auth::Provider(Provider::Basic(Basic::test_value())).into(),
+            Auth::Provider(Provider::ClientCert(ClientCert {
+                certs: vec![test_ca()],
+                subject: Some("CN=orders".to_owned()),
+                san: None,
+            }))
+            .into(),
+        ));
+        let req_ctx = create_client_cert_request("CN=orders", &[]);
+        verify_and_assert(&verifier, &req_ctx, Verification::succeed()).await;
+    }
+
+    #[tokio::test]
+    async fn verify_any_api_key() {
+        let verifier = AuthVerifier::from(Auth::Or(
+            Auth::Provider(Provider::Basic(Basic::test_value())).into(),
+            Auth::Provider(Provider::ApiKeys(ApiKeys::test_value())).into(),
+        ));
+        let req_ctx = create_api_key_request("acme-key");
+        verify_and_assert(&verifier, &req_ctx, Verification::succeed()).await;
+    }
+
     // Helper Functions
     async fn verify_and_assert(
         verifier: &AuthVerifier,
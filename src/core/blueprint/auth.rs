@@ -1,9 +1,10 @@
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::fmt::Debug;
 
 use jsonwebtoken::jwk::JwkSet;
+use rustls_pki_types::CertificateDer;
 
-use crate::core::config::{ConfigModule, Content};
+use crate::core::config::{self, ClientCa, ConfigModule, Content, Introspection};
 
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct Basic {
@@ -18,10 +19,46 @@ pub struct Jwt {
     pub jwks: JwkSet,
 }
 
+/// Requires a certificate verified against the CA of the provider, with a
+/// subject and a SAN that match the patterns, when they're set
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ClientCert {
+    pub certs: Vec<CertificateDer<'static>>,
+    pub subject: Option<String>,
+    pub san: Option<String>,
+}
+
+/// Validates opaque tokens with the introspection endpoint of an OAuth2
+/// authorization server (RFC 7662). The active tokens are cached for their
+/// remaining lifetime, up to `max_age` seconds when it's set.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct OAuth2Introspection {
+    pub url: String,
+    pub client_id: Option<String>,
+    pub client_secret: Option<String>,
+    pub issuer: Option<String>,
+    pub audiences: HashSet<String>,
+    pub max_age: Option<u64>,
+}
+
+/// Requires an API key, read from the header or else from the query parameter,
+/// that is listed by its hash and was granted the scopes. The keys are
+/// indexed by their lowercase hash.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ApiKeys {
+    pub keys: HashMap<String, config::ApiKey>,
+    pub header: Option<String>,
+    pub query: Option<String>,
+    pub scopes: Vec<String>,
+}
+
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub enum Provider {
     Basic(Basic),
     Jwt(Jwt),
+    ClientCert(ClientCert),
+    OAuth2Introspection(OAuth2Introspection),
+    ApiKeys(ApiKeys),
 }
 
 impl From<Content<String>> for Content<Provider> {
@@ -47,6 +84,55 @@ impl From<Content<JwkSet>> for Content<Provider> {
     }
 }
 
+impl From<Content<ClientCa>> for Content<Provider> {
+    fn from(content: Content<ClientCa>) -> Self {
+        Content {
+            id: content.id,
+            content: Provider::ClientCert(ClientCert {
+                certs: content.content.certs,
+                subject: content.content.subject,
+                san: content.content.san,
+            }),
+        }
+    }
+}
+
+impl From<Content<Introspection>> for Content<Provider> {
+    fn from(content: Content<Introspection>) -> Self {
+        let introspection = content.content;
+        Content {
+            id: content.id,
+            content: Provider::OAuth2Introspection(OAuth2Introspection {
+                url: introspection.url,
+                client_id: introspection.client_id,
+                client_secret: introspection.client_secret,
+                issuer: introspection.issuer,
+                audiences: introspection.audiences.into_iter().collect(),
+                max_age: introspection.max_age,
+            }),
+        }
+    }
+}
+
+impl From<Content<config::ApiKeys>> for Content<Provider> {
+    fn from(content: Content<config::ApiKeys>) -> Self {
+        let api_keys = content.content;
+        Content {
+            id: content.id,
+            content: Provider::ApiKeys(ApiKeys {
+                header: api_keys.header().map(str::to_owned),
+                query: api_keys.query,
+                scopes: api_keys.scopes,
+                keys: api_keys
+                    .keys
+                    .into_iter()
+                    .map(|key| (key.hash.to_lowercase(), key))
+                    .collect(),
+            }),
+        }
+    }
+}
+
 impl Provider {
     /// Used to collect all auth providers from the config module
     pub fn from_config(config_module: &ConfigModule) -> Vec<Content<Provider>> {
@@ -62,6 +148,27 @@ impl Provider {
                     .iter()
                     .map(|jwks| jwks.clone().into()),
             )
+            .chain(
+                config_module
+                    .extensions()
+                    .client_ca
+                    .iter()
+                    .map(|client_ca| client_ca.clone().into()),
+            )
+            .chain(
+                config_module
+                    .extensions()
+                    .introspection
+                    .iter()
+                    .map(|introspection| introspection.clone().into()),
+            )
+            .chain(
+                config_module
+                    .extensions()
+                    .api_keys
+                    .iter()
+                    .map(|api_keys| api_keys.clone().into()),
+            )
             .collect()
     }
 }
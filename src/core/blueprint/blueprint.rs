@@ -108,6 +108,7 @@ pub struct EnumValueDefinition {
 pub struct SchemaDefinition {
     pub query: String,
     pub mutation: Option<String>,
+    pub subscription: Option<String>,
     pub directives: Vec<Directive>,
 }
 
@@ -128,6 +129,7 @@ pub struct FieldDefinition {
     pub directives: Vec<Directive>,
     pub description: Option<String>,
     pub default_value: Option<serde_json::Value>,
+    pub cost: Option<usize>,
 }
 
 impl FieldDefinition {
@@ -182,6 +184,10 @@ impl Blueprint {
         self.schema.mutation.clone()
     }
 
+    pub fn subscription(&self) -> Option<String> {
+        self.schema.subscription.clone()
+    }
+
     fn drop_resolvers(mut self) -> Self {
         for def in self.definitions.iter_mut() {
             if let Definition::Object(def) = def {
@@ -8,7 +8,9 @@ use tailcall_valid::{Valid, Validator};
 use union_resolver::update_union_resolver;
 
 use crate::core::blueprint::*;
-use crate::core::config::{Config, Enum, Field, GraphQLOperationType, Protected, Union};
+use crate::core::config::{
+    Config, Enum, Field, GraphQLOperationType, Invalidate, Protected, RateLimit, Union,
+};
 use crate::core::directive::DirectiveCodec;
 use crate::core::ir::model::{Cache, IR};
 use crate::core::try_fold::TryFold;
@@ -295,6 +297,7 @@ fn update_args<'a>() -> TryFold<
                 directives: to_directives(&field.directives),
                 resolver: None,
                 default_value: field.default_value.clone(),
+                cost: field.cost.as_ref().map(|cost| cost.weight),
             })
         },
     )
@@ -359,19 +362,33 @@ pub fn fix_dangling_resolvers<'a>() -> TryFold<
 
 /// Wraps the IO Expression with Expression::Cached
 /// if `Field::cache` is present for that field
-pub fn update_cache_resolvers<'a>() -> TryFold<
+pub fn update_cache_resolvers<'a>(
+    object_name: &'a str,
+) -> TryFold<
     'a,
     (&'a ConfigModule, &'a Field, &'a config::Type, &'a str),
     FieldDefinition,
     BlueprintError,
 > {
     TryFold::<(&ConfigModule, &Field, &config::Type, &str), FieldDefinition, BlueprintError>::new(
-        move |(_config, field, typ, _name), mut b_field| {
-            if let Some(config::Cache { max_age }) = field.cache.as_ref().or(typ.cache.as_ref()) {
-                b_field.map_expr(|expression| Cache::wrap(*max_age, expression))
-            }
-
-            Valid::succeed(b_field)
+        move |(_config, field, typ, name), mut b_field| {
+            let Some(cache) = field.cache.as_ref().or(typ.cache.as_ref()) else {
+                return Valid::succeed(b_field);
+            };
+
+            Valid::from_iter(cache.vary_by.iter(), |path| {
+                let root = path.split('.').next().unwrap_or_default();
+                if ["args", "headers", "claims", "value"].contains(&root) {
+                    Valid::succeed(())
+                } else {
+                    Valid::fail(BlueprintError::InvalidCacheVaryBy(path.clone()))
+                }
+            })
+            .map(move |_| {
+                let scope = format!("{}.{}", object_name, name);
+                b_field.map_expr(|expression| Cache::wrap(cache, scope, expression));
+                b_field
+            })
         },
     )
 }
@@ -517,8 +534,11 @@ pub fn to_field_definition(
         .and(update_graphql(operation_type).trace(config::GraphQL::trace_name().as_str()))
         .and(update_modify().trace(config::Modify::trace_name().as_str()))
         .and(update_call(operation_type, object_name).trace(config::Call::trace_name().as_str()))
+        .and(update_sse(object_name).trace(config::Sse::trace_name().as_str()))
         .and(fix_dangling_resolvers())
-        .and(update_cache_resolvers())
+        .and(update_cache_resolvers(object_name).trace(config::Cache::trace_name().as_str()))
+        .and(update_invalidate(object_name).trace(Invalidate::trace_name().as_str()))
+        .and(update_rate_limit(object_name).trace(RateLimit::trace_name().as_str()))
         .and(update_protected(object_name).trace(Protected::trace_name().as_str()))
         .and(update_enum_alias())
         .and(update_union_resolver())
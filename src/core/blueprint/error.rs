@@ -46,6 +46,9 @@ pub enum BlueprintError {
     #[error("Protobuf files were not specified in the config")]
     ProtobufFilesNotSpecifiedInConfig,
 
+    #[error("protobufMethod is required with the ApplicationProtobuf encoding")]
+    ProtobufMethodRequired,
+
     #[error("GroupBy is only supported for GET requests")]
     GroupByOnlyForGet,
 
@@ -61,6 +64,9 @@ pub enum BlueprintError {
     #[error("Input types can not be protected")]
     InputTypesCannotBeProtected,
 
+    #[error("Input types can not be rate limited")]
+    InputTypesCannotBeRateLimited,
+
     #[error("@protected operator is used but there is no @link definitions for auth providers")]
     ProtectedOperatorNoAuthProviders,
 
@@ -142,12 +148,69 @@ pub enum BlueprintError {
     #[error("Mutation type is not defined")]
     MutationTypeNotDefined,
 
+    #[error("Subscription type is not defined")]
+    SubscriptionTypeNotDefined,
+
+    #[error("@sse can only be used on fields of the subscription type")]
+    SseOnlyForSubscription,
+
+    #[error("trustedDocuments requires enableJIT")]
+    TrustedDocumentsRequireJIT,
+
+    #[error("trustedDocuments requires at least one link of type Operation")]
+    TrustedDocumentsRequireOperations,
+
+    #[error("Operation {0} is defined in more than one trusted document")]
+    DuplicateTrustedOperation(String),
+
+    #[error("queryLimits requires enableJIT")]
+    QueryLimitsRequireJIT,
+
+    #[error("responseCache requires enableJIT")]
+    ResponseCacheRequiresJIT,
+
+    #[error("The token of the admin API can't be empty")]
+    AdminTokenRequired,
+
+    #[error("failureThreshold must be a percentage between 1 and 100")]
+    InvalidFailureThreshold,
+
+    #[error("varyBy paths must start with args, headers, claims or value: {0}")]
+    InvalidCacheVaryBy(String),
+
+    #[error("@invalidate can only be used on the fields of the mutation type")]
+    InvalidateRequiresMutation,
+
+    #[error("Invalid Redis URL: {0}")]
+    InvalidRedisUrl(String),
+
+    #[error("The prefix of the Redis keys can't be empty")]
+    EmptyRedisPrefix,
+
+    #[error("maxSize must be greater than 0")]
+    InvalidDiskCacheSize,
+
+    #[error("No link of type Cert or Key has the id '{0}'")]
+    TlsLinkNotFound(String),
+
+    #[error("The link '{0}' doesn't contain a certificate or a private key in PEM")]
+    InvalidTlsLink(String),
+
+    #[error("cert and key have to be set together")]
+    TlsCertAndKeyRequired,
+
+    #[error("The TLS settings can't be loaded: {0}")]
+    InvalidTls(String),
+
     #[error("Certificate is required for HTTP2")]
     CertificateIsRequiredForHTTP2,
 
     #[error("Key is required for HTTP2")]
     KeyIsRequiredForHTTP2,
 
+    #[error("Client certificates can only be verified by an HTTP2 server")]
+    ClientCertificateRequiresHTTP2,
+
     #[error("Experimental headers must start with 'x-' or 'X-'. Got: '{0}'")]
     ExperimentalHeaderInvalidFormat(String),
 
@@ -35,7 +35,7 @@ pub fn config_blueprint<'a>() -> TryFold<'a, ConfigModule, Blueprint, BlueprintE
     });
 
     let links = TryFoldConfig::<Blueprint>::new(|config_module, blueprint| {
-        Valid::from(Links::try_from(config_module.links.clone())).map_to(blueprint)
+        Valid::from(Links::try_from(config_module.config())).map_to(blueprint)
     });
 
     let opentelemetry = to_opentelemetry().transform::<Blueprint>(
@@ -71,6 +71,10 @@ impl Index {
         self.schema.mutation.as_deref()
     }
 
+    pub fn get_subscription(&self) -> Option<&str> {
+        self.schema.subscription.as_deref()
+    }
+
     pub fn is_type_implements(&self, type_name: &str, type_or_interface: &str) -> bool {
         if type_name == type_or_interface {
             return true;
@@ -256,6 +260,15 @@ mod test {
         assert_eq!(index.get_mutation(), None);
     }
 
+    #[test]
+    fn test_get_subscription() {
+        let mut index = setup();
+        assert_eq!(index.get_subscription(), None);
+
+        index.schema.subscription = Some("Subscription".to_string());
+        assert_eq!(index.get_subscription(), Some("Subscription"));
+    }
+
     #[test]
     fn test_is_type_implements() {
         let index = setup();
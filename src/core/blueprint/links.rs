@@ -1,15 +1,19 @@
 use tailcall_valid::{Valid, ValidationError, Validator};
 
 use super::BlueprintError;
-use crate::core::config::{Link, LinkType};
+use crate::core::config::{Config, Link, LinkType};
 use crate::core::directive::DirectiveCodec;
 
 pub struct Links;
 
-impl TryFrom<Vec<Link>> for Links {
+impl TryFrom<&Config> for Links {
     type Error = ValidationError<crate::core::blueprint::BlueprintError>;
 
-    fn try_from(links: Vec<Link>) -> Result<Self, Self::Error> {
+    fn try_from(config: &Config) -> Result<Self, Self::Error> {
+        let links = &config.links;
+        // keys referenced by the upstream TLS settings aren't used by the server
+        let tls_link_ids = config.tls_link_ids();
+
         Valid::from_iter(links.iter().enumerate(), |(pos, link)| {
             Valid::succeed(link.to_owned())
                 .and_then(|link| {
@@ -45,6 +49,7 @@ impl TryFrom<Vec<Link>> for Links {
             let key_links = links
                 .iter()
                 .filter(|l| l.type_of == LinkType::Key)
+                .filter(|l| l.id.as_ref().map_or(true, |id| !tls_link_ids.contains(id)))
                 .collect::<Vec<&Link>>();
 
             if key_links.len() > 1 {
@@ -18,6 +18,7 @@ mod schema;
 mod server;
 pub mod telemetry;
 mod timeout;
+mod trusted_documents;
 mod union_resolver;
 mod upstream;
 
@@ -34,6 +35,7 @@ pub use operators::*;
 pub use schema::*;
 pub use server::*;
 pub use timeout::GlobalTimeout;
+pub use trusted_documents::*;
 pub use upstream::*;
 
 use crate::core::config::ConfigModule;
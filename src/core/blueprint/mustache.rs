@@ -88,9 +88,14 @@ impl<'a> MustachePartsValidator<'a> {
                     return Valid::fail(BlueprintError::VarNotSetInServerConfig(tail.to_string()));
                 }
             }
-            "headers" | "env" => {
-                // "headers" and "env" refers to values known at runtime, which
-                // we can't validate here
+            "headers" | "env" | "claims" => {
+                // "headers", "env" and "claims" refers to values known at
+                // runtime, which we can't validate here
+            }
+            "clientCert" => {
+                if !matches!(tail, "subject" | "san") {
+                    return Valid::fail(BlueprintError::UnknownTemplateDirective(parts.join(".")));
+                }
             }
             _ => {
                 return Valid::fail(BlueprintError::UnknownTemplateDirective(head.to_string()));
@@ -116,7 +121,8 @@ impl FieldDefinition {
         let parts_validator = MustachePartsValidator::new(type_of, config, self);
 
         match &self.resolver {
-            Some(IR::IO(IO::Http { req_template, .. })) => {
+            Some(IR::IO(IO::Http { req_template, .. }))
+            | Some(IR::IO(IO::Sse { req_template })) => {
                 Valid::from_iter(req_template.root_url.expression_segments(), |parts| {
                     parts_validator.validate(parts, false).trace("path")
                 })
@@ -221,6 +227,7 @@ mod test {
             directives: vec![],
             description: None,
             default_value: None,
+            cost: None,
         };
 
         (config, fld)
@@ -61,6 +61,7 @@ pub fn compile_entity_resolver(inputs: CompileEntityResolver<'_>) -> Valid<IR, B
                 Resolver::Expr(expr) => {
                     compile_expr(super::CompileExpr { config_module, field, expr, validate: true })
                 }
+                Resolver::Sse(_) => Valid::fail(BlueprintError::SseOnlyForSubscription),
                 Resolver::ApolloFederation(federation) => match federation {
                     ApolloFederation::EntityResolver(entity_resolver) => {
                         compile_entity_resolver(CompileEntityResolver { entity_resolver, ..inputs })
@@ -1,3 +1,5 @@
+use std::time::Duration;
+
 use serde_json::Value;
 use tailcall_valid::{Valid, Validator};
 
@@ -113,6 +115,16 @@ pub fn compile_call(
     .and_then(|field| {
         Valid::from_option(field.resolver, BlueprintError::ResultResolverCanNotBeEmpty)
     })
+    .map(|expr| match call.timeout {
+        Some(timeout) => {
+            let timeout = Duration::from_millis(timeout.get());
+            expr.modify(&mut |expr| match expr {
+                IR::IO(io) => Some(IR::IO(io.clone().with_timeout(timeout))),
+                _ => None,
+            })
+        }
+        None => expr,
+    })
 }
 
 fn get_type_and_field(call: &config::Step) -> Option<(String, String)> {
@@ -1,4 +1,5 @@
 use std::collections::{HashMap, HashSet};
+use std::time::Duration;
 
 use tailcall_valid::{Valid, Validator};
 
@@ -10,6 +11,7 @@ use crate::core::graphql::RequestTemplate;
 use crate::core::helpers;
 use crate::core::ir::model::{IO, IR};
 use crate::core::ir::RelatedFields;
+use crate::core::retry::RetryPolicy;
 use crate::core::try_fold::TryFold;
 
 fn create_related_fields(
@@ -88,7 +90,23 @@ pub fn compile_graphql(
             let field_name = graphql.name.clone();
             let batch = graphql.batch;
             let dedupe = graphql.dedupe.unwrap_or_default();
-            IR::IO(IO::GraphQL { req_template, field_name, batch, dl_id: None, dedupe })
+            let retry = graphql
+                .retry
+                .as_ref()
+                .or(config.upstream.retry.as_ref())
+                .map(RetryPolicy::from);
+            let timeout = graphql
+                .timeout
+                .map(|timeout| Duration::from_millis(timeout.get()));
+            IR::IO(IO::GraphQL {
+                req_template,
+                field_name,
+                batch,
+                dl_id: None,
+                dedupe,
+                retry,
+                timeout,
+            })
         })
 }
 
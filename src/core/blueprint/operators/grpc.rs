@@ -1,11 +1,12 @@
 use std::fmt::Display;
+use std::time::Duration;
 
 use prost_reflect::prost_types::FileDescriptorSet;
 use prost_reflect::FieldDescriptor;
 use tailcall_valid::{Valid, ValidationError, Validator};
 
 use super::apply_select;
-use crate::core::blueprint::{BlueprintError, FieldDefinition};
+use crate::core::blueprint::{to_resolver_tls, BlueprintError, FieldDefinition};
 use crate::core::config::group_by::GroupBy;
 use crate::core::config::{Config, ConfigModule, Field, GraphQLOperationType, Grpc, Resolver};
 use crate::core::grpc::protobuf::{ProtobufOperation, ProtobufSet};
@@ -13,6 +14,7 @@ use crate::core::grpc::request_template::RequestTemplate;
 use crate::core::ir::model::{IO, IR};
 use crate::core::json::JsonSchema;
 use crate::core::mustache::Mustache;
+use crate::core::retry::RetryPolicy;
 use crate::core::try_fold::TryFold;
 use crate::core::{config, helpers};
 
@@ -28,7 +30,7 @@ fn to_url(grpc: &Grpc, method: &GrpcMethod) -> Valid<Mustache, String> {
     })
 }
 
-fn to_operation(
+pub fn to_operation(
     method: &GrpcMethod,
     file_descriptor_set: FileDescriptorSet,
 ) -> Valid<ProtobufOperation, String> {
@@ -186,6 +188,15 @@ pub fn compile_grpc(inputs: CompileGrpc) -> Valid<IR, BlueprintError> {
     let grpc = inputs.grpc;
     let validate_with_schema = inputs.validate_with_schema;
     let dedupe = grpc.dedupe.unwrap_or_default();
+    let retry = grpc
+        .retry
+        .as_ref()
+        .or(config_module.upstream.retry.as_ref())
+        .map(RetryPolicy::from);
+    let timeout = grpc
+        .timeout
+        .map(|timeout| Duration::from_millis(timeout.get()));
+    let tls = to_resolver_tls(config_module, grpc.tls.as_ref());
 
     Valid::from(GrpcMethod::try_from(grpc.method.as_str()))
         .and_then(|method| {
@@ -218,7 +229,8 @@ pub fn compile_grpc(inputs: CompileGrpc) -> Valid<IR, BlueprintError> {
             };
             validation.map(|_| (url, headers, operation, body))
         })
-        .map(|(url, headers, operation, body)| {
+        .zip(tls)
+        .map(|((url, headers, operation, body), tls)| {
             let req_template = RequestTemplate {
                 url,
                 headers,
@@ -232,9 +244,20 @@ pub fn compile_grpc(inputs: CompileGrpc) -> Valid<IR, BlueprintError> {
                     group_by: Some(GroupBy::new(grpc.batch_key.clone(), None)),
                     dl_id: None,
                     dedupe,
+                    retry,
+                    timeout,
+                    tls,
                 })
             } else {
-                IR::IO(IO::Grpc { req_template, group_by: None, dl_id: None, dedupe })
+                IR::IO(IO::Grpc {
+                    req_template,
+                    group_by: None,
+                    dl_id: None,
+                    dedupe,
+                    retry,
+                    timeout,
+                    tls,
+                })
             };
 
             (io, &grpc.select)
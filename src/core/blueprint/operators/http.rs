@@ -1,14 +1,46 @@
+use std::time::Duration;
+
 use tailcall_valid::{Valid, Validator};
 
 use crate::core::blueprint::*;
 use crate::core::config::group_by::GroupBy;
-use crate::core::config::{Field, Resolver};
+use crate::core::config::{Encoding, Field, Resolver};
 use crate::core::endpoint::Endpoint;
+use crate::core::grpc::protobuf::ProtobufOperation;
 use crate::core::http::{HttpFilter, Method, RequestTemplate};
 use crate::core::ir::model::{IO, IR};
+use crate::core::retry::RetryPolicy;
 use crate::core::try_fold::TryFold;
 use crate::core::{config, helpers, Mustache};
 
+/// Resolves the operation whose messages encode the requests and decode the
+/// responses with the `ApplicationProtobuf` encoding.
+fn to_protobuf_operation(
+    config_module: &config::ConfigModule,
+    http: &config::Http,
+) -> Valid<Option<ProtobufOperation>, BlueprintError> {
+    if http.encoding != Encoding::ApplicationProtobuf {
+        return Valid::succeed(None);
+    }
+
+    let Some(method) = &http.protobuf_method else {
+        return Valid::fail(BlueprintError::ProtobufMethodRequired);
+    };
+
+    Valid::from(GrpcMethod::try_from(method.as_str())).and_then(|method| {
+        let file_descriptor_set = config_module.extensions().get_file_descriptor_set();
+
+        if file_descriptor_set.file.is_empty() {
+            return Valid::fail(BlueprintError::ProtobufFilesNotSpecifiedInConfig);
+        }
+
+        match to_operation(&method, file_descriptor_set).to_result() {
+            Ok(operation) => Valid::succeed(Some(operation)),
+            Err(e) => Valid::from_validation_err(BlueprintError::from_validation_string(e)),
+        }
+    })
+}
+
 pub fn compile_http(
     config_module: &config::ConfigModule,
     http: &config::Http,
@@ -20,6 +52,9 @@ pub fn compile_http(
         Err(e) => Valid::from_validation_err(BlueprintError::from_validation_string(e)),
     };
 
+    let protobuf = to_protobuf_operation(config_module, http);
+    let tls = to_resolver_tls(config_module, http.tls.as_ref());
+
     Valid::<(), BlueprintError>::fail(BlueprintError::GroupByOnlyForGet)
         .when(|| !http.batch_key.is_empty() && http.method != Method::GET)
         .and(
@@ -31,7 +66,8 @@ pub fn compile_http(
         )
         .and(Valid::succeed(http.url.as_str()))
         .zip(mustache_headers)
-        .and_then(|(base_url, headers)| {
+        .zip(protobuf)
+        .and_then(|((base_url, headers), protobuf)| {
             let query = http
                 .query
                 .clone()
@@ -52,19 +88,28 @@ pub fn compile_http(
                     .body(http.body.clone())
                     .encoding(http.encoding.clone()),
             )
-            .map(|req_tmpl| req_tmpl.headers(headers))
+            .map(|req_tmpl| req_tmpl.headers(headers).protobuf(protobuf))
             {
                 Ok(data) => Valid::succeed(data),
                 Err(e) => Valid::fail(BlueprintError::Error(e)),
             }
         })
-        .map(|req_template| {
+        .zip(tls)
+        .map(|(req_template, tls)| {
             // marge http and upstream on_request
             let http_filter = http
                 .on_request
                 .clone()
                 .or(config_module.upstream.on_request.clone())
                 .map(|on_request| HttpFilter { on_request });
+            let retry = http
+                .retry
+                .as_ref()
+                .or(config_module.upstream.retry.as_ref())
+                .map(RetryPolicy::from);
+            let timeout = http
+                .timeout
+                .map(|timeout| Duration::from_millis(timeout.get()));
 
             let io = if !http.batch_key.is_empty() && http.method == Method::GET {
                 // Find a query parameter that contains a reference to the {{.value}} key
@@ -80,6 +125,9 @@ pub fn compile_http(
                     http_filter,
                     is_list,
                     dedupe,
+                    retry,
+                    timeout,
+                    tls,
                 })
             } else {
                 IR::IO(IO::Http {
@@ -89,6 +137,9 @@ pub fn compile_http(
                     http_filter,
                     is_list,
                     dedupe,
+                    retry,
+                    timeout,
+                    tls,
                 })
             };
             (io, &http.select)
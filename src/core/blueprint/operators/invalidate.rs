@@ -0,0 +1,73 @@
+use std::num::NonZeroU64;
+
+use tailcall_valid::Valid;
+
+use crate::core::blueprint::{BlueprintError, FieldDefinition};
+use crate::core::config::{self, Config, ConfigModule, Field};
+use crate::core::ir::model::{Invalidate, IR};
+use crate::core::mustache::Mustache;
+use crate::core::try_fold::TryFold;
+
+/// The longest time the values cached with tags are kept for, including the
+/// time they can be served stale, that the invalidations have to outlive
+fn max_tagged_age(config: &Config) -> Option<NonZeroU64> {
+    config
+        .types
+        .values()
+        .flat_map(|type_| {
+            type_.cache.iter().chain(
+                type_
+                    .fields
+                    .values()
+                    .filter_map(|field| field.cache.as_ref()),
+            )
+        })
+        .filter(|cache| !cache.tags.is_empty())
+        .map(|cache| cache.ttl())
+        .max()
+}
+
+/// Wraps the resolver of a mutation field with IR::Invalidate when
+/// `@invalidate` is set on it
+pub fn update_invalidate<'a>(
+    type_name: &'a str,
+) -> TryFold<
+    'a,
+    (&'a ConfigModule, &'a Field, &'a config::Type, &'a str),
+    FieldDefinition,
+    BlueprintError,
+> {
+    TryFold::<(&ConfigModule, &Field, &config::Type, &'a str), FieldDefinition, BlueprintError>::new(
+        move |(config, field, _, _), mut b_field| {
+            let Some(invalidate) = &field.invalidate else {
+                return Valid::succeed(b_field);
+            };
+
+            if config.schema.mutation.as_deref() != Some(type_name) {
+                return Valid::fail(BlueprintError::InvalidateRequiresMutation);
+            }
+
+            // without tagged values there is nothing to invalidate
+            let Some(max_age) = max_tagged_age(config) else {
+                return Valid::succeed(b_field);
+            };
+
+            let invalidate = Invalidate {
+                tags: invalidate
+                    .tags
+                    .iter()
+                    .map(|tag| Mustache::parse(tag))
+                    .collect(),
+                max_age,
+            };
+
+            let resolver = b_field
+                .resolver
+                .take()
+                .unwrap_or(IR::ContextPath(vec![b_field.name.clone()]));
+            b_field.resolver = Some(IR::Invalidate(invalidate, Box::new(resolver)));
+
+            Valid::succeed(b_field)
+        },
+    )
+}
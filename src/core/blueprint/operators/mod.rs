@@ -5,10 +5,13 @@ mod expr;
 mod graphql;
 mod grpc;
 mod http;
+mod invalidate;
 mod js;
 mod modify;
 mod protected;
+mod rate_limit;
 mod select;
+mod sse;
 
 pub use apollo_federation::*;
 pub use call::*;
@@ -17,7 +20,10 @@ pub use expr::*;
 pub use graphql::*;
 pub use grpc::*;
 pub use http::*;
+pub use invalidate::*;
 pub use js::*;
 pub use modify::*;
 pub use protected::*;
+pub use rate_limit::*;
 pub use select::*;
+pub use sse::*;
@@ -0,0 +1,50 @@
+use tailcall_valid::Valid;
+
+use crate::core::blueprint::{BlueprintError, FieldDefinition};
+use crate::core::config::{self, ConfigModule, Field};
+use crate::core::ir::model::{RateLimit, IR};
+use crate::core::mustache::Mustache;
+use crate::core::rate_limit::RateLimitPolicy;
+use crate::core::try_fold::TryFold;
+
+/// Wraps the resolver of the field with IR::RateLimit when `@rateLimit` is set
+/// on the field, or on its type for the fields that have a resolver. The calls
+/// to the fields of a type share the limit of the type.
+pub fn update_rate_limit<'a>(
+    type_name: &'a str,
+) -> TryFold<
+    'a,
+    (&'a ConfigModule, &'a Field, &'a config::Type, &'a str),
+    FieldDefinition,
+    BlueprintError,
+> {
+    TryFold::<(&ConfigModule, &Field, &config::Type, &'a str), FieldDefinition, BlueprintError>::new(
+        move |(config, field, type_, name), mut b_field| {
+            let (rate_limit, scope) = match (&field.rate_limit, &type_.rate_limit) {
+                (Some(rate_limit), _) => (rate_limit, format!("{}.{}", type_name, name)),
+                (None, Some(rate_limit)) if field.has_resolver() => {
+                    (rate_limit, type_name.to_string())
+                }
+                _ => return Valid::succeed(b_field),
+            };
+
+            if config.input_types().contains(type_name) {
+                return Valid::fail(BlueprintError::InputTypesCannotBeRateLimited);
+            }
+
+            let rate_limit = RateLimit {
+                scope,
+                key: rate_limit.key.as_deref().map(Mustache::parse),
+                policy: RateLimitPolicy::from(rate_limit),
+            };
+
+            let resolver = b_field
+                .resolver
+                .take()
+                .unwrap_or(IR::ContextPath(vec![b_field.name.clone()]));
+            b_field.resolver = Some(IR::RateLimit(rate_limit, Box::new(resolver)));
+
+            Valid::succeed(b_field)
+        },
+    )
+}
@@ -0,0 +1,65 @@
+use tailcall_valid::{Valid, Validator};
+
+use crate::core::blueprint::{BlueprintError, FieldDefinition};
+use crate::core::config::{self, ConfigModule, Field, Resolver};
+use crate::core::endpoint::Endpoint;
+use crate::core::helpers;
+use crate::core::http::{Method, RequestTemplate};
+use crate::core::ir::model::{IO, IR};
+use crate::core::try_fold::TryFold;
+
+pub fn compile_sse(sse: &config::Sse) -> Valid<IR, BlueprintError> {
+    let mustache_headers = match helpers::headers::to_mustache_headers(&sse.headers).to_result() {
+        Ok(mustache_headers) => Valid::succeed(mustache_headers),
+        Err(e) => Valid::from_validation_err(BlueprintError::from_validation_string(e)),
+    };
+
+    mustache_headers.and_then(|headers| {
+        let query = sse
+            .query
+            .iter()
+            .map(|key_value| {
+                (
+                    key_value.key.clone(),
+                    key_value.value.clone(),
+                    key_value.skip_empty.unwrap_or_default(),
+                )
+            })
+            .collect();
+
+        match RequestTemplate::try_from(
+            Endpoint::new(sse.url.clone())
+                .method(Method::GET)
+                .query(query),
+        ) {
+            Ok(req_template) => Valid::succeed(IR::IO(IO::Sse {
+                req_template: req_template.headers(headers),
+            })),
+            Err(e) => Valid::fail(BlueprintError::Error(e)),
+        }
+    })
+}
+
+pub fn update_sse<'a>(
+    object_name: &'a str,
+) -> TryFold<
+    'a,
+    (&'a ConfigModule, &'a Field, &'a config::Type, &'a str),
+    FieldDefinition,
+    BlueprintError,
+> {
+    TryFold::<(&ConfigModule, &Field, &config::Type, &'a str), FieldDefinition, BlueprintError>::new(
+        move |(config_module, field, _, _), b_field| {
+            let Some(Resolver::Sse(sse)) = &field.resolver else {
+                return Valid::succeed(b_field);
+            };
+
+            let is_subscription = config_module.schema.subscription.as_deref() == Some(object_name);
+
+            Valid::<(), BlueprintError>::fail(BlueprintError::SseOnlyForSubscription)
+                .when(|| !is_subscription)
+                .and(compile_sse(sse))
+                .map(|resolver| b_field.resolver(Some(resolver)))
+        },
+    )
+}
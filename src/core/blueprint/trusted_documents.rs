@@ -0,0 +1,180 @@
+use std::collections::HashMap;
+
+use tailcall_valid::{Valid, Validator};
+
+use super::BlueprintError;
+use crate::core::config::ConfigModule;
+use crate::core::persisted_query::sha256;
+
+/// The documents that are allowed to be executed when `trustedDocuments` is
+/// enabled. These are the operations loaded through the links of type
+/// `Operation`, that can be referenced either by the sha256 of the document or
+/// by the name of one of its operations.
+#[derive(Clone, Debug, Default)]
+pub struct TrustedDocuments {
+    by_hash: HashMap<String, String>,
+    by_name: HashMap<String, String>,
+}
+
+impl TrustedDocuments {
+    /// Returns the trusted document that should be executed for a request.
+    /// Requests carrying a document are only allowed if the document itself is
+    /// trusted, otherwise the document is looked up by the provided hash or
+    /// operation name.
+    pub fn resolve<'a>(
+        &'a self,
+        query: &str,
+        operation_name: Option<&str>,
+        hash: Option<&str>,
+    ) -> Option<&'a str> {
+        let document = if !query.is_empty() {
+            self.by_hash.get(&sha256(query))
+        } else if let Some(hash) = hash {
+            self.by_hash.get(&hash.to_lowercase())
+        } else {
+            self.by_name.get(operation_name?)
+        };
+
+        document.map(String::as_str)
+    }
+}
+
+pub fn to_trusted_documents(
+    config_module: &ConfigModule,
+) -> Valid<Option<TrustedDocuments>, BlueprintError> {
+    let server = &config_module.server;
+    if !server.enable_trusted_documents() {
+        return Valid::succeed(None);
+    }
+
+    let operations = &config_module.extensions().operations;
+    let trusted_documents = if !server.enable_jit() {
+        Valid::fail(BlueprintError::TrustedDocumentsRequireJIT)
+    } else if operations.is_empty() {
+        Valid::fail(BlueprintError::TrustedDocumentsRequireOperations)
+    } else {
+        Valid::from_iter(
+            operations.iter(),
+            |operation| match async_graphql::parser::parse_query(&operation.content) {
+                Ok(document) => Valid::succeed((
+                    operation.content.clone(),
+                    document
+                        .operations
+                        .iter()
+                        .filter_map(|(name, _)| name.map(|name| name.to_string()))
+                        .collect::<Vec<_>>(),
+                )),
+                Err(e) => Valid::fail(BlueprintError::Error(e.into())),
+            },
+        )
+        .and_then(|documents| {
+            let mut trusted_documents = TrustedDocuments::default();
+            let mut duplicates = Vec::new();
+
+            for (content, names) in documents {
+                for name in names {
+                    if trusted_documents
+                        .by_name
+                        .insert(name.clone(), content.clone())
+                        .is_some()
+                    {
+                        duplicates.push(name);
+                    }
+                }
+                trusted_documents.by_hash.insert(sha256(&content), content);
+            }
+
+            Valid::from_iter(duplicates, |name| {
+                Valid::<(), _>::fail(BlueprintError::DuplicateTrustedOperation(name))
+            })
+            .map(|_| trusted_documents)
+        })
+    };
+
+    trusted_documents
+        .map(Some)
+        .trace("trustedDocuments")
+        .trace("@server")
+        .trace("schema")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::config::{Config, Content, Extensions, Server};
+
+    const USERS: &str = "query Users { users { id } }";
+    const POSTS: &str = "query Posts { posts { id } } query Post { post(id: 1) { id } }";
+
+    fn config_module(operations: &[&str]) -> ConfigModule {
+        let server = Server { trusted_documents: Some(true), ..Default::default() };
+        let config = Config { server, ..Default::default() };
+        let extensions = Extensions {
+            operations: operations
+                .iter()
+                .map(|content| Content { id: None, content: content.to_string() })
+                .collect(),
+            ..Default::default()
+        };
+
+        ConfigModule::new(config, extensions)
+    }
+
+    fn trusted_documents(operations: &[&str]) -> TrustedDocuments {
+        to_trusted_documents(&config_module(operations))
+            .to_result()
+            .unwrap()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_disabled() {
+        let actual = to_trusted_documents(&ConfigModule::default())
+            .to_result()
+            .unwrap();
+
+        assert!(actual.is_none());
+    }
+
+    #[test]
+    fn test_resolve_by_document() {
+        let trusted = trusted_documents(&[USERS, POSTS]);
+
+        assert_eq!(trusted.resolve(USERS, None, None), Some(USERS));
+        assert_eq!(trusted.resolve("{ users { name } }", None, None), None);
+    }
+
+    #[test]
+    fn test_resolve_by_hash() {
+        let trusted = trusted_documents(&[USERS, POSTS]);
+        let hash = sha256(POSTS);
+
+        assert_eq!(trusted.resolve("", None, Some(&hash)), Some(POSTS));
+        assert_eq!(trusted.resolve("", None, Some("abc")), None);
+    }
+
+    #[test]
+    fn test_resolve_by_operation_name() {
+        let trusted = trusted_documents(&[USERS, POSTS]);
+
+        assert_eq!(trusted.resolve("", Some("Post"), None), Some(POSTS));
+        assert_eq!(trusted.resolve("", Some("Comments"), None), None);
+        assert_eq!(trusted.resolve("", None, None), None);
+    }
+
+    #[test]
+    fn test_duplicate_operation_name() {
+        let actual =
+            to_trusted_documents(&config_module(&[USERS, "query Users { users { name } }"]))
+                .to_result();
+
+        assert!(actual.is_err());
+    }
+
+    #[test]
+    fn test_without_operations() {
+        let actual = to_trusted_documents(&config_module(&[])).to_result();
+
+        assert!(actual.is_err());
+    }
+}
@@ -8,8 +8,13 @@ use ttl_cache::TtlCache;
 
 use super::error::Result;
 
+fn now() -> u64 {
+    chrono::Utc::now().timestamp_millis() as u64
+}
+
 pub struct InMemoryCache<K: Hash + Eq, V> {
-    data: Arc<RwLock<TtlCache<K, V>>>,
+    /// The values along with the time they were set at
+    data: Arc<RwLock<TtlCache<K, (V, u64)>>>,
     hits: AtomicUsize,
     miss: AtomicUsize,
 }
@@ -39,18 +44,22 @@ impl<K: Hash + Eq + Send + Sync, V: Clone + Send + Sync> crate::core::Cache
     #[allow(clippy::too_many_arguments)]
     async fn set<'a>(&'a self, key: K, value: V, ttl: NonZeroU64) -> Result<()> {
         let ttl = Duration::from_millis(ttl.get());
-        self.data.write().unwrap().insert(key, value, ttl);
+        self.data.write().unwrap().insert(key, (value, now()), ttl);
         Ok(())
     }
 
     async fn get<'a>(&'a self, key: &'a K) -> Result<Option<Self::Value>> {
-        let val = self.data.read().unwrap().get(key).cloned();
-        if val.is_some() {
+        Ok(self.get_entry(key).await?.map(|(value, _)| value))
+    }
+
+    async fn get_entry<'a>(&'a self, key: &'a K) -> Result<Option<(Self::Value, u64)>> {
+        let entry = self.data.read().unwrap().get(key).cloned();
+        if entry.is_some() {
             self.hits.fetch_add(1, Ordering::Relaxed);
         } else {
             self.miss.fetch_add(1, Ordering::Relaxed);
         }
-        Ok(val)
+        Ok(entry.map(|(value, set_at)| (value, now().saturating_sub(set_at))))
     }
 
     fn hit_rate(&self) -> Option<f64> {
@@ -66,6 +75,11 @@ impl<K: Hash + Eq + Send + Sync, V: Clone + Send + Sync> crate::core::Cache
 
         None
     }
+
+    async fn clear<'a>(&'a self) -> Result<()> {
+        self.data.write().unwrap().clear();
+        Ok(())
+    }
 }
 
 #[cfg(test)]
@@ -89,4 +103,30 @@ mod tests {
         tokio::time::sleep(Duration::from_millis(ttl.get())).await;
         assert_eq!(cache.get(&10).await.ok(), Some(None));
     }
+
+    #[tokio::test]
+    async fn test_get_entry_age() {
+        let cache: crate::core::cache::InMemoryCache<u64, String> =
+            crate::core::cache::InMemoryCache::default();
+        let ttl = NonZeroU64::new(1000).unwrap();
+
+        cache.set(10, "hello".into(), ttl).await.unwrap();
+        tokio::time::sleep(Duration::from_millis(20)).await;
+
+        let (value, age) = cache.get_entry(&10).await.unwrap().unwrap();
+        assert_eq!(value, "hello");
+        assert!(age >= 20 && age < 1000);
+    }
+
+    #[tokio::test]
+    async fn test_clear() {
+        let cache: crate::core::cache::InMemoryCache<u64, String> =
+            crate::core::cache::InMemoryCache::default();
+        let ttl = NonZeroU64::new(1000).unwrap();
+
+        cache.set(10, "hello".into(), ttl).await.unwrap();
+        cache.clear().await.unwrap();
+
+        assert_eq!(cache.get(&10).await.ok(), Some(None));
+    }
 }
@@ -1,4 +1,6 @@
 pub mod cache;
 pub mod error;
+mod tags;
 pub use cache::*;
 pub use error::Error;
+pub use tags::TagIndex;
@@ -0,0 +1,199 @@
+use std::hash::{Hash, Hasher};
+use std::num::NonZeroU64;
+
+use async_graphql::ConstValue;
+use tailcall_hasher::TailcallHasher;
+
+use super::error::Result;
+use crate::core::ir::model::IoId;
+use crate::core::{Cache, EntityCache};
+
+const VALUE: &str = "value";
+const TAGS: &str = "tags";
+const CACHED_AT: &str = "cachedAt";
+
+/// Key of the entry holding the time a tag was last invalidated at
+fn tag_key(tag: &str) -> IoId {
+    let mut hasher = TailcallHasher::default();
+    "tag".hash(&mut hasher);
+    tag.hash(&mut hasher);
+    IoId::new(hasher.finish())
+}
+
+/// Tracks the tags of the cached values on top of the cache. A tagged value is
+/// stored with its tags and the time it was fetched at, and invalidating a tag
+/// stores the time of the invalidation in the cache itself, so that the
+/// instances sharing a cache see it. Values fetched before the last
+/// invalidation of one of their tags are then treated as misses.
+pub struct TagIndex<'a> {
+    cache: &'a EntityCache,
+}
+
+impl<'a> TagIndex<'a> {
+    pub fn new(cache: &'a EntityCache) -> Self {
+        Self { cache }
+    }
+
+    /// Returns a cached value along with its age, unless one of its tags was
+    /// invalidated after it was fetched. `tagged` tells if the value was
+    /// cached with tags.
+    pub async fn get(&self, key: &IoId, tagged: bool) -> Result<Option<(ConstValue, u64)>> {
+        let Some((entry, age)) = self.cache.get_entry(key).await? else {
+            return Ok(None);
+        };
+        if !tagged {
+            return Ok(Some((entry, age)));
+        }
+
+        let ConstValue::Object(mut entry) = entry else {
+            return Ok(None);
+        };
+        let cached_at = match entry.get(CACHED_AT) {
+            Some(ConstValue::Number(cached_at)) => cached_at.as_u64().unwrap_or_default(),
+            _ => return Ok(None),
+        };
+        if let Some(ConstValue::List(tags)) = entry.get(TAGS) {
+            for tag in tags {
+                let ConstValue::String(tag) = tag else {
+                    continue;
+                };
+                if let Some(ConstValue::Number(invalidated_at)) =
+                    self.cache.get(&tag_key(tag)).await?
+                {
+                    if invalidated_at.as_u64().unwrap_or_default() >= cached_at {
+                        return Ok(None);
+                    }
+                }
+            }
+        }
+
+        Ok(entry.shift_remove(VALUE).map(|value| (value, age)))
+    }
+
+    /// Caches a value fetched at `fetched_at` along with its tags
+    pub async fn set(
+        &self,
+        key: IoId,
+        value: ConstValue,
+        tags: Vec<String>,
+        fetched_at: u64,
+        ttl: NonZeroU64,
+    ) -> Result<()> {
+        if tags.is_empty() {
+            return self.cache.set(key, value, ttl).await;
+        }
+
+        let entry = ConstValue::Object(
+            [
+                (VALUE, value),
+                (
+                    TAGS,
+                    ConstValue::List(tags.into_iter().map(ConstValue::String).collect()),
+                ),
+                (CACHED_AT, ConstValue::from(fetched_at)),
+            ]
+            .into_iter()
+            .map(|(name, value)| (async_graphql::Name::new(name), value))
+            .collect(),
+        );
+        self.cache.set(key, entry, ttl).await
+    }
+
+    /// Invalidates the values cached with one of the tags. The invalidation is
+    /// kept for `ttl`, which has to outlive the values cached with the tags.
+    pub async fn invalidate(&self, tags: &[String], ttl: NonZeroU64) -> Result<()> {
+        let now = Self::now();
+        for tag in tags {
+            self.cache
+                .set(tag_key(tag), ConstValue::from(now), ttl)
+                .await?;
+        }
+        Ok(())
+    }
+
+    /// The current time, in milliseconds, the values are fetched at
+    pub fn now() -> u64 {
+        chrono::Utc::now().timestamp_millis() as u64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::cache::InMemoryCache;
+
+    fn ttl() -> NonZeroU64 {
+        NonZeroU64::new(60000).unwrap()
+    }
+
+    fn value(entry: Option<(ConstValue, u64)>) -> Option<ConstValue> {
+        entry.map(|(value, _)| value)
+    }
+
+    #[tokio::test]
+    async fn test_invalidate() {
+        let cache: InMemoryCache<IoId, ConstValue> = InMemoryCache::default();
+        let index = TagIndex::new(&cache);
+        let tags = vec!["user:1".to_string()];
+
+        let fetched_at = TagIndex::now() - 1;
+        index
+            .set(
+                IoId::new(1),
+                ConstValue::from("Leanne"),
+                tags.clone(),
+                fetched_at,
+                ttl(),
+            )
+            .await
+            .unwrap();
+        index
+            .set(
+                IoId::new(2),
+                ConstValue::from("Ervin"),
+                vec!["user:2".to_string()],
+                fetched_at,
+                ttl(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(
+            value(index.get(&IoId::new(1), true).await.unwrap()),
+            Some(ConstValue::from("Leanne"))
+        );
+
+        index.invalidate(&tags, ttl()).await.unwrap();
+
+        assert_eq!(index.get(&IoId::new(1), true).await.unwrap(), None);
+        assert_eq!(
+            value(index.get(&IoId::new(2), true).await.unwrap()),
+            Some(ConstValue::from("Ervin"))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_untagged() {
+        let cache: InMemoryCache<IoId, ConstValue> = InMemoryCache::default();
+        let index = TagIndex::new(&cache);
+
+        index
+            .set(
+                IoId::new(1),
+                ConstValue::from("Leanne"),
+                vec![],
+                TagIndex::now(),
+                ttl(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(
+            cache.get(&IoId::new(1)).await.unwrap(),
+            Some(ConstValue::from("Leanne"))
+        );
+        assert_eq!(
+            value(index.get(&IoId::new(1), false).await.unwrap()),
+            Some(ConstValue::from("Leanne"))
+        );
+    }
+}
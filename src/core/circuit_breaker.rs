@@ -0,0 +1,401 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use futures_util::stream::BoxStream;
+use hyper::body::Bytes;
+use once_cell::sync::Lazy;
+use prometheus::{register_int_counter_vec, register_int_gauge_vec, IntCounterVec, IntGaugeVec};
+
+use crate::core::blueprint::Tls;
+use crate::core::http::{reqwest_error, Response};
+use crate::core::{config, HttpIO};
+
+const DEFAULT_FAILURE_THRESHOLD: u64 = 50;
+const DEFAULT_MINIMUM_REQUESTS: u64 = 10;
+const DEFAULT_WINDOW: u64 = 10000;
+const DEFAULT_COOLDOWN: u64 = 30000;
+const DEFAULT_HALF_OPEN_REQUESTS: u64 = 1;
+
+static CIRCUIT_BREAKER_STATE: Lazy<IntGaugeVec> = Lazy::new(|| {
+    register_int_gauge_vec!(
+        "circuit_breaker_state",
+        "State of the circuit breaker of an upstream origin: 0 is closed, 1 is half-open and 2 is open",
+        &["origin"]
+    )
+    .expect("Failed to register circuit breaker state")
+});
+
+static CIRCUIT_BREAKER_REJECTED: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "circuit_breaker_rejected_requests_total",
+        "Number of requests to an upstream origin rejected by its open circuit breaker",
+        &["origin"]
+    )
+    .expect("Failed to register circuit breaker rejections")
+});
+
+/// Error returned for the requests rejected by an open circuit.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("Circuit breaker is open for {0}")]
+pub struct CircuitOpen(pub String);
+
+/// When the circuit of an origin opens and for how long it stays open. The
+/// durations are in milliseconds.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CircuitBreakerPolicy {
+    /// Percentage of failed requests at which the circuit opens
+    pub failure_threshold: u64,
+    pub minimum_requests: u64,
+    pub window: u64,
+    pub cooldown: u64,
+    pub half_open_requests: u64,
+}
+
+impl From<&config::CircuitBreaker> for CircuitBreakerPolicy {
+    fn from(circuit_breaker: &config::CircuitBreaker) -> Self {
+        Self {
+            failure_threshold: circuit_breaker
+                .failure_threshold
+                .unwrap_or(DEFAULT_FAILURE_THRESHOLD),
+            minimum_requests: circuit_breaker
+                .minimum_requests
+                .map_or(DEFAULT_MINIMUM_REQUESTS, |n| n.get()),
+            window: circuit_breaker.window.map_or(DEFAULT_WINDOW, |n| n.get()),
+            cooldown: circuit_breaker.cooldown.unwrap_or(DEFAULT_COOLDOWN),
+            half_open_requests: circuit_breaker
+                .half_open_requests
+                .map_or(DEFAULT_HALF_OPEN_REQUESTS, |n| n.get()),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum State {
+    Closed,
+    Open { until: u64 },
+    HalfOpen { since: u64, trials: u64 },
+}
+
+impl State {
+    fn metric(&self) -> i64 {
+        match self {
+            State::Closed => 0,
+            State::HalfOpen { .. } => 1,
+            State::Open { .. } => 2,
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Circuit {
+    state: State,
+    window_start: u64,
+    requests: u64,
+    failures: u64,
+}
+
+impl Circuit {
+    fn new(now: u64) -> Self {
+        Self {
+            state: State::Closed,
+            window_start: now,
+            requests: 0,
+            failures: 0,
+        }
+    }
+
+    fn transition(&mut self, origin: &str, state: State, now: u64) {
+        CIRCUIT_BREAKER_STATE
+            .with_label_values(&[origin])
+            .set(state.metric());
+
+        match state {
+            State::Open { .. } => tracing::warn!("Circuit breaker opened for {}", origin),
+            State::Closed => tracing::info!("Circuit breaker closed for {}", origin),
+            State::HalfOpen { .. } => {}
+        }
+
+        *self = Self { state, ..Self::new(now) };
+    }
+}
+
+/// Tracks the failure rate of the requests made to every upstream origin and
+/// decides which requests can be made.
+pub struct CircuitBreaker {
+    policy: CircuitBreakerPolicy,
+    circuits: Mutex<HashMap<String, Circuit>>,
+}
+
+impl CircuitBreaker {
+    pub fn new(policy: CircuitBreakerPolicy) -> Self {
+        Self { policy, circuits: Mutex::new(HashMap::new()) }
+    }
+
+    /// Checks if a request to the origin can be made at `now`.
+    fn acquire(&self, origin: &str, now: u64) -> bool {
+        let mut circuits = self.circuits.lock().unwrap();
+        let circuit = circuits.entry(origin.to_string()).or_insert_with(|| {
+            CIRCUIT_BREAKER_STATE
+                .with_label_values(&[origin])
+                .set(State::Closed.metric());
+            Circuit::new(now)
+        });
+
+        match circuit.state {
+            State::Closed => true,
+            State::Open { until } if now >= until => {
+                circuit.transition(origin, State::HalfOpen { since: now, trials: 1 }, now);
+                true
+            }
+            State::Open { .. } => false,
+            // trial requests that never finished are given up after a cooldown
+            State::HalfOpen { since, .. } if now.saturating_sub(since) >= self.policy.cooldown => {
+                circuit.state = State::HalfOpen { since: now, trials: 1 };
+                true
+            }
+            State::HalfOpen { since, trials } if trials < self.policy.half_open_requests => {
+                circuit.state = State::HalfOpen { since, trials: trials + 1 };
+                true
+            }
+            State::HalfOpen { .. } => false,
+        }
+    }
+
+    /// Records whether a request made to the origin failed.
+    fn record(&self, origin: &str, failed: bool, now: u64) {
+        let mut circuits = self.circuits.lock().unwrap();
+        let Some(circuit) = circuits.get_mut(origin) else {
+            return;
+        };
+
+        match circuit.state {
+            State::Closed => {
+                if now.saturating_sub(circuit.window_start) >= self.policy.window {
+                    *circuit = Circuit::new(now);
+                }
+
+                circuit.requests += 1;
+                circuit.failures += failed as u64;
+
+                if circuit.requests >= self.policy.minimum_requests
+                    && circuit.failures * 100 >= self.policy.failure_threshold * circuit.requests
+                {
+                    let until = now + self.policy.cooldown;
+                    circuit.transition(origin, State::Open { until }, now);
+                }
+            }
+            State::HalfOpen { .. } if failed => {
+                let until = now + self.policy.cooldown;
+                circuit.transition(origin, State::Open { until }, now);
+            }
+            State::HalfOpen { .. } => circuit.transition(origin, State::Closed, now),
+            // requests sent before the circuit opened don't change its state
+            State::Open { .. } => {}
+        }
+    }
+}
+
+/// Checks if the request failed because of the upstream, rather than because
+/// of the request itself.
+fn is_failure<A>(result: &anyhow::Result<A>, status: impl Fn(&A) -> reqwest::StatusCode) -> bool {
+    match result {
+        Ok(response) => status(response).is_server_error(),
+        Err(error) => reqwest_error(error)
+            .and_then(|error| error.status())
+            .map_or(true, |status| status.is_server_error()),
+    }
+}
+
+/// Milliseconds since the unix epoch. Uses chrono so that it also works on
+/// wasm targets.
+fn now() -> u64 {
+    chrono::Utc::now().timestamp_millis() as u64
+}
+
+/// Wraps an [HttpIO] with a [CircuitBreaker], failing the requests to origins
+/// with an open circuit with [CircuitOpen].
+pub struct CircuitBreakerHttp {
+    http: Arc<dyn HttpIO>,
+    circuit_breaker: Arc<CircuitBreaker>,
+}
+
+impl CircuitBreakerHttp {
+    pub fn new(http: Arc<dyn HttpIO>, circuit_breaker: Arc<CircuitBreaker>) -> Self {
+        Self { http, circuit_breaker }
+    }
+
+    fn acquire(&self, request: &reqwest::Request) -> anyhow::Result<String> {
+        let origin = request.url().origin().ascii_serialization();
+
+        if self.circuit_breaker.acquire(&origin, now()) {
+            Ok(origin)
+        } else {
+            CIRCUIT_BREAKER_REJECTED.with_label_values(&[&origin]).inc();
+            Err(CircuitOpen(origin).into())
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl HttpIO for CircuitBreakerHttp {
+    async fn execute(&self, request: reqwest::Request) -> anyhow::Result<Response<Bytes>> {
+        let origin = self.acquire(&request)?;
+        let result = self.http.execute(request).await;
+        let failed = is_failure(&result, |response| response.status);
+        self.circuit_breaker.record(&origin, failed, now());
+
+        result
+    }
+
+    async fn execute_stream(
+        &self,
+        request: reqwest::Request,
+    ) -> anyhow::Result<BoxStream<'static, anyhow::Result<Bytes>>> {
+        let origin = self.acquire(&request)?;
+        let result = self.http.execute_stream(request).await;
+        let failed = is_failure(&result, |_| reqwest::StatusCode::OK);
+        self.circuit_breaker.record(&origin, failed, now());
+
+        result
+    }
+
+    fn supports_tls(&self) -> bool {
+        self.http.supports_tls()
+    }
+
+    fn with_tls(&self, tls: &Tls) -> Option<Arc<dyn HttpIO>> {
+        let http = self.http.with_tls(tls)?;
+        Some(Arc::new(Self::new(http, self.circuit_breaker.clone())))
+    }
+
+    async fn clear_cache(&self) -> anyhow::Result<()> {
+        self.http.clear_cache().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use reqwest::StatusCode;
+
+    use super::*;
+
+    const ORIGIN: &str = "http://localhost:8080";
+
+    /// Counts the purges of its cache
+    #[derive(Default)]
+    struct CachedHttp {
+        purges: AtomicUsize,
+    }
+
+    #[async_trait::async_trait]
+    impl HttpIO for CachedHttp {
+        async fn execute(&self, _request: reqwest::Request) -> anyhow::Result<Response<Bytes>> {
+            Ok(Response::default())
+        }
+
+        async fn clear_cache(&self) -> anyhow::Result<()> {
+            self.purges.fetch_add(1, Ordering::SeqCst);
+            Ok(())
+        }
+    }
+
+    fn circuit_breaker() -> CircuitBreaker {
+        CircuitBreaker::new(CircuitBreakerPolicy {
+            failure_threshold: 50,
+            minimum_requests: 4,
+            window: 1000,
+            cooldown: 500,
+            half_open_requests: 1,
+        })
+    }
+
+    /// Makes a request at `now` and records its outcome if it was allowed
+    fn request(circuit_breaker: &CircuitBreaker, failed: bool, now: u64) -> bool {
+        let allowed = circuit_breaker.acquire(ORIGIN, now);
+        if allowed {
+            circuit_breaker.record(ORIGIN, failed, now);
+        }
+        allowed
+    }
+
+    #[test]
+    fn test_opens_at_failure_threshold() {
+        let circuit_breaker = circuit_breaker();
+
+        assert!(request(&circuit_breaker, false, 0));
+        assert!(request(&circuit_breaker, true, 10));
+        assert!(request(&circuit_breaker, false, 20));
+        assert!(request(&circuit_breaker, true, 30));
+
+        assert!(!circuit_breaker.acquire(ORIGIN, 40));
+        assert!(circuit_breaker.acquire("http://localhost:8081", 40));
+    }
+
+    #[test]
+    fn test_waits_for_minimum_requests() {
+        let circuit_breaker = circuit_breaker();
+
+        assert!(request(&circuit_breaker, true, 0));
+        assert!(request(&circuit_breaker, true, 10));
+        assert!(request(&circuit_breaker, true, 20));
+
+        assert!(circuit_breaker.acquire(ORIGIN, 30));
+    }
+
+    #[test]
+    fn test_forgets_requests_of_previous_window() {
+        let circuit_breaker = circuit_breaker();
+
+        assert!(request(&circuit_breaker, true, 0));
+        assert!(request(&circuit_breaker, true, 10));
+        assert!(request(&circuit_breaker, true, 20));
+        assert!(request(&circuit_breaker, false, 1000));
+        assert!(request(&circuit_breaker, true, 1010));
+
+        assert!(circuit_breaker.acquire(ORIGIN, 1020));
+    }
+
+    #[test]
+    fn test_half_open() {
+        let circuit_breaker = circuit_breaker();
+        for now in 0..4 {
+            request(&circuit_breaker, true, now);
+        }
+
+        // a single trial request is let through after the cooldown
+        assert!(!circuit_breaker.acquire(ORIGIN, 400));
+        assert!(circuit_breaker.acquire(ORIGIN, 600));
+        assert!(!circuit_breaker.acquire(ORIGIN, 610));
+
+        // a failed trial opens the circuit again
+        circuit_breaker.record(ORIGIN, true, 620);
+        assert!(!circuit_breaker.acquire(ORIGIN, 1000));
+
+        // a successful trial closes it
+        assert!(request(&circuit_breaker, false, 1120));
+        assert!(circuit_breaker.acquire(ORIGIN, 1130));
+        assert!(circuit_breaker.acquire(ORIGIN, 1140));
+    }
+
+    #[test]
+    fn test_is_failure() {
+        let status = |status: &StatusCode| *status;
+        let error: anyhow::Result<StatusCode> = Err(anyhow::anyhow!("timeout"));
+
+        assert!(is_failure(&Ok(StatusCode::BAD_GATEWAY), status));
+        assert!(!is_failure(&Ok(StatusCode::NOT_FOUND), status));
+        assert!(is_failure(&error, status));
+    }
+
+    #[tokio::test]
+    async fn test_clears_cache_of_client() {
+        let http = Arc::new(CachedHttp::default());
+        let circuit_breaker = CircuitBreakerHttp::new(http.clone(), Arc::new(circuit_breaker()));
+
+        circuit_breaker.clear_cache().await.unwrap();
+
+        assert_eq!(http.purges.load(Ordering::SeqCst), 1);
+    }
+}
@@ -16,8 +16,8 @@ use tailcall_valid::{Valid, Validator};
 use super::directive::Directive;
 use super::from_document::from_document;
 use super::{
-    AddField, Alias, Cache, Call, Discriminate, Expr, GraphQL, Grpc, Http, Link, Modify, Omit,
-    Protected, Resolver, Server, Telemetry, Upstream, JS,
+    AddField, Alias, Cache, Call, Cost, Discriminate, Expr, GraphQL, Grpc, Http, Invalidate, Link,
+    Modify, Omit, Protected, RateLimit, Resolver, Server, Sse, Telemetry, Upstream, JS,
 };
 use crate::core::config::npo::QueryPath;
 use crate::core::config::source::Source;
@@ -115,6 +115,10 @@ pub struct Type {
     #[serde(default)]
     pub protected: Option<Protected>,
     ///
+    /// Limits how often the fields of the type can be resolved by a client
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub rate_limit: Option<RateLimit>,
+    ///
     /// Apollo federation entity resolver.
     #[serde(flatten, default, skip_serializing_if = "is_default")]
     pub resolver: Option<Resolver>,
@@ -219,10 +223,25 @@ pub struct Field {
     #[serde(default)]
     pub protected: Option<Protected>,
 
+    ///
+    /// Limits how often the field can be resolved by a client
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub rate_limit: Option<RateLimit>,
+
+    ///
+    /// Purges the cached values with the given tags once the mutation succeeds
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub invalidate: Option<Invalidate>,
+
     ///
     /// Used to overwrite the default discrimination strategy
     pub discriminate: Option<Discriminate>,
 
+    ///
+    /// Sets the cost of the field used to compute the complexity of operations
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub cost: Option<Cost>,
+
     ///
     /// Resolver for the field
     #[serde(flatten, default, skip_serializing_if = "is_default")]
@@ -601,6 +620,29 @@ impl Config {
         all_types.difference(&used_types).cloned().collect()
     }
 
+    /// Gets the ids of the links referenced by the `tls` settings of
+    /// `@upstream`, `@http` and `@grpc`.
+    pub fn tls_link_ids(&self) -> HashSet<String> {
+        let resolvers = self
+            .types
+            .values()
+            .flat_map(|type_| type_.fields.values())
+            .filter_map(|field| match &field.resolver {
+                Some(Resolver::Http(http)) => http.tls.as_ref(),
+                Some(Resolver::Grpc(grpc)) => grpc.tls.as_ref(),
+                _ => None,
+            });
+
+        self.upstream
+            .tls
+            .iter()
+            .chain(resolvers)
+            .flat_map(|tls| [&tls.cert, &tls.key, &tls.ca])
+            .flatten()
+            .cloned()
+            .collect()
+    }
+
     /// Gets all the type names used in the schema.
     pub fn get_all_used_type_names(&self) -> HashSet<String> {
         let mut set = HashSet::new();
@@ -649,22 +691,27 @@ impl Config {
             .add_directive(Alias::directive_definition(generated_types))
             .add_directive(Cache::directive_definition(generated_types))
             .add_directive(Call::directive_definition(generated_types))
+            .add_directive(Cost::directive_definition(generated_types))
             .add_directive(Expr::directive_definition(generated_types))
             .add_directive(GraphQL::directive_definition(generated_types))
             .add_directive(Grpc::directive_definition(generated_types))
             .add_directive(Http::directive_definition(generated_types))
+            .add_directive(Invalidate::directive_definition(generated_types))
             .add_directive(JS::directive_definition(generated_types))
             .add_directive(Link::directive_definition(generated_types))
             .add_directive(Modify::directive_definition(generated_types))
             .add_directive(Omit::directive_definition(generated_types))
             .add_directive(Protected::directive_definition(generated_types))
+            .add_directive(RateLimit::directive_definition(generated_types))
             .add_directive(Server::directive_definition(generated_types))
+            .add_directive(Sse::directive_definition(generated_types))
             .add_directive(Telemetry::directive_definition(generated_types))
             .add_directive(Upstream::directive_definition(generated_types))
             .add_directive(Discriminate::directive_definition(generated_types))
             .add_input(GraphQL::input_definition())
             .add_input(Grpc::input_definition())
             .add_input(Http::input_definition())
+            .add_input(Sse::input_definition())
             .add_input(Expr::input_definition())
             .add_input(JS::input_definition())
             .add_input(Modify::input_definition())
@@ -686,6 +733,11 @@ pub enum Encoding {
     #[default]
     ApplicationJson,
     ApplicationXWwwFormUrlencoded,
+    MultipartFormData,
+    ApplicationXml,
+    TextPlain,
+    ApplicationMsgpack,
+    ApplicationProtobuf,
 }
 
 #[cfg(test)]
@@ -4,6 +4,7 @@ use std::ops::Deref;
 use jsonwebtoken::jwk::JwkSet;
 use prost_reflect::prost_types::{FileDescriptorProto, FileDescriptorSet};
 use rustls_pki_types::{CertificateDer, PrivateKeyDer};
+use serde::Deserialize;
 use tailcall_valid::{Valid, Validator};
 
 use crate::core::config::Config;
@@ -112,6 +113,12 @@ impl Clone for PrivateKey {
     }
 }
 
+impl PartialEq for PrivateKey {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.secret_der() == other.0.secret_der()
+    }
+}
+
 impl From<PrivateKeyDer<'static>> for PrivateKey {
     fn from(value: PrivateKeyDer<'static>) -> Self {
         Self(value)
@@ -124,6 +131,70 @@ impl PrivateKey {
     }
 }
 
+/// The CA certificates that client certificates are verified against, read
+/// from a `ClientCa` link, along with the patterns set in the `meta` of the
+/// link that the subject and a SAN of the certificates have to match.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ClientCa {
+    #[serde(skip)]
+    pub certs: Vec<CertificateDer<'static>>,
+    pub subject: Option<String>,
+    pub san: Option<String>,
+}
+
+/// The introspection endpoint of an OAuth2 authorization server, read from
+/// the `src` of an `Introspection` link, along with the settings set in the
+/// `meta` of the link: the credentials to authenticate to the endpoint with,
+/// whose secret can be a template like `{{.env.CLIENT_SECRET}}`, the issuer
+/// and audiences the active tokens have to carry, and the longest time, in
+/// seconds, the active tokens are cached for.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "camelCase")]
+pub struct Introspection {
+    #[serde(skip)]
+    pub url: String,
+    pub client_id: Option<String>,
+    pub client_secret: Option<String>,
+    pub issuer: Option<String>,
+    pub audiences: Vec<String>,
+    pub max_age: Option<u64>,
+}
+
+/// The API keys read from an `ApiKeys` link, along with the settings set in
+/// the `meta` of the link: the header or the query parameter the keys are
+/// read from, and the scopes the keys have to be granted.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ApiKeys {
+    #[serde(skip)]
+    pub keys: Vec<ApiKey>,
+    pub header: Option<String>,
+    pub query: Option<String>,
+    pub scopes: Vec<String>,
+}
+
+impl ApiKeys {
+    /// The header the keys are read from, which is `X-API-Key` unless the
+    /// keys are read from a query parameter
+    pub fn header(&self) -> Option<&str> {
+        self.header
+            .as_deref()
+            .or(self.query.is_none().then_some("X-API-Key"))
+    }
+}
+
+/// An API key listed by an `ApiKeys` link, with the hex encoded SHA-256 hash
+/// of the key, the name of the client it was issued to, and its scopes.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ApiKey {
+    pub name: String,
+    pub hash: String,
+    #[serde(default)]
+    pub scopes: Vec<String>,
+}
+
 /// Extensions are meta-information required before we can generate the
 /// blueprint. Typically, this information cannot be inferred without performing
 /// an IO operation, i.e., reading a file, making an HTTP call, etc.
@@ -141,12 +212,28 @@ pub struct Extensions {
     /// Contains the key used on HTTP2 with TLS
     pub keys: Vec<PrivateKey>,
 
+    /// Contains the PEM files of the certificates and keys referenced by the
+    /// `tls` settings of the upstreams
+    pub tls: Vec<Content<String>>,
+
     /// Contains the endpoints
     pub endpoint_set: EndpointSet<Unchecked>,
 
+    /// Contains the GraphQL documents loaded from the operation links
+    pub operations: Vec<Content<String>>,
+
     pub htpasswd: Vec<Content<String>>,
 
     pub jwks: Vec<Content<JwkSet>>,
+
+    /// Contains the CAs that verify the certificates of the clients
+    pub client_ca: Vec<Content<ClientCa>>,
+
+    /// Contains the endpoints that introspect the tokens of the clients
+    pub introspection: Vec<Content<Introspection>>,
+
+    /// Contains the API keys of the clients
+    pub api_keys: Vec<Content<ApiKeys>>,
 }
 
 impl Extensions {
@@ -162,7 +249,11 @@ impl Extensions {
     }
 
     pub fn has_auth(&self) -> bool {
-        !self.htpasswd.is_empty() || !self.jwks.is_empty()
+        !self.htpasswd.is_empty()
+            || !self.jwks.is_empty()
+            || !self.client_ca.is_empty()
+            || !self.introspection.is_empty()
+            || !self.api_keys.is_empty()
     }
 }
 
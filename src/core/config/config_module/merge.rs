@@ -100,7 +100,10 @@ impl Contravariant for Field {
                 cache: self.cache.merge_right(other.cache),
                 default_value: self.default_value.or(other.default_value),
                 protected: self.protected.merge_right(other.protected),
+                rate_limit: self.rate_limit.merge_right(other.rate_limit),
+                invalidate: self.invalidate.merge_right(other.invalidate),
                 discriminate: self.discriminate.merge_right(other.discriminate),
+                cost: self.cost.merge_right(other.cost),
                 resolver: self.resolver.merge_right(other.resolver),
                 directives: self.directives.merge_right(other.directives),
             })
@@ -122,7 +125,10 @@ impl Covariant for Field {
                 cache: self.cache.merge_right(other.cache),
                 default_value: self.default_value.or(other.default_value),
                 protected: self.protected.merge_right(other.protected),
+                rate_limit: self.rate_limit.merge_right(other.rate_limit),
+                invalidate: self.invalidate.merge_right(other.invalidate),
                 discriminate: self.discriminate.merge_right(other.discriminate),
+                cost: self.cost.merge_right(other.cost),
                 resolver: self.resolver.merge_right(other.resolver),
                 directives: self.directives.merge_right(other.directives),
             })
@@ -139,6 +145,7 @@ impl Contravariant for Type {
             implements: self.implements.merge_right(other.implements),
             cache: self.cache.merge_right(other.cache),
             protected: self.protected.merge_right(other.protected),
+            rate_limit: self.rate_limit.merge_right(other.rate_limit),
             resolver: self.resolver.merge_right(other.resolver),
             directives: self.directives.merge_right(other.directives),
         })
@@ -155,6 +162,7 @@ impl Covariant for Type {
             implements: self.implements.merge_right(other.implements),
             cache: self.cache.merge_right(other.cache),
             protected: self.protected.merge_right(other.protected),
+            rate_limit: self.rate_limit.merge_right(other.rate_limit),
             resolver: self.resolver.merge_right(other.resolver),
             directives: self.directives.merge_right(other.directives),
         })
@@ -3,6 +3,8 @@ use std::num::NonZeroU64;
 use serde::{Deserialize, Serialize};
 use tailcall_macros::{DirectiveDefinition, InputDefinition, MergeRight};
 
+use crate::core::is_default;
+
 #[derive(
     Clone,
     Debug,
@@ -24,4 +26,52 @@ pub struct Cache {
     /// Specifies the duration, in milliseconds, of how long the value has to be
     /// stored in the cache.
     pub max_age: NonZeroU64,
+
+    /// Specifies the duration, in milliseconds, an expired value is still
+    /// served for while it's refreshed in the background.
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub stale_while_revalidate: Option<NonZeroU64>,
+
+    /// Specifies the duration, in milliseconds, an expired value is still
+    /// served for when refreshing it fails.
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub stale_if_error: Option<NonZeroU64>,
+
+    /// A mustache template rendering the key of the cached value, used
+    /// instead of the request made to fetch it, e.g. `{{.args.id}}`. The
+    /// headers of the request and the claims of its token are available as
+    /// `{{.headers}}` and `{{.claims}}`.
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub key: Option<String>,
+
+    /// Paths of the values the cached value varies with on top of its key,
+    /// starting with `args`, `headers`, `claims` or `value`, e.g.
+    /// `headers.x-tenant` or `claims.org`.
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub vary_by: Vec<String>,
+
+    /// Caches the value separately for each client, identified by the
+    /// subject of its token or of its certificate, and marks the response as
+    /// private. The values fetched for anonymous clients aren't cached.
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub private: bool,
+
+    /// Tags of the cached value, mustache templates rendered with the
+    /// arguments and the value returned by the resolver as `{{.value}}`, e.g.
+    /// `user:{{.args.id}}`. The value is purged from the cache when one of its
+    /// tags is invalidated by `@invalidate`.
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub tags: Vec<String>,
+}
+
+impl Cache {
+    /// How long the values are kept in the cache, until they can't be served
+    /// stale anymore
+    pub fn ttl(&self) -> NonZeroU64 {
+        let stale = self
+            .stale_while_revalidate
+            .max(self.stale_if_error)
+            .map_or(0, NonZeroU64::get);
+        self.max_age.saturating_add(stale)
+    }
 }
@@ -1,4 +1,5 @@
 use std::collections::BTreeMap;
+use std::num::NonZeroU64;
 
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
@@ -52,4 +53,10 @@ pub struct Call {
     /// with APIs that expect unique results for identical inputs, such as
     /// nonce-based APIs.
     pub dedupe: Option<bool>,
+
+    #[serde(default, skip_serializing_if = "is_default")]
+    /// The time in milliseconds after which each request made by the steps is
+    /// abandoned and the field resolves to an error. It replaces the `timeout`
+    /// of the operators of the called fields.
+    pub timeout: Option<NonZeroU64>,
 }
@@ -0,0 +1,25 @@
+use serde::{Deserialize, Serialize};
+use tailcall_macros::{DirectiveDefinition, MergeRight};
+
+#[derive(
+    Serialize,
+    Deserialize,
+    Clone,
+    Debug,
+    PartialEq,
+    Eq,
+    schemars::JsonSchema,
+    DirectiveDefinition,
+    MergeRight,
+)]
+#[directive_definition(locations = "FieldDefinition")]
+#[serde(deny_unknown_fields)]
+/// The `@cost` directive sets the weight of a field when computing the
+/// complexity of an operation against the `complexity` limit of the
+/// `queryLimits` configured on `@server`. By default fields cost `1` and fields
+/// that make IO calls cost `10`.
+pub struct Cost {
+    /// The cost added to the complexity of the operation every time the field
+    /// is selected.
+    pub weight: usize,
+}
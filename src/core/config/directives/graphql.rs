@@ -1,7 +1,9 @@
+use std::num::NonZeroU64;
+
 use serde::{Deserialize, Serialize};
 use tailcall_macros::{DirectiveDefinition, InputDefinition};
 
-use crate::core::config::KeyValue;
+use crate::core::config::{KeyValue, Retry};
 use crate::core::is_default;
 
 #[derive(
@@ -56,4 +58,14 @@ pub struct GraphQL {
     /// with APIs that expect unique results for identical inputs, such as
     /// nonce-based APIs.
     pub dedupe: Option<bool>,
+
+    #[serde(default, skip_serializing_if = "is_default")]
+    /// The policy used to retry the requests made by the `@graphQL` operator
+    /// when they fail. It replaces the `retry` policy set on `@upstream`.
+    pub retry: Option<Retry>,
+
+    #[serde(default, skip_serializing_if = "is_default")]
+    /// The time in milliseconds after which a call to the `@graphQL` operator
+    /// is abandoned and the field resolves to an error.
+    pub timeout: Option<NonZeroU64>,
 }
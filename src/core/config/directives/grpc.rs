@@ -1,8 +1,10 @@
+use std::num::NonZeroU64;
+
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use tailcall_macros::{DirectiveDefinition, InputDefinition};
 
-use crate::core::config::KeyValue;
+use crate::core::config::{KeyValue, Retry, Tls};
 use crate::core::is_default;
 
 #[derive(
@@ -58,6 +60,22 @@ pub struct Grpc {
     /// nonce-based APIs.
     pub dedupe: Option<bool>,
 
+    #[serde(default, skip_serializing_if = "is_default")]
+    /// The policy used to retry the requests made by the `@grpc` operator
+    /// when they fail. It replaces the `retry` policy set on `@upstream`.
+    pub retry: Option<Retry>,
+
+    #[serde(default, skip_serializing_if = "is_default")]
+    /// The time in milliseconds after which a call to the `@grpc` operator is
+    /// abandoned and the field resolves to an error.
+    pub timeout: Option<NonZeroU64>,
+
+    #[serde(default, skip_serializing_if = "is_default")]
+    /// The client certificate and certificate authorities used for the TLS
+    /// connections of the `@grpc` operator. It replaces the `tls` settings of
+    /// `@upstream`.
+    pub tls: Option<Tls>,
+
     /// You can use `select` with mustache syntax to re-construct the directives
     /// response to the desired format. This is useful when data are deeply
     /// nested or want to keep specific fields only from the response.
@@ -1,8 +1,10 @@
+use std::num::NonZeroU64;
+
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use tailcall_macros::{DirectiveDefinition, InputDefinition};
 
-use crate::core::config::{Encoding, KeyValue, URLQuery};
+use crate::core::config::{Encoding, KeyValue, Retry, Tls, URLQuery};
 use crate::core::http::Method;
 use crate::core::is_default;
 use crate::core::json::JsonSchema;
@@ -45,10 +47,29 @@ pub struct Http {
 
     #[serde(default, skip_serializing_if = "is_default")]
     /// The `encoding` parameter specifies the encoding of the request body. It
-    /// can be `ApplicationJson` or `ApplicationXWwwFormUrlEncoded`. @default
-    /// `ApplicationJson`.
+    /// can be `ApplicationJson`, `ApplicationXWwwFormUrlEncoded`,
+    /// `MultipartFormData`, `ApplicationXml`, `TextPlain`, `ApplicationMsgpack`
+    /// or `ApplicationProtobuf`. With `MultipartFormData` the body has to be an
+    /// object, its `Upload` values are sent as files and the other values as
+    /// text fields. With `ApplicationXml` a body that is an object with a
+    /// single key is converted to XML, the keys prefixed with `@` are
+    /// attributes, `#text` is the text of the element and the other keys have
+    /// to be valid XML names, while the values rendered into any other body
+    /// are escaped. The responses of the last four encodings are decoded in
+    /// the same format: XML elements become objects, with their attributes as
+    /// fields and their text under `text` when they have attributes or
+    /// children, text becomes a string and protobuf is decoded with the output
+    /// message of `protobufMethod`. The responses of the others are decoded as
+    /// JSON. @default `ApplicationJson`.
     pub encoding: Encoding,
 
+    #[serde(rename = "protobufMethod", default, skip_serializing_if = "is_default")]
+    /// The method, as `package.Service.Method`, of a service defined in the
+    /// files linked with `@link(type: Protobuf)`. Its input message encodes
+    /// the body and its output message decodes the response when the
+    /// `encoding` is `ApplicationProtobuf`.
+    pub protobuf_method: Option<String>,
+
     #[serde(rename = "batchKey", default, skip_serializing_if = "is_default")]
     /// The `batchKey` dictates the path Tailcall will follow to group the returned items from the batch request. For more details please refer out [n + 1 guide](https://tailcall.run/docs/guides/n+1#solving-using-batching).
     pub batch_key: Vec<String>,
@@ -91,6 +112,22 @@ pub struct Http {
     /// nonce-based APIs.
     pub dedupe: Option<bool>,
 
+    #[serde(default, skip_serializing_if = "is_default")]
+    /// The policy used to retry the requests made by the `@http` operator
+    /// when they fail. It replaces the `retry` policy set on `@upstream`.
+    pub retry: Option<Retry>,
+
+    #[serde(default, skip_serializing_if = "is_default")]
+    /// The time in milliseconds after which a call to the `@http` operator is
+    /// abandoned and the field resolves to an error.
+    pub timeout: Option<NonZeroU64>,
+
+    #[serde(default, skip_serializing_if = "is_default")]
+    /// The client certificate and certificate authorities used for the TLS
+    /// connections of the `@http` operator. It replaces the `tls` settings of
+    /// `@upstream`.
+    pub tls: Option<Tls>,
+
     /// You can use `select` with mustache syntax to re-construct the directives
     /// response to the desired format. This is useful when data are deeply
     /// nested or want to keep specific fields only from the response.
@@ -0,0 +1,25 @@
+use serde::{Deserialize, Serialize};
+use tailcall_macros::{DirectiveDefinition, MergeRight};
+
+#[derive(
+    Clone,
+    Debug,
+    PartialEq,
+    Deserialize,
+    Serialize,
+    Eq,
+    schemars::JsonSchema,
+    MergeRight,
+    DirectiveDefinition,
+)]
+#[directive_definition(locations = "FieldDefinition")]
+/// The @invalidate operator purges the values cached by `@cache` with one of
+/// the `tags` once the mutation field it is applied to succeeds, so that the
+/// following reads don't return stale values until their `maxAge` expires.
+#[serde(rename_all = "camelCase")]
+#[serde(deny_unknown_fields)]
+pub struct Invalidate {
+    /// The tags to invalidate, mustache templates like the tags of `@cache`,
+    /// e.g. `user:{{.args.id}}`. `{{.value}}` is the result of the mutation.
+    pub tags: Vec<String>,
+}
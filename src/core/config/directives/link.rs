@@ -25,6 +25,9 @@ pub enum LinkType {
     Operation,
     Htpasswd,
     Jwks,
+    ApiKeys,
+    ClientCa,
+    Introspection,
     Grpc,
 }
 
@@ -52,6 +55,7 @@ pub struct Link {
     ///
     /// The source of the link. It can be a URL or a path to a file.
     /// If a path is provided, it is relative to the file that imports the link.
+    /// For an `Introspection` link, it's the URL of the introspection endpoint.
     #[serde(default, skip_serializing_if = "is_default")]
     pub src: String,
     ///
@@ -2,18 +2,22 @@ mod add_field;
 mod alias;
 mod cache;
 mod call;
+mod cost;
 mod discriminate;
 mod expr;
 mod federation;
 mod graphql;
 mod grpc;
 mod http;
+mod invalidate;
 mod js;
 mod link;
 mod modify;
 mod omit;
 mod protected;
+mod rate_limit;
 mod server;
+mod sse;
 mod telemetry;
 mod upstream;
 
@@ -21,17 +25,21 @@ pub use add_field::*;
 pub use alias::*;
 pub use cache::*;
 pub use call::*;
+pub use cost::*;
 pub use discriminate::*;
 pub use expr::*;
 pub use federation::*;
 pub use graphql::*;
 pub use grpc::*;
 pub use http::*;
+pub use invalidate::*;
 pub use js::*;
 pub use link::*;
 pub use modify::*;
 pub use omit::*;
 pub use protected::*;
+pub use rate_limit::*;
 pub use server::*;
+pub use sse::*;
 pub use telemetry::*;
 pub use upstream::*;
@@ -0,0 +1,63 @@
+use std::num::NonZeroU64;
+
+use serde::{Deserialize, Serialize};
+use tailcall_macros::{DirectiveDefinition, MergeRight};
+
+use crate::core::is_default;
+
+#[derive(
+    Clone,
+    Debug,
+    PartialEq,
+    Deserialize,
+    Serialize,
+    Eq,
+    schemars::JsonSchema,
+    MergeRight,
+    DirectiveDefinition,
+)]
+#[directive_definition(locations = "Object,FieldDefinition")]
+/// The @rateLimit operator limits how many times the field, or the fields with
+/// a resolver of the type it is applied to, can be resolved by the same client
+/// over a period of time. Calls over the limit fail with an error that tells
+/// the client when to retry. A `@rateLimit` on a field takes precedence over
+/// the one on its type.
+#[serde(rename_all = "camelCase")]
+#[serde(deny_unknown_fields)]
+pub struct RateLimit {
+    /// The number of calls allowed for a client within the `window`.
+    pub requests: NonZeroU64,
+
+    /// The duration of the window, in milliseconds.
+    pub window: NonZeroU64,
+
+    /// A mustache template that identifies the client the limit applies to,
+    /// e.g. `{{.headers.x-api-key}}` or `{{.claims.sub}}`. Besides the usual
+    /// context, `{{.client.ip}}` is the address of the client and
+    /// `{{.claims}}` holds the claims of the token verified for the request.
+    /// On runtimes that don't know the address of the client, like Cloudflare
+    /// and Lambda, it's read from the `CF-Connecting-IP` header or from the
+    /// last address of `X-Forwarded-For`. Headers have to be listed in
+    /// `allowedHeaders` of `@upstream`. Calls are rejected when their client
+    /// can't be identified, i.e. a value of the template or the address of the
+    /// client is missing. @default the address of the client.
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub key: Option<String>,
+
+    /// The algorithm used to count the calls of a client. `SlidingWindow`
+    /// weighs the calls of the previous window by how much it overlaps with
+    /// the last `window` milliseconds, smoothing bursts at window boundaries.
+    /// `TokenBucket` allows bursts of up to `requests` calls, refilled at a
+    /// rate of `requests` per `window`. @default `SlidingWindow`.
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub algorithm: RateLimitAlgorithm,
+}
+
+#[derive(
+    Deserialize, Serialize, Debug, PartialEq, Eq, Clone, Default, schemars::JsonSchema, MergeRight,
+)]
+pub enum RateLimitAlgorithm {
+    #[default]
+    SlidingWindow,
+    TokenBucket,
+}
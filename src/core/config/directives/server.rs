@@ -6,9 +6,10 @@ use serde::{Deserialize, Serialize};
 use tailcall_macros::DirectiveDefinition;
 
 use crate::core::config::headers::Headers;
-use crate::core::config::{merge_key_value_vecs, KeyValue};
+use crate::core::config::{merge_key_value_vecs, ConfigReaderContext, KeyValue};
 use crate::core::is_default;
 use crate::core::macros::MergeRight;
+use crate::core::mustache::Mustache;
 
 #[derive(
     Serialize,
@@ -35,6 +36,12 @@ pub struct Server {
     #[serde(default, skip_serializing_if = "is_default", rename = "enableJIT")]
     pub enable_jit: Option<bool>,
 
+    #[serde(default, skip_serializing_if = "is_default")]
+    /// `admin` enables the admin API, served under the `admin` route, to
+    /// inspect the running server and purge its caches. Its requests are
+    /// authenticated with the `token`.
+    pub admin: Option<Admin>,
+
     #[serde(default, skip_serializing_if = "is_default")]
     /// `apolloTracing` exposes GraphQL query performance data, including
     /// execution time of queries and individual resolvers.
@@ -46,6 +53,13 @@ pub struct Server {
     /// debugging. Use judiciously. @default `false`.
     pub batch_requests: Option<bool>,
 
+    #[serde(default, skip_serializing_if = "is_default")]
+    /// `compression` compresses the responses with gzip, brotli or zstd,
+    /// depending on the `Accept-Encoding` header of the requests. Request
+    /// bodies compressed with any of them are decompressed, up to
+    /// `maxDecompressedSize`.
+    pub compression: Option<Compression>,
+
     #[serde(default, skip_serializing_if = "is_default")]
     /// `headers` contains key-value pairs that are included as default headers
     /// in server responses, allowing for consistent header management across
@@ -72,6 +86,14 @@ pub struct Server {
     #[serde(default, skip_serializing_if = "is_default")]
     pub enable_federation: Option<bool>,
 
+    #[serde(default, skip_serializing_if = "is_default")]
+    /// `maxUploadSize` is the maximum size in bytes of the requests sent with
+    /// the GraphQL multipart request spec, files included. Larger requests are
+    /// rejected. These requests also require an `Apollo-Require-Preflight` or
+    /// an `X-Apollo-Operation-Name` header, so that browsers don't send them
+    /// across origins without a CORS preflight. @default `10485760`.
+    pub max_upload_size: Option<u64>,
+
     #[serde(default, skip_serializing_if = "is_default")]
     /// `pipelineFlush` allows to control flushing behavior of the server
     /// pipeline.
@@ -81,12 +103,28 @@ pub struct Server {
     /// `port` sets the Tailcall running port. @default `8000`.
     pub port: Option<u16>,
 
+    #[serde(default, skip_serializing_if = "is_default")]
+    /// `queryLimits` restricts the shape and the cost of the operations that
+    /// are executed. Operations exceeding any of the limits are rejected before
+    /// execution. The introspection fields `__schema` and `__type` aren't
+    /// counted, so that introspection queries are exempt from the limits.
+    /// Requires `enableJIT`.
+    pub query_limits: Option<QueryLimits>,
+
     #[serde(default, skip_serializing_if = "is_default")]
     /// `queryValidation` checks incoming GraphQL queries against the schema,
     /// preventing errors from invalid queries. Can be disabled for performance.
     /// @default `false`.
     pub query_validation: Option<bool>,
 
+    #[serde(default, skip_serializing_if = "is_default")]
+    /// `responseCache` caches the responses of whole queries, keyed by the
+    /// operation, its variables and the `varyHeaders`, for the smallest
+    /// `maxAge` of the `@cache` directives of the operation. Queries selecting
+    /// a field without `@cache`, protected queries and private responses
+    /// aren't cached. Requires `enableJIT`.
+    pub response_cache: Option<ResponseCache>,
+
     #[serde(default, skip_serializing_if = "is_default")]
     /// `responseValidation` Tailcall automatically validates responses from
     /// upstream services using inferred schema. @default `false`.
@@ -101,6 +139,14 @@ pub struct Server {
     /// `showcase` enables the /showcase/graphql endpoint.
     pub showcase: Option<bool>,
 
+    #[serde(default, skip_serializing_if = "is_default")]
+    /// `trustedDocuments` only allows the execution of the operations loaded
+    /// through links of type `Operation`. Clients can either send the document
+    /// itself or reference it by its sha256 hash, using the `persistedQuery`
+    /// extension, or by its operation name. Every other document is rejected.
+    /// Requires `enableJIT`. @default `false`.
+    pub trusted_documents: Option<bool>,
+
     #[serde(default, skip_serializing_if = "is_default")]
     #[merge_right(merge_right_fn = "merge_right_vars")]
     /// This configuration defines local variables for server operations. Useful
@@ -119,11 +165,11 @@ pub struct Server {
 
     #[serde(default, skip_serializing_if = "is_default")]
     /// `routes` allows customization of server endpoint paths.
-    /// It provides options to change the default paths for status and GraphQL
-    /// endpoints. Default values are:
+    /// It provides options to change the default paths for status, GraphQL
+    /// and admin endpoints. Default values are:
     /// - status: "/status"
-    /// - graphQL: "/graphql" If not specified, these default values will be
-    ///   used.
+    /// - graphQL: "/graphql"
+    /// - admin: "/admin" If not specified, these default values will be used.
     pub routes: Option<Routes>,
 }
 
@@ -133,6 +179,8 @@ pub struct Routes {
     status: String,
     #[serde(rename = "graphQL", default = "default_graphql")]
     graphql: String,
+    #[serde(default = "default_admin")]
+    admin: String,
 }
 
 fn default_status() -> String {
@@ -143,19 +191,31 @@ fn default_graphql() -> String {
     "/graphql".into()
 }
 
+fn default_admin() -> String {
+    "/admin".into()
+}
+
 impl Default for Routes {
     fn default() -> Self {
-        Self { status: "/status".into(), graphql: "/graphql".into() }
+        Self {
+            status: "/status".into(),
+            graphql: "/graphql".into(),
+            admin: "/admin".into(),
+        }
     }
 }
 
 impl Routes {
     pub fn with_status<T: Into<String>>(self, status: T) -> Self {
-        Self { graphql: self.graphql, status: status.into() }
+        Self { status: status.into(), ..self }
     }
 
     pub fn with_graphql<T: Into<String>>(self, graphql: T) -> Self {
-        Self { status: self.status, graphql: graphql.into() }
+        Self { graphql: graphql.into(), ..self }
+    }
+
+    pub fn with_admin<T: Into<String>>(self, admin: T) -> Self {
+        Self { admin: admin.into(), ..self }
     }
 }
 
@@ -164,12 +224,126 @@ fn merge_right_vars(mut left: Vec<KeyValue>, right: Vec<KeyValue>) -> Vec<KeyVal
     left
 }
 
+#[derive(
+    Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq, schemars::JsonSchema, MergeRight,
+)]
+#[serde(rename_all = "camelCase")]
+pub struct Admin {
+    /// `token` is the bearer token the requests to the admin API are sent
+    /// with, in their `Authorization` header. It can be read from the
+    /// environment with `{{.env.NAME}}`.
+    pub token: String,
+}
+
+impl Admin {
+    pub fn render_mustache(&mut self, reader_ctx: &ConfigReaderContext) {
+        self.token = Mustache::parse(&self.token).render(reader_ctx);
+    }
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, schemars::JsonSchema, MergeRight)]
 #[serde(rename_all = "camelCase")]
 pub struct ScriptOptions {
     pub timeout: Option<u64>,
 }
 
+#[derive(
+    Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq, schemars::JsonSchema, MergeRight,
+)]
+#[serde(rename_all = "camelCase")]
+pub struct QueryLimits {
+    #[serde(default, skip_serializing_if = "is_default")]
+    /// `depth` is the maximum nesting of selections in an operation.
+    pub depth: Option<usize>,
+
+    #[serde(default, skip_serializing_if = "is_default")]
+    /// `fields` is the maximum number of fields selected by an operation,
+    /// including the nested ones.
+    pub fields: Option<usize>,
+
+    #[serde(default, skip_serializing_if = "is_default")]
+    /// `aliases` is the maximum number of aliased fields in an operation.
+    pub aliases: Option<usize>,
+
+    #[serde(default, skip_serializing_if = "is_default")]
+    /// `complexity` is the maximum total cost of the fields selected by an
+    /// operation. Fields cost `1` unless they make IO calls, which cost `10`,
+    /// or their cost is set with the `@cost` directive.
+    pub complexity: Option<usize>,
+}
+
+impl QueryLimits {
+    pub fn is_empty(&self) -> bool {
+        self.depth.is_none()
+            && self.fields.is_none()
+            && self.aliases.is_none()
+            && self.complexity.is_none()
+    }
+}
+
+#[derive(
+    Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq, schemars::JsonSchema, MergeRight,
+)]
+#[serde(rename_all = "camelCase")]
+pub struct Compression {
+    #[serde(default, skip_serializing_if = "is_default")]
+    /// `minSize` is the size in bytes under which responses are sent
+    /// uncompressed. @default `1024`.
+    pub min_size: Option<usize>,
+
+    #[serde(default, skip_serializing_if = "is_default")]
+    /// `algorithms` are the encodings the responses can be compressed with,
+    /// in order of preference when a client accepts several of them equally.
+    /// @default `[Zstd, Brotli, Gzip]`.
+    pub algorithms: Option<Vec<CompressionAlgorithm>>,
+
+    #[serde(default, skip_serializing_if = "is_default")]
+    /// `maxDecompressedSize` is the maximum size in bytes of the body of a
+    /// compressed request once decompressed. Larger requests are rejected.
+    /// @default `10485760`.
+    pub max_decompressed_size: Option<u64>,
+}
+
+impl Compression {
+    pub fn get_min_size(&self) -> usize {
+        self.min_size.unwrap_or(1024)
+    }
+
+    pub fn get_max_decompressed_size(&self) -> u64 {
+        self.max_decompressed_size.unwrap_or(10 * 1024 * 1024)
+    }
+
+    pub fn get_algorithms(&self) -> Vec<CompressionAlgorithm> {
+        self.algorithms.clone().unwrap_or_else(|| {
+            vec![
+                CompressionAlgorithm::Zstd,
+                CompressionAlgorithm::Brotli,
+                CompressionAlgorithm::Gzip,
+            ]
+        })
+    }
+}
+
+#[derive(
+    Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, schemars::JsonSchema, MergeRight,
+)]
+pub enum CompressionAlgorithm {
+    Gzip,
+    Brotli,
+    Zstd,
+}
+
+#[derive(
+    Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq, schemars::JsonSchema, MergeRight,
+)]
+#[serde(rename_all = "camelCase")]
+pub struct ResponseCache {
+    #[serde(default, skip_serializing_if = "is_default")]
+    /// `varyHeaders` are the request headers the cached responses vary with,
+    /// like the ones selecting a tenant or a locale. @default `[]`.
+    pub vary_headers: Vec<String>,
+}
+
 #[derive(
     Deserialize, Serialize, Debug, PartialEq, Eq, Clone, Default, schemars::JsonSchema, MergeRight,
 )]
@@ -219,6 +393,10 @@ impl Server {
     pub fn enable_batch_requests(&self) -> bool {
         self.batch_requests.unwrap_or(false)
     }
+    pub fn enable_trusted_documents(&self) -> bool {
+        self.trusted_documents.unwrap_or(false)
+    }
+
     pub fn enable_showcase(&self) -> bool {
         self.showcase.unwrap_or(false)
     }
@@ -258,6 +436,10 @@ impl Server {
         self.version.unwrap_or(HttpVersion::HTTP1)
     }
 
+    pub fn get_max_upload_size(&self) -> u64 {
+        self.max_upload_size.unwrap_or(10 * 1024 * 1024)
+    }
+
     pub fn get_pipeline_flush(&self) -> bool {
         self.pipeline_flush.unwrap_or(true)
     }
@@ -266,6 +448,10 @@ impl Server {
         self.enable_jit.unwrap_or(true)
     }
 
+    pub fn get_query_limits(&self) -> QueryLimits {
+        self.query_limits.clone().unwrap_or_default()
+    }
+
     pub fn get_routes(&self) -> Routes {
         self.routes.clone().unwrap_or_default()
     }
@@ -0,0 +1,41 @@
+use serde::{Deserialize, Serialize};
+use tailcall_macros::{DirectiveDefinition, InputDefinition};
+
+use crate::core::config::{KeyValue, URLQuery};
+use crate::core::is_default;
+
+#[derive(
+    Serialize,
+    Deserialize,
+    Clone,
+    Debug,
+    Default,
+    PartialEq,
+    Eq,
+    schemars::JsonSchema,
+    DirectiveDefinition,
+    InputDefinition,
+)]
+#[directive_definition(locations = "FieldDefinition")]
+#[serde(deny_unknown_fields)]
+/// The @sse operator indicates that a field of the subscription root type is
+/// backed by a stream of Server-Sent Events.
+///
+/// Tailcall keeps the connection to the upstream open and every `data` payload
+/// received on it is published as a new value of the field to its subscribers.
+pub struct Sse {
+    /// This refers to URL of the event stream.
+    pub url: String,
+
+    #[serde(default, skip_serializing_if = "is_default")]
+    /// The `headers` parameter allows you to customize the headers of the HTTP
+    /// request made by the `@sse` operator. It is used by specifying a
+    /// key-value map of header names and their values.
+    pub headers: Vec<KeyValue>,
+
+    #[serde(default, skip_serializing_if = "is_default")]
+    /// This represents the query parameters of the request. You can pass it as
+    /// a static object or use Mustache template for dynamic parameters. These
+    /// parameters will be added to the URL.
+    pub query: Vec<URLQuery>,
+}
@@ -1,4 +1,5 @@
 use std::collections::BTreeSet;
+use std::num::NonZeroU64;
 
 use derive_setters::Setters;
 use serde::{Deserialize, Serialize};
@@ -34,6 +35,158 @@ pub struct Proxy {
     pub url: String,
 }
 
+#[derive(
+    Serialize, Deserialize, PartialEq, Eq, Clone, Debug, Default, schemars::JsonSchema, MergeRight,
+)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+/// Controls how failed upstream requests are retried. Requests are retried
+/// when they fail to connect, time out or respond with one of the
+/// `statusCodes`, waiting an exponentially growing backoff between attempts.
+pub struct Retry {
+    #[serde(default, skip_serializing_if = "is_default")]
+    /// The maximum number of attempts made for a request, including the first
+    /// one. @default `3`.
+    pub max_attempts: Option<NonZeroU64>,
+
+    #[serde(default, skip_serializing_if = "is_default")]
+    /// The time in milliseconds to wait before the first retry. It doubles
+    /// with every further attempt. @default `100`.
+    pub initial_backoff: Option<u64>,
+
+    #[serde(default, skip_serializing_if = "is_default")]
+    /// The maximum time in milliseconds to wait between two attempts.
+    /// @default `5000`.
+    pub max_backoff: Option<u64>,
+
+    #[serde(default, skip_serializing_if = "is_default")]
+    /// When enabled, a random part of up to half of the backoff is subtracted
+    /// from it, so that clients failing at the same time don't retry all at
+    /// once. @default `true`.
+    pub jitter: Option<bool>,
+
+    #[serde(default, skip_serializing_if = "is_default")]
+    /// The response status codes that are retried. @default `[429, 502, 503,
+    /// 504]`.
+    pub status_codes: Option<BTreeSet<u16>>,
+
+    #[serde(default, skip_serializing_if = "is_default")]
+    /// When enabled, only idempotent requests are retried, that is HTTP
+    /// requests with the `GET`, `HEAD`, `OPTIONS`, `PUT`, `DELETE` or `TRACE`
+    /// methods and GraphQL or gRPC queries. @default `true`.
+    pub idempotent_only: Option<bool>,
+}
+
+#[derive(
+    Serialize, Deserialize, PartialEq, Eq, Clone, Debug, Default, schemars::JsonSchema, MergeRight,
+)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+/// Stops sending requests to an upstream origin that keeps failing. Once the
+/// share of failed requests to an origin reaches the `failureThreshold`, the
+/// circuit opens and requests fail immediately. After the `cooldown` a few
+/// trial requests are let through, closing the circuit again if they succeed.
+pub struct CircuitBreaker {
+    #[serde(default, skip_serializing_if = "is_default")]
+    /// The percentage of failed requests within the `window` at which the
+    /// circuit of an origin opens. Requests fail when they can't connect, time
+    /// out or respond with a 5xx status code. @default `50`.
+    pub failure_threshold: Option<u64>,
+
+    #[serde(default, skip_serializing_if = "is_default")]
+    /// The number of requests that have to be made to an origin within the
+    /// `window` before its failure rate is considered. @default `10`.
+    pub minimum_requests: Option<NonZeroU64>,
+
+    #[serde(default, skip_serializing_if = "is_default")]
+    /// The time in milliseconds over which the requests to an origin are
+    /// counted. @default `10000`.
+    pub window: Option<NonZeroU64>,
+
+    #[serde(default, skip_serializing_if = "is_default")]
+    /// The time in milliseconds for which an open circuit rejects requests
+    /// before letting trial requests through. @default `30000`.
+    pub cooldown: Option<u64>,
+
+    #[serde(default, skip_serializing_if = "is_default")]
+    /// The number of trial requests let through while the circuit is
+    /// half-open. @default `1`.
+    pub half_open_requests: Option<NonZeroU64>,
+}
+
+#[derive(
+    Serialize, Deserialize, PartialEq, Eq, Clone, Debug, Default, schemars::JsonSchema, MergeRight,
+)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+/// The client certificate presented to upstreams that require mutual TLS and
+/// the certificate authorities trusted to verify them. The values are the
+/// `id`s of `@link`s: `cert` and `ca` of `Cert` links and `key` of a `Key`
+/// link.
+pub struct Tls {
+    #[serde(default, skip_serializing_if = "is_default")]
+    /// The id of the `Cert` link with the client certificate chain. It
+    /// requires `key`.
+    pub cert: Option<String>,
+
+    #[serde(default, skip_serializing_if = "is_default")]
+    /// The id of the `Key` link with the private key of the client
+    /// certificate. It requires `cert`.
+    pub key: Option<String>,
+
+    #[serde(default, skip_serializing_if = "is_default")]
+    /// The id of the `Cert` link with the bundle of certificate authorities
+    /// trusted in addition to the system ones.
+    pub ca: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Eq, Clone, Debug, schemars::JsonSchema, MergeRight)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+/// A Redis server, or any server that speaks the Redis protocol, shared by all
+/// the instances of Tailcall.
+pub struct Redis {
+    /// The URL of the server, in the
+    /// `redis://[[username]:password@]host[:port][/database]` format.
+    pub url: String,
+
+    #[serde(default, skip_serializing_if = "is_default")]
+    /// The prefix of the keys written by Tailcall, so that several
+    /// configurations can share a server. It can't be empty, since purging the
+    /// cache removes the keys with the prefix. @default `tailcall:`.
+    pub prefix: Option<String>,
+}
+
+impl Redis {
+    pub fn get_prefix(&self) -> String {
+        self.prefix.clone().unwrap_or("tailcall:".to_string())
+    }
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Eq, Clone, Debug, schemars::JsonSchema, MergeRight)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+/// A directory where the cached values are kept, so that they survive the
+/// restarts of Tailcall.
+pub struct Disk {
+    /// The path of the directory, created if it doesn't exist.
+    pub path: String,
+
+    #[serde(default, skip_serializing_if = "is_default")]
+    /// The maximum size in bytes of the cache, the least recently used entries
+    /// being evicted once it's reached. @default `1073741824` (1 GiB).
+    pub max_size: Option<u64>,
+}
+
+impl Disk {
+    pub fn get_max_size(&self) -> u64 {
+        self.max_size.unwrap_or(1024 * 1024 * 1024)
+    }
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Eq, Clone, Debug, schemars::JsonSchema, MergeRight)]
+#[serde(rename_all = "camelCase")]
+/// The storage of the values cached with `@cache`.
+pub enum CacheStorage {
+    Redis(Redis),
+    Disk(Disk),
+}
+
 #[derive(
     Serialize,
     Deserialize,
@@ -73,6 +226,19 @@ pub struct Upstream {
     /// the batch).
     pub batch: Option<Batch>,
 
+    #[serde(default, skip_serializing_if = "is_default")]
+    /// The storage of the values cached with `@cache`. By default every
+    /// instance keeps its own cache in memory, with `redis` all the instances
+    /// share the same cache and with `disk` the cache, along with the
+    /// responses of `httpCache`, survives restarts.
+    pub cache: Option<CacheStorage>,
+
+    #[serde(default, skip_serializing_if = "is_default")]
+    /// Enables a circuit breaker for every upstream origin, failing requests
+    /// immediately while the origin is failing. The state of the circuits is
+    /// reported by the Prometheus exporter of `@telemetry`.
+    pub circuit_breaker: Option<CircuitBreaker>,
+
     #[serde(default, skip_serializing_if = "is_default")]
     /// The time in seconds that the connection will wait for a response before
     /// timing out.
@@ -121,6 +287,12 @@ pub struct Upstream {
     /// enabling custom routing and security policies.
     pub proxy: Option<Proxy>,
 
+    #[serde(default, skip_serializing_if = "is_default")]
+    /// The policy used to retry failed requests to upstream services. It can be
+    /// overridden for a resolver with the `retry` argument of `@http`, `@grpc`
+    /// and `@graphQL`. If not set, requests are not retried.
+    pub retry: Option<Retry>,
+
     #[serde(default, skip_serializing_if = "is_default")]
     /// The time in seconds between each TCP keep-alive message sent to maintain
     /// the connection.
@@ -128,9 +300,18 @@ pub struct Upstream {
 
     #[serde(default, skip_serializing_if = "is_default")]
     /// The maximum time in seconds that the connection will wait for a
-    /// response.
+    /// response. It's independent from the `timeout` of the `@http`, `@grpc`
+    /// and `@graphQL` operators, which only fails the field of the call while
+    /// the rest of the response still resolves.
     pub timeout: Option<u64>,
 
+    #[serde(default, skip_serializing_if = "is_default")]
+    /// The client certificate and certificate authorities used for the TLS
+    /// connections to upstream services, for those that require mutual TLS. It
+    /// can be overridden for a resolver with the `tls` argument of `@http` and
+    /// `@grpc`.
+    pub tls: Option<Tls>,
+
     #[serde(default, skip_serializing_if = "is_default")]
     /// The User-Agent header value to be used in HTTP requests. @default
     /// `Tailcall/1.0`
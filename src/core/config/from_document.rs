@@ -12,7 +12,9 @@ use indexmap::IndexMap;
 use tailcall_valid::{Valid, ValidationError, Validator};
 
 use super::directive::{to_directive, Directive};
-use super::{Alias, Discriminate, Resolver, Telemetry, FEDERATION_DIRECTIVES};
+use super::{
+    Alias, Cost, Discriminate, Invalidate, RateLimit, Resolver, Telemetry, FEDERATION_DIRECTIVES,
+};
 use crate::core::config::{
     self, Cache, Config, Enum, Link, Modify, Omit, Protected, RootSchema, Server, Union, Upstream,
     Variant,
@@ -241,11 +243,21 @@ where
     Resolver::from_directives(directives)
         .fuse(Cache::from_directives(directives.iter()))
         .fuse(to_fields(fields))
-        .fuse(Protected::from_directives(directives.iter()))
+        .fuse(
+            Protected::from_directives(directives.iter())
+                .zip(RateLimit::from_directives(directives.iter())),
+        )
         .fuse(to_add_fields_from_directives(directives))
         .fuse(to_federation_directives(directives))
         .map(
-            |(resolver, cache, fields, protected, added_fields, unknown_directives)| {
+            |(
+                resolver,
+                cache,
+                fields,
+                (protected, rate_limit),
+                added_fields,
+                unknown_directives,
+            )| {
                 let doc = description.to_owned().map(|pos| pos.node);
                 let implements = implements.iter().map(|pos| pos.node.to_string()).collect();
                 config::Type {
@@ -255,6 +267,7 @@ where
                     implements,
                     cache,
                     protected,
+                    rate_limit,
                     resolver,
                     directives: unknown_directives,
                 }
@@ -330,21 +343,30 @@ where
     let doc = description.to_owned().map(|pos| pos.node);
 
     config::Resolver::from_directives(directives)
-        .fuse(Cache::from_directives(directives.iter()))
+        .fuse(
+            Cache::from_directives(directives.iter())
+                .zip(Invalidate::from_directives(directives.iter())),
+        )
         .fuse(Omit::from_directives(directives.iter()))
         .fuse(Modify::from_directives(directives.iter()))
-        .fuse(Protected::from_directives(directives.iter()))
-        .fuse(Discriminate::from_directives(directives.iter()))
+        .fuse(
+            Protected::from_directives(directives.iter())
+                .zip(RateLimit::from_directives(directives.iter())),
+        )
+        .fuse(
+            Discriminate::from_directives(directives.iter())
+                .zip(Cost::from_directives(directives.iter())),
+        )
         .fuse(default_value)
         .fuse(to_federation_directives(directives))
         .map(
             |(
                 resolver,
-                cache,
+                (cache, invalidate),
                 omit,
                 modify,
-                protected,
-                discriminate,
+                (protected, rate_limit),
+                (discriminate, cost),
                 default_value,
                 directives,
             )| config::Field {
@@ -355,7 +377,10 @@ where
                 omit,
                 cache,
                 protected,
+                rate_limit,
+                invalidate,
                 discriminate,
+                cost,
                 default_value,
                 resolver,
                 directives,
@@ -223,6 +223,9 @@ fn field_directives(field: &crate::core::config::Field) -> Vec<Positioned<ConstD
         field.omit.as_ref().map(|d| pos(d.to_directive())),
         field.cache.as_ref().map(|d| pos(d.to_directive())),
         field.protected.as_ref().map(|d| pos(d.to_directive())),
+        field.rate_limit.as_ref().map(|d| pos(d.to_directive())),
+        field.invalidate.as_ref().map(|d| pos(d.to_directive())),
+        field.cost.as_ref().map(|d| pos(d.to_directive())),
     ];
 
     directives
@@ -249,6 +252,12 @@ fn type_directives(type_def: &crate::core::config::Type) -> Vec<Positioned<Const
                 .as_ref()
                 .map(|protected| pos(protected.to_directive())),
         )
+        .chain(
+            type_def
+                .rate_limit
+                .as_ref()
+                .map(|rate_limit| pos(rate_limit.to_directive())),
+        )
         .chain(
             type_def
                 .resolver
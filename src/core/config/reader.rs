@@ -1,5 +1,9 @@
+use std::collections::BTreeMap;
 use std::path::Path;
+use std::str::FromStr;
 
+use http::header::HeaderName;
+use regex::Regex;
 use rustls_pemfile;
 use rustls_pki_types::{
     CertificateDer, PrivateKeyDer, PrivatePkcs1KeyDer, PrivatePkcs8KeyDer, PrivateSec1KeyDer,
@@ -7,8 +11,9 @@ use rustls_pki_types::{
 use tailcall_valid::{Valid, Validator};
 use url::Url;
 
-use super::{ConfigModule, Content, Link, LinkType, PrivateKey};
+use super::{ApiKeys, ClientCa, ConfigModule, Content, Introspection, Link, LinkType, PrivateKey};
 use crate::core::config::{Config, ConfigReaderContext, Source};
+use crate::core::mustache::Mustache;
 use crate::core::proto_reader::ProtoReader;
 use crate::core::resource_reader::{Cached, Resource, ResourceReader};
 use crate::core::rest::EndpointSet;
@@ -33,6 +38,12 @@ impl ConfigReader {
         }
     }
 
+    /// Paths of all the local files read so far, including the ones reached
+    /// through links
+    pub fn local_files(&self) -> Vec<String> {
+        self.resource_reader.local_files()
+    }
+
     /// Reads the links in a Config and fill the content
     #[async_recursion::async_recursion]
     async fn ext_links(
@@ -58,6 +69,20 @@ impl ConfigReader {
         }
 
         let mut extensions = config_module.extensions().clone();
+        // the certificates and keys referenced by the upstream TLS settings
+        // aren't used by the server
+        let tls_link_ids = config_module.config().tls_link_ids();
+        let vars: BTreeMap<String, String> = config_module
+            .server
+            .vars
+            .iter()
+            .map(|vars| (vars.key.clone(), vars.value.clone()))
+            .collect();
+        let reader_ctx = ConfigReaderContext {
+            runtime: &self.runtime,
+            vars: &vars,
+            headers: Default::default(),
+        };
         let mut config_module = Valid::succeed(config_module);
 
         for link in links.iter() {
@@ -89,6 +114,22 @@ impl ConfigReader {
                     let content = source.content;
                     extensions.script = Some(content);
                 }
+                LinkType::Cert | LinkType::Key
+                    if link.id.as_ref().is_some_and(|id| tls_link_ids.contains(id)) =>
+                {
+                    if !self.runtime.http.supports_tls() {
+                        anyhow::bail!(
+                            "The TLS settings of the upstreams aren't supported by this runtime"
+                        );
+                    }
+
+                    let source = self.resource_reader.read_file(path).await?;
+                    let content = source.content;
+
+                    extensions
+                        .tls
+                        .push(Content { id: link.id.clone(), content });
+                }
                 LinkType::Cert => {
                     let source = self.resource_reader.read_file(path).await?;
                     let content = source.content;
@@ -104,6 +145,9 @@ impl ConfigReader {
                     let content = source.content;
 
                     extensions.endpoint_set = EndpointSet::try_new(&content)?;
+                    extensions
+                        .operations
+                        .push(Content { id: link.id.clone(), content });
                 }
                 LinkType::Htpasswd => {
                     let source = self.resource_reader.read_file(path).await?;
@@ -124,6 +168,65 @@ impl ConfigReader {
                         content: serde_path_to_error::deserialize(de)?,
                     })
                 }
+                LinkType::ApiKeys => {
+                    let source = self.resource_reader.read_file(path).await?;
+                    let content = source.content;
+
+                    let mut api_keys: ApiKeys = match link.meta.clone() {
+                        Some(meta) => serde_json::from_value(meta)?,
+                        None => ApiKeys::default(),
+                    };
+                    if let Some(header) = &api_keys.header {
+                        HeaderName::from_str(header)?;
+                    }
+
+                    let de = &mut serde_json::Deserializer::from_str(&content);
+                    api_keys.keys = serde_path_to_error::deserialize(de)?;
+                    if let Some(key) = api_keys.keys.iter().find(|key| {
+                        key.hash.len() != 64 || !key.hash.bytes().all(|b| b.is_ascii_hexdigit())
+                    }) {
+                        anyhow::bail!(
+                            "The hash of the API key `{}` isn't a hex encoded SHA-256 hash",
+                            key.name
+                        );
+                    }
+
+                    extensions
+                        .api_keys
+                        .push(Content { id: link.id.clone(), content: api_keys });
+                }
+                LinkType::ClientCa => {
+                    let source = self.resource_reader.read_file(path).await?;
+                    let content = source.content;
+
+                    let mut client_ca: ClientCa = match link.meta.clone() {
+                        Some(meta) => serde_json::from_value(meta)?,
+                        None => ClientCa::default(),
+                    };
+                    for pattern in client_ca.subject.iter().chain(client_ca.san.iter()) {
+                        Regex::new(pattern)?;
+                    }
+                    client_ca.certs = self.load_cert(content).await?;
+
+                    extensions
+                        .client_ca
+                        .push(Content { id: link.id.clone(), content: client_ca });
+                }
+                LinkType::Introspection => {
+                    let mut introspection: Introspection = match link.meta.clone() {
+                        Some(meta) => serde_json::from_value(meta)?,
+                        None => Introspection::default(),
+                    };
+                    // the endpoint is called for every token, so it isn't read here
+                    introspection.url = Url::parse(&link.src)?.to_string();
+                    introspection.client_secret = introspection
+                        .client_secret
+                        .map(|secret| Mustache::parse(&secret).render(&reader_ctx));
+
+                    extensions
+                        .introspection
+                        .push(Content { id: link.id.clone(), content: introspection });
+                }
                 LinkType::Grpc => {
                     let meta = self
                         .proto_reader
@@ -225,6 +328,9 @@ impl ConfigReader {
             headers: Default::default(),
         };
         config.telemetry.render_mustache(&reader_ctx)?;
+        if let Some(admin) = config.server.admin.as_mut() {
+            admin.render_mustache(&reader_ctx);
+        }
 
         // Create initial config set & extend it with the links
         self.ext_links(ConfigModule::from(config), parent_dir).await
@@ -249,6 +355,7 @@ mod reader_tests {
     use std::path::{Path, PathBuf};
 
     use pretty_assertions::assert_eq;
+    use tailcall_valid::Validator;
 
     use crate::core::config::reader::ConfigReader;
     use crate::core::config::{Config, Type};
@@ -358,6 +465,51 @@ mod reader_tests {
         );
     }
 
+    #[tokio::test]
+    async fn test_local_files_read_through_links() {
+        let runtime = crate::core::runtime::test::init(None);
+
+        let cargo_manifest = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+        let reader = ConfigReader::init(runtime);
+        let config_path = format!("{}/examples/jsonplaceholder_script.graphql", cargo_manifest);
+
+        reader.read(config_path.clone()).await.unwrap();
+
+        let script_path = format!("{}/examples/scripts/echo.js", cargo_manifest);
+        assert_eq!(reader.local_files(), vec![config_path, script_path]);
+    }
+
+    #[tokio::test]
+    async fn test_tls_links_require_tls_support() {
+        let runtime = crate::core::runtime::test::init(None);
+
+        let cargo_manifest = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+        let sdl = format!(
+            r#"
+            schema
+              @link(type: Cert, id: "client-cert", src: "{0}/tests/server/config/example.crt")
+              @link(type: Key, id: "client-key", src: "{0}/tests/server/config/example-rsa.key")
+              @upstream(tls: {{cert: "client-cert", key: "client-key"}}) {{
+              query: Query
+            }}
+
+            type Query {{
+              hello: String @expr(body: "world")
+            }}
+            "#,
+            cargo_manifest
+        );
+        let config = Config::from_sdl(&sdl).to_result().unwrap();
+        let reader = ConfigReader::init(runtime);
+
+        let actual = reader.resolve(config, None).await.unwrap_err();
+
+        assert_eq!(
+            actual.to_string(),
+            "The TLS settings of the upstreams aren't supported by this runtime"
+        );
+    }
+
     #[test]
     fn test_relative_path() {
         let path_dir = Path::new("abc/xyz");
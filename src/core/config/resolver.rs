@@ -4,7 +4,7 @@ use serde::{Deserialize, Serialize};
 use tailcall_macros::{CustomResolver, MergeRight};
 use tailcall_valid::{Valid, Validator};
 
-use super::{Call, EntityResolver, Expr, GraphQL, Grpc, Http, JS};
+use super::{Call, EntityResolver, Expr, GraphQL, Grpc, Http, Sse, JS};
 use crate::core::directive::DirectiveCodec;
 
 #[derive(Clone, Debug, PartialEq, Eq)]
@@ -32,6 +32,7 @@ pub enum Resolver {
     Call(Call),
     Js(JS),
     Expr(Expr),
+    Sse(Sse),
     #[serde(skip)]
     #[resolver(skip_directive)]
     ApolloFederation(ApolloFederation),
@@ -211,6 +211,7 @@ fn merge_type(type_: &Type, mut merge_into: Type) -> Type {
     merge_into.implements = merge_into.implements.merge_right(type_.implements.clone());
     merge_into.cache = merge_into.cache.merge_right(type_.cache.clone());
     merge_into.protected = merge_into.protected.merge_right(type_.protected.clone());
+    merge_into.rate_limit = merge_into.rate_limit.merge_right(type_.rate_limit.clone());
     merge_into.doc = merge_into.doc.merge_right(type_.doc.clone());
 
     // Handle field output type merging correctly.
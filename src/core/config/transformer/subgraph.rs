@@ -548,6 +548,7 @@ mod tests {
                     ..Default::default()
                 }],
                 dedupe: None,
+                timeout: None,
             };
 
             let resolver = Resolver::Call(call);
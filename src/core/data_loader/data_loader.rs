@@ -2,18 +2,28 @@ use std::any::TypeId;
 use std::borrow::Cow;
 use std::collections::{HashMap, HashSet};
 use std::hash::Hash;
-use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
 use std::sync::{Arc, Mutex};
 use std::time::Duration;
 
 use futures_channel::oneshot;
 use futures_timer::Delay;
+use serde::Serialize;
 
 pub use super::cache::NoCache;
 pub use super::factory::CacheFactory;
 pub use super::loader::Loader;
 pub use super::storage::CacheStorage;
 
+/// Counts of the loads made by a [DataLoader]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct DataLoaderStats {
+    /// Number of batches loaded
+    pub batches: usize,
+    /// Number of keys loaded, across all the batches
+    pub keys: usize,
+}
+
 /// Data loader.
 ///
 /// Reference: <https://github.com/facebook/dataloader>
@@ -39,6 +49,8 @@ where
             inner: Arc::new(DataLoaderInner {
                 requests: Mutex::new(Requests::new(&NoCache)),
                 loader,
+                batches: AtomicUsize::new(0),
+                keys: AtomicUsize::new(0),
             }),
             delay: Duration::from_millis(1),
             max_batch_size: 1000,
@@ -59,6 +71,8 @@ where
             inner: Arc::new(DataLoaderInner {
                 requests: Mutex::new(Requests::new(&cache_factory)),
                 loader,
+                batches: AtomicUsize::new(0),
+                keys: AtomicUsize::new(0),
             }),
             delay: Duration::from_millis(1),
             max_batch_size: 1000,
@@ -88,6 +102,14 @@ where
         &self.inner.loader
     }
 
+    /// Get the counts of the loads made so far.
+    pub fn stats(&self) -> DataLoaderStats {
+        DataLoaderStats {
+            batches: self.inner.batches.load(Ordering::Relaxed),
+            keys: self.inner.keys.load(Ordering::Relaxed),
+        }
+    }
+
     /// Enable/Disable cache of all loaders.
     pub fn enable_all_cache(&self, enable: bool) {
         self.disable_cache.store(!enable, Ordering::SeqCst);
@@ -316,6 +338,8 @@ struct DataLoaderInner<
 > {
     requests: Mutex<Requests<K, T, C>>,
     loader: T,
+    batches: AtomicUsize,
+    keys: AtomicUsize,
 }
 
 impl<K, T, C> DataLoaderInner<K, T, C>
@@ -330,6 +354,8 @@ where
         T: Loader<K>,
     {
         let keys = keys.into_iter().collect::<Vec<_>>();
+        self.batches.fetch_add(1, Ordering::Relaxed);
+        self.keys.fetch_add(keys.len(), Ordering::Relaxed);
 
         match self.loader.load(&keys).await {
             Ok(values) => {
@@ -412,6 +438,9 @@ mod tests {
             .unwrap(),
             (0..100).map(Option::Some).collect::<Vec<_>>()
         );
+        let stats = loader.stats();
+        assert_eq!(stats.keys, 100);
+        assert!(stats.batches >= 10);
     }
 
     #[tokio::test]
@@ -7,6 +7,6 @@ mod storage;
 
 // Making public as it is unused and clippy gives warning.
 pub use cache::LruCache;
-pub use data_loader::DataLoader;
+pub use data_loader::{DataLoader, DataLoaderStats};
 pub use dedupe::DedupeResult;
 pub use loader::Loader;
@@ -374,6 +374,9 @@ impl Context {
                     headers: vec![],
                     method: field_name.id(),
                     dedupe: None,
+                    retry: None,
+                    timeout: None,
+                    tls: None,
                     select: None,
                 }));
 
@@ -5,20 +5,24 @@ use std::time::Duration;
 
 use async_graphql::async_trait;
 use async_graphql::futures_util::future::join_all;
+use hyper::body::Bytes;
+use reqwest::Request;
 
 use crate::core::config::Batch;
 use crate::core::data_loader::{DataLoader, Loader};
 use crate::core::http::{DataLoaderRequest, Response};
+use crate::core::retry::{self, RetryPolicy};
 use crate::core::runtime::TargetRuntime;
 
 pub struct GraphqlDataLoader {
     pub runtime: TargetRuntime,
     pub batch: bool,
+    pub retry: Option<RetryPolicy>,
 }
 
 impl GraphqlDataLoader {
-    pub fn new(runtime: TargetRuntime, batch: bool) -> Self {
-        GraphqlDataLoader { runtime, batch }
+    pub fn new(runtime: TargetRuntime, batch: bool, retry: Option<RetryPolicy>) -> Self {
+        GraphqlDataLoader { runtime, batch, retry }
     }
 
     pub fn into_data_loader(
@@ -29,6 +33,12 @@ impl GraphqlDataLoader {
             .delay(Duration::from_millis(batch.delay as u64))
             .max_batch_size(batch.max_size.unwrap_or_default())
     }
+
+    /// Only queries are loaded in batches, so requests are always retried as
+    /// idempotent.
+    async fn execute(&self, request: Request) -> anyhow::Result<Response<Bytes>> {
+        retry::execute(&*self.runtime.http, request, self.retry.as_ref(), true).await
+    }
 }
 
 #[async_trait::async_trait]
@@ -43,12 +53,12 @@ impl Loader<DataLoaderRequest> for GraphqlDataLoader {
     ) -> async_graphql::Result<HashMap<DataLoaderRequest, Self::Value>, Self::Error> {
         if self.batch {
             let batched_req = create_batched_request(keys);
-            let result = self.runtime.http.execute(batched_req).await?.to_json();
+            let result = self.execute(batched_req).await?.to_json();
             let hashmap = extract_responses(result, keys);
             Ok(hashmap)
         } else {
             let results = keys.iter().map(|key| async {
-                let result = self.runtime.http.execute(key.to_request()).await;
+                let result = self.execute(key.to_request()).await;
                 (key.clone(), result)
             });
             let results = join_all(results).await;
@@ -6,6 +6,7 @@ use anyhow::Result;
 use async_graphql::async_trait;
 use async_graphql::futures_util::future::join_all;
 use async_graphql_value::ConstValue;
+use reqwest::Request;
 
 use super::data_loader_request::DataLoaderRequest;
 use super::protobuf::ProtobufOperation;
@@ -16,6 +17,7 @@ use crate::core::data_loader::{DataLoader, Loader};
 use crate::core::grpc::request::create_grpc_request;
 use crate::core::http::Response;
 use crate::core::json::JsonLike;
+use crate::core::retry::RetryPolicy;
 use crate::core::runtime::TargetRuntime;
 
 #[derive(Clone)]
@@ -23,6 +25,7 @@ pub struct GrpcDataLoader {
     pub(crate) runtime: TargetRuntime,
     pub(crate) operation: ProtobufOperation,
     pub(crate) group_by: Option<GroupBy>,
+    pub(crate) retry: Option<RetryPolicy>,
 }
 
 impl GrpcDataLoader {
@@ -32,13 +35,20 @@ impl GrpcDataLoader {
             .max_batch_size(batch.max_size.unwrap_or_default())
     }
 
+    /// Only queries are loaded in batches, so requests are always retried as
+    /// idempotent.
+    async fn execute(&self, request: Request) -> Result<Response<async_graphql::Value>> {
+        let retry = self.retry.as_ref();
+        execute_grpc_request(&self.runtime, &self.operation, request, retry, true).await
+    }
+
     async fn load_dedupe_only(
         &self,
         keys: &[DataLoaderRequest],
     ) -> anyhow::Result<HashMap<DataLoaderRequest, Response<async_graphql::Value>>> {
         let results = keys.iter().map(|key| async {
             let result = match key.to_request() {
-                Ok(req) => execute_grpc_request(&self.runtime, &self.operation, req).await,
+                Ok(req) => self.execute(req).await,
                 Err(error) => Err(error),
             };
 
@@ -75,8 +85,7 @@ impl GrpcDataLoader {
             multiple_body,
         );
 
-        let response =
-            execute_grpc_request(&self.runtime, &self.operation, multiple_request).await?;
+        let response = self.execute(multiple_request).await?;
 
         let path = &group_by.path();
         let response_body = response.body.group_by(path);
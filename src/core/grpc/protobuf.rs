@@ -171,6 +171,12 @@ impl ProtobufOperation {
         message_to_bytes(message)
     }
 
+    /// Encodes the input without the framing of gRPC, for protobuf sent over
+    /// plain HTTP
+    pub fn encode_input(&self, input: &str) -> Result<Vec<u8>> {
+        Ok(to_message(&self.input_type, input)?.encode_to_vec())
+    }
+
     pub fn convert_multiple_inputs<'a>(
         &self,
         child_inputs: impl Iterator<Item = &'a str>,
@@ -218,8 +224,14 @@ impl ProtobufOperation {
         // see https://www.oreilly.com/library/view/grpc-up-and/9781492058328/ch04.html#:~:text=Length%2DPrefixed%20Message%20Framing
         // 1st byte - compression flag
         // 2-4th bytes - length of the message
+        self.decode_output(&bytes[5..])
+    }
+
+    /// Decodes an output without the framing of gRPC, for protobuf sent over
+    /// plain HTTP
+    pub fn decode_output<T: serde::de::DeserializeOwned>(&self, bytes: &[u8]) -> Result<T> {
         let message =
-            DynamicMessage::decode(self.output_type.clone(), &bytes[5..]).with_context(|| {
+            DynamicMessage::decode(self.output_type.clone(), bytes).with_context(|| {
                 format!(
                     "Failed to parse response for type {}",
                     self.output_type.full_name()
@@ -5,6 +5,7 @@ use url::Url;
 
 use super::protobuf::ProtobufOperation;
 use crate::core::http::Response;
+use crate::core::retry::{self, RetryPolicy};
 use crate::core::runtime::TargetRuntime;
 
 pub static GRPC_STATUS: &str = "grpc-status";
@@ -21,8 +22,10 @@ pub async fn execute_grpc_request(
     runtime: &TargetRuntime,
     operation: &ProtobufOperation,
     request: Request,
+    retry: Option<&RetryPolicy>,
+    idempotent: bool,
 ) -> Result<Response<async_graphql::Value>> {
-    let response = runtime.http2_only.execute(request).await?;
+    let response = retry::execute(&*runtime.http2_only, request, retry, idempotent).await?;
 
     let grpc_status = response
         .headers
@@ -124,7 +127,7 @@ mod tests {
         let test_http = TestHttp { scenario: TestScenario::SuccessWithoutGrpcStatus };
         let (runtime, operation, request) = prepare_args(test_http).await?;
 
-        let result = execute_grpc_request(&runtime, &operation, request).await;
+        let result = execute_grpc_request(&runtime, &operation, request, None, true).await;
 
         assert!(
             result.is_ok(),
@@ -138,7 +141,7 @@ mod tests {
         let test_http = TestHttp { scenario: TestScenario::SuccessWithOkGrpcStatus };
         let (runtime, operation, request) = prepare_args(test_http).await?;
 
-        let result = execute_grpc_request(&runtime, &operation, request).await;
+        let result = execute_grpc_request(&runtime, &operation, request, None, true).await;
 
         assert!(
             result.is_ok(),
@@ -152,7 +155,7 @@ mod tests {
         let test_http = TestHttp { scenario: TestScenario::SuccessWithErrorGrpcStatus };
         let (runtime, operation, request) = prepare_args(test_http).await?;
 
-        let result = execute_grpc_request(&runtime, &operation, request).await;
+        let result = execute_grpc_request(&runtime, &operation, request, None, true).await;
 
         assert!(
             result.is_err(),
@@ -193,7 +196,7 @@ mod tests {
         let test_http = TestHttp { scenario: TestScenario::Error };
         let (runtime, operation, request) = prepare_args(test_http).await?;
 
-        let result = execute_grpc_request(&runtime, &operation, request).await;
+        let result = execute_grpc_request(&runtime, &operation, request, None, true).await;
 
         assert!(result.is_err(), "Expected error");
         assert_eq!(result.unwrap_err().to_string(), "Failed to execute request");
@@ -0,0 +1,315 @@
+use anyhow::Result;
+use hyper::header::{AUTHORIZATION, CONTENT_TYPE, WWW_AUTHENTICATE};
+use hyper::{Body, Method, Request, Response, StatusCode};
+use serde::Serialize;
+use serde_json::json;
+
+use crate::core::app_context::AppContext;
+use crate::core::blueprint::Admin;
+use crate::core::data_loader::DataLoaderStats;
+
+#[derive(Serialize)]
+struct Endpoint {
+    method: String,
+    path: String,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct HitRates {
+    io: Option<f64>,
+    response: Option<f64>,
+    persisted_queries: Option<f64>,
+}
+
+#[derive(Serialize)]
+struct DataLoaders {
+    http: Vec<DataLoaderStats>,
+    graphql: Vec<DataLoaderStats>,
+    grpc: Vec<DataLoaderStats>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Stats {
+    operation_plans: usize,
+    hit_rates: HitRates,
+    data_loaders: DataLoaders,
+}
+
+/// Tells if the path is served by the admin API
+pub fn is_admin_path(path: &str, route: &str) -> bool {
+    path.strip_prefix(route)
+        .is_some_and(|rest| rest.is_empty() || rest.starts_with('/'))
+}
+
+/// Compares the token in constant time, so that it can't be guessed from the
+/// time taken to reject the requests
+fn is_authorized(req: &Request<Body>, token: &str) -> bool {
+    let Some(sent) = req
+        .headers()
+        .get(AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+    else {
+        return false;
+    };
+
+    sent.len() == token.len()
+        && sent
+            .bytes()
+            .zip(token.bytes())
+            .fold(0, |diff, (a, b)| diff | (a ^ b))
+            == 0
+}
+
+fn stats(app_ctx: &AppContext) -> Stats {
+    Stats {
+        operation_plans: app_ctx.operation_plans.len(),
+        hit_rates: HitRates {
+            io: app_ctx.runtime.cache.hit_rate(),
+            response: app_ctx.response_cache.hit_rate(),
+            persisted_queries: app_ctx.runtime.persisted_queries.hit_rate(),
+        },
+        data_loaders: DataLoaders {
+            http: app_ctx
+                .http_data_loaders
+                .iter()
+                .map(|dl| dl.stats())
+                .collect(),
+            graphql: app_ctx
+                .gql_data_loaders
+                .iter()
+                .map(|dl| dl.stats())
+                .collect(),
+            grpc: app_ctx
+                .grpc_data_loaders
+                .iter()
+                .map(|dl| dl.stats())
+                .collect(),
+        },
+    }
+}
+
+fn endpoints(app_ctx: &AppContext) -> Vec<Endpoint> {
+    app_ctx
+        .endpoints
+        .get_endpoints()
+        .iter()
+        .map(|endpoint| Endpoint {
+            method: endpoint.get_method().to_string(),
+            path: endpoint.get_path().as_str().to_string(),
+        })
+        .collect()
+}
+
+async fn purge_io_cache(app_ctx: &AppContext) -> std::result::Result<(), String> {
+    app_ctx.response_cache.clear().await;
+    app_ctx
+        .runtime
+        .cache
+        .clear()
+        .await
+        .map_err(|e| e.to_string())
+}
+
+async fn purge_http_cache(app_ctx: &AppContext) -> std::result::Result<(), String> {
+    app_ctx
+        .runtime
+        .http
+        .clear_cache()
+        .await
+        .map_err(|e| e.to_string())?;
+    app_ctx
+        .runtime
+        .http2_only
+        .clear_cache()
+        .await
+        .map_err(|e| e.to_string())
+}
+
+fn response(
+    status: StatusCode,
+    content_type: &str,
+    body: impl Into<Body>,
+) -> Result<Response<Body>> {
+    Ok(Response::builder()
+        .status(status)
+        .header(CONTENT_TYPE, content_type)
+        .body(body.into())?)
+}
+
+fn json<T: Serialize>(value: &T) -> Result<Response<Body>> {
+    response(
+        StatusCode::OK,
+        "application/json",
+        serde_json::to_vec(value)?,
+    )
+}
+
+fn purged(result: std::result::Result<(), String>) -> Result<Response<Body>> {
+    match result {
+        Ok(()) => Ok(Response::builder()
+            .status(StatusCode::NO_CONTENT)
+            .body(Body::empty())?),
+        Err(error) => response(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "application/json",
+            json!({ "message": error }).to_string(),
+        ),
+    }
+}
+
+/// Serves the admin API, which exposes the config, the REST endpoints and
+/// the statistics of the running server, and purges its caches. The requests
+/// are authenticated with the token of the admin API.
+pub async fn handle_admin(
+    req: Request<Body>,
+    app_ctx: &AppContext,
+    admin: &Admin,
+) -> Result<Response<Body>> {
+    if !is_authorized(&req, &admin.token) {
+        return Ok(Response::builder()
+            .status(StatusCode::UNAUTHORIZED)
+            .header(WWW_AUTHENTICATE, "Bearer")
+            .body(Body::empty())?);
+    }
+
+    let route = app_ctx.blueprint.server.routes.admin();
+    let path = req.uri().path().strip_prefix(route).unwrap_or_default();
+
+    match (req.method(), path) {
+        (&Method::GET, "/config") => response(
+            StatusCode::OK,
+            "text/plain; charset=utf-8",
+            admin.config.clone(),
+        ),
+        (&Method::GET, "/endpoints") => json(&endpoints(app_ctx)),
+        (&Method::GET, "/stats") => json(&stats(app_ctx)),
+        (&Method::DELETE, "/cache/io") => purged(purge_io_cache(app_ctx).await),
+        (&Method::DELETE, "/cache/http") => purged(purge_http_cache(app_ctx).await),
+        _ => Ok(Response::builder()
+            .status(StatusCode::NOT_FOUND)
+            .body(Body::empty())?),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::num::NonZeroU64;
+
+    use async_graphql_value::ConstValue;
+    use tailcall_valid::Validator;
+
+    use super::*;
+    use crate::core::blueprint::Blueprint;
+    use crate::core::config::{Config, ConfigModule};
+    use crate::core::ir::model::IoId;
+    use crate::core::rest::EndpointSet;
+    use crate::core::runtime::test::init;
+
+    fn app_ctx() -> (AppContext, Admin) {
+        let sdl = r#"
+            schema
+              @server(admin: {token: "secret"})
+              @link(
+                type: Introspection
+                src: "http://idp/introspect"
+                meta: {clientId: "tailcall", clientSecret: "s3cret"}
+              ) {
+              query: Query
+            }
+
+            type Query {
+              hello: String @expr(body: "world")
+            }
+        "#;
+        let config = Config::from_sdl(sdl).to_result().unwrap();
+        let blueprint = Blueprint::try_from(&ConfigModule::from(config)).unwrap();
+        let admin = blueprint.server.admin.clone().unwrap();
+        let app_ctx = AppContext::new(blueprint, init(None), EndpointSet::default());
+        (app_ctx, admin)
+    }
+
+    fn request(method: Method, path: &str, token: Option<&str>) -> Request<Body> {
+        let mut req = Request::builder()
+            .method(method)
+            .uri(format!("http://localhost:8000{}", path));
+        if let Some(token) = token {
+            req = req.header(AUTHORIZATION, format!("Bearer {}", token));
+        }
+        req.body(Body::empty()).unwrap()
+    }
+
+    async fn body(resp: Response<Body>) -> String {
+        let bytes = hyper::body::to_bytes(resp.into_body()).await.unwrap();
+        String::from_utf8(bytes.to_vec()).unwrap()
+    }
+
+    #[test]
+    fn test_is_admin_path() {
+        assert!(is_admin_path("/admin", "/admin"));
+        assert!(is_admin_path("/admin/stats", "/admin"));
+        assert!(!is_admin_path("/administrator", "/admin"));
+        assert!(!is_admin_path("/graphql", "/admin"));
+    }
+
+    #[tokio::test]
+    async fn test_unauthorized() {
+        let (app_ctx, admin) = app_ctx();
+
+        for token in [None, Some("wrong"), Some("secre")] {
+            let req = request(Method::GET, "/admin/stats", token);
+            let resp = handle_admin(req, &app_ctx, &admin).await.unwrap();
+            assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_config_hides_secrets() {
+        let (app_ctx, admin) = app_ctx();
+
+        let req = request(Method::GET, "/admin/config", Some("secret"));
+        let resp = handle_admin(req, &app_ctx, &admin).await.unwrap();
+
+        assert_eq!(resp.status(), StatusCode::OK);
+        let config = body(resp).await;
+        assert!(config.contains("type Query"));
+        assert!(!config.contains("secret"));
+        assert!(!config.contains("s3cret"));
+    }
+
+    #[tokio::test]
+    async fn test_stats() {
+        let (app_ctx, admin) = app_ctx();
+
+        let req = request(Method::GET, "/admin/stats", Some("secret"));
+        let resp = handle_admin(req, &app_ctx, &admin).await.unwrap();
+
+        assert_eq!(resp.status(), StatusCode::OK);
+        let stats: serde_json::Value = serde_json::from_str(&body(resp).await).unwrap();
+        assert_eq!(stats["operationPlans"], 0);
+        assert_eq!(stats["dataLoaders"]["http"], serde_json::json!([]));
+    }
+
+    #[tokio::test]
+    async fn test_purge_io_cache() {
+        let (app_ctx, admin) = app_ctx();
+        let ttl = NonZeroU64::new(60000).unwrap();
+        app_ctx
+            .runtime
+            .cache
+            .set(IoId::new(1), ConstValue::from("Leanne"), ttl)
+            .await
+            .unwrap();
+
+        let req = request(Method::DELETE, "/admin/cache/io", Some("secret"));
+        let resp = handle_admin(req, &app_ctx, &admin).await.unwrap();
+
+        assert_eq!(resp.status(), StatusCode::NO_CONTENT);
+        assert_eq!(
+            app_ctx.runtime.cache.get(&IoId::new(1)).await.unwrap(),
+            None
+        );
+    }
+}
@@ -0,0 +1,350 @@
+use std::borrow::Cow;
+use std::fmt::Write;
+
+use anyhow::{anyhow, bail};
+use async_graphql_value::ConstValue;
+use quick_xml::escape::escape;
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::Reader;
+use serde_json::{Map, Value};
+
+use crate::core::path::PathString;
+
+/// Prefix of the keys that are written as attributes of an element
+const ATTRIBUTE_PREFIX: char = '@';
+/// Key that is written as the text of an element with attributes or children
+const TEXT_KEY: &str = "#text";
+/// Field that holds the text of a decoded element with attributes or children.
+/// Unlike [TEXT_KEY] it's a valid GraphQL name.
+const TEXT_FIELD: &str = "text";
+
+struct Element {
+    name: String,
+    fields: Map<String, Value>,
+    text: String,
+}
+
+impl Element {
+    fn new(start: &BytesStart) -> anyhow::Result<Self> {
+        let name = String::from_utf8(start.local_name().as_ref().to_vec())?;
+        let mut fields = Map::new();
+
+        for attribute in start.attributes() {
+            let attribute = attribute?;
+            // namespace declarations don't carry any data
+            if attribute.key.as_ref().starts_with(b"xmlns") {
+                continue;
+            }
+
+            let key = String::from_utf8(attribute.key.local_name().as_ref().to_vec())?;
+            let value = attribute.unescape_value()?.into_owned();
+            fields.insert(key, Value::String(value));
+        }
+
+        Ok(Self { name, fields, text: String::new() })
+    }
+
+    /// Adds a child element, the children with the same name, or the name of
+    /// an attribute, become a list
+    fn insert(&mut self, name: String, value: Value) {
+        match self.fields.get_mut(&name) {
+            Some(Value::Array(list)) => list.push(value),
+            Some(existing) => *existing = Value::Array(vec![existing.take(), value]),
+            None => {
+                self.fields.insert(name, value);
+            }
+        }
+    }
+
+    fn into_value(mut self) -> Value {
+        if self.fields.is_empty() {
+            if self.text.is_empty() {
+                Value::Null
+            } else {
+                Value::String(self.text)
+            }
+        } else {
+            let text = std::mem::take(&mut self.text);
+            if !text.is_empty() {
+                self.insert(TEXT_FIELD.to_string(), Value::String(text));
+            }
+            Value::Object(self.fields)
+        }
+    }
+}
+
+/// Converts an XML document to JSON. The root element becomes an object with a
+/// single key, elements with only text become strings, empty elements become
+/// `null` and the others become objects. Attributes become fields like child
+/// elements, the text next to attributes or children is kept under `text` and
+/// repeated elements become lists. Namespace prefixes are dropped from the
+/// names, so that the fields can be selected in GraphQL.
+pub fn xml_to_json(xml: &[u8]) -> anyhow::Result<Value> {
+    let mut reader = Reader::from_reader(xml);
+    reader.trim_text(true);
+
+    let mut stack: Vec<Element> = Vec::new();
+    let mut root = None;
+
+    let mut close = |element: Element, stack: &mut Vec<Element>| {
+        let name = element.name.clone();
+        let value = element.into_value();
+        match stack.last_mut() {
+            Some(parent) => parent.insert(name, value),
+            None => root = Some((name, value)),
+        }
+    };
+
+    loop {
+        match reader.read_event()? {
+            Event::Start(start) => stack.push(Element::new(&start)?),
+            Event::Empty(start) => close(Element::new(&start)?, &mut stack),
+            Event::End(_) => {
+                let element = stack
+                    .pop()
+                    .ok_or_else(|| anyhow!("Unexpected closing tag in XML"))?;
+                close(element, &mut stack);
+            }
+            Event::Text(text) => {
+                if let Some(element) = stack.last_mut() {
+                    element.text.push_str(&text.unescape()?);
+                }
+            }
+            Event::CData(data) => {
+                if let Some(element) = stack.last_mut() {
+                    element
+                        .text
+                        .push_str(std::str::from_utf8(&data.into_inner())?);
+                }
+            }
+            Event::Eof if stack.is_empty() => break,
+            Event::Eof => bail!("Unexpected end of XML"),
+            _ => {}
+        }
+    }
+
+    match root {
+        Some((name, value)) => Ok(Value::Object(Map::from_iter([(name, value)]))),
+        None => bail!("The XML document has no root element"),
+    }
+}
+
+fn to_text(value: &Value) -> String {
+    match value {
+        Value::String(text) => text.clone(),
+        value => value.to_string(),
+    }
+}
+
+/// Tells if the character can start an XML name
+fn is_name_start_char(c: char) -> bool {
+    matches!(
+        c,
+        ':' | 'A'..='Z'
+            | '_'
+            | 'a'..='z'
+            | '\u{C0}'..='\u{D6}'
+            | '\u{D8}'..='\u{F6}'
+            | '\u{F8}'..='\u{2FF}'
+            | '\u{370}'..='\u{37D}'
+            | '\u{37F}'..='\u{1FFF}'
+            | '\u{200C}'..='\u{200D}'
+            | '\u{2070}'..='\u{218F}'
+            | '\u{2C00}'..='\u{2FEF}'
+            | '\u{3001}'..='\u{D7FF}'
+            | '\u{F900}'..='\u{FDCF}'
+            | '\u{FDF0}'..='\u{FFFD}'
+            | '\u{10000}'..='\u{EFFFF}'
+    )
+}
+
+/// Tells if the character can be part of an XML name
+fn is_name_char(c: char) -> bool {
+    is_name_start_char(c)
+        || matches!(
+            c,
+            '-' | '.' | '0'..='9' | '\u{B7}' | '\u{300}'..='\u{36F}' | '\u{203F}'..='\u{2040}'
+        )
+}
+
+/// Checks that a key of the JSON is a valid name for an element or an
+/// attribute, so that it can't inject markup into the document
+fn check_name(name: &str) -> anyhow::Result<&str> {
+    let mut chars = name.chars();
+    if chars.next().is_some_and(is_name_start_char) && chars.all(is_name_char) {
+        Ok(name)
+    } else {
+        bail!("'{}' isn't a valid XML name", name)
+    }
+}
+
+fn write_element(xml: &mut String, name: &str, value: &Value) -> anyhow::Result<()> {
+    let name = check_name(name)?;
+    match value {
+        Value::Array(items) => {
+            for item in items {
+                write_element(xml, name, item)?;
+            }
+        }
+        Value::Object(fields) => {
+            write!(xml, "<{}", name)?;
+            for (key, value) in fields {
+                if let Some(attribute) = key.strip_prefix(ATTRIBUTE_PREFIX) {
+                    let attribute = check_name(attribute)?;
+                    write!(xml, " {}=\"{}\"", attribute, escape(&to_text(value)))?;
+                }
+            }
+            xml.push('>');
+            for (key, value) in fields {
+                if key == TEXT_KEY {
+                    xml.push_str(&escape(&to_text(value)));
+                } else if !key.starts_with(ATTRIBUTE_PREFIX) {
+                    write_element(xml, key, value)?;
+                }
+            }
+            write!(xml, "</{}>", name)?;
+        }
+        Value::Null => write!(xml, "<{}/>", name)?,
+        value => write!(xml, "<{}>{}</{}>", name, escape(&to_text(value)), name)?,
+    }
+
+    Ok(())
+}
+
+/// Converts JSON to an XML document. The value has to be an object with a
+/// single key, the root element. Keys prefixed with `@` are written as
+/// attributes and `#text` as the text of the element. The other keys have to
+/// be valid XML names.
+pub fn json_to_xml(value: &Value) -> anyhow::Result<String> {
+    let root = value
+        .as_object()
+        .filter(|fields| fields.len() == 1)
+        .and_then(|fields| fields.iter().next())
+        .ok_or_else(|| {
+            anyhow!("The body of an XML request has to be an object with a single key")
+        })?;
+
+    let mut xml = String::from(r#"<?xml version="1.0" encoding="UTF-8"?>"#);
+    write_element(&mut xml, root.0, root.1)?;
+
+    Ok(xml)
+}
+
+/// Escapes the values rendered into the templates of XML documents
+pub struct XmlEscaped<'a, C>(pub &'a C);
+
+impl<C: PathString> PathString for XmlEscaped<'_, C> {
+    fn path_string<T: AsRef<str>>(&self, path: &[T]) -> Option<Cow<'_, str>> {
+        self.0
+            .path_string(path)
+            .map(|value| Cow::Owned(escape(value.as_ref()).into_owned()))
+    }
+}
+
+/// Encodes JSON in MessagePack
+pub fn json_to_msgpack(value: &Value) -> anyhow::Result<Vec<u8>> {
+    Ok(rmp_serde::to_vec(value)?)
+}
+
+/// Decodes MessagePack, binary values become [ConstValue::Binary]
+pub fn msgpack_to_value(bytes: &[u8]) -> anyhow::Result<ConstValue> {
+    Ok(rmp_serde::from_slice(bytes)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_xml_to_json() {
+        let xml = r#"<?xml version="1.0"?>
+            <soap:Envelope xmlns:soap="http://schemas.xmlsoap.org/soap/envelope/">
+              <soap:Body>
+                <user id="1">
+                  <name>Leanne &amp; Graham</name>
+                  <tag>a</tag>
+                  <tag>b</tag>
+                  <phone type="home">555</phone>
+                  <deleted/>
+                </user>
+              </soap:Body>
+            </soap:Envelope>"#;
+
+        let actual = xml_to_json(xml.as_bytes()).unwrap();
+        let expected = json!({
+            "Envelope": {
+                "Body": {
+                    "user": {
+                        "id": "1",
+                        "name": "Leanne & Graham",
+                        "tag": ["a", "b"],
+                        "phone": { "type": "home", "text": "555" },
+                        "deleted": null
+                    }
+                }
+            }
+        });
+
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_xml_to_json_cdata() {
+        let actual = xml_to_json(b"<note><![CDATA[1 < 2]]></note>").unwrap();
+
+        assert_eq!(actual, json!({ "note": "1 < 2" }));
+    }
+
+    #[test]
+    fn test_xml_to_json_invalid() {
+        assert!(xml_to_json(b"").is_err());
+        assert!(xml_to_json(b"<a><b></a>").is_err());
+    }
+
+    #[test]
+    fn test_json_to_xml() {
+        let value = json!({
+            "user": {
+                "@id": 1,
+                "name": "Leanne & Graham",
+                "tag": ["a", "b"],
+                "phone": { "@type": "home", "#text": "555" },
+                "deleted": null
+            }
+        });
+
+        let actual = json_to_xml(&value).unwrap();
+        let expected = r#"<?xml version="1.0" encoding="UTF-8"?><user id="1"><name>Leanne &amp; Graham</name><tag>a</tag><tag>b</tag><phone type="home">555</phone><deleted/></user>"#;
+
+        assert_eq!(actual, expected);
+        assert!(json_to_xml(&json!({ "a": 1, "b": 2 })).is_err());
+    }
+
+    #[test]
+    fn test_json_to_xml_invalid_names() {
+        let invalid = [
+            json!({ "user><admin": 1 }),
+            json!({ "user": { "name id": 1 } }),
+            json!({ "user": { "@id=\"1\" admin": 1 } }),
+            json!({ "user": { "1st": 1 } }),
+            json!({ "user": { "@": 1 } }),
+        ];
+
+        for value in invalid {
+            assert!(json_to_xml(&value).is_err(), "{}", value);
+        }
+        assert!(json_to_xml(&json!({ "soap:Envelope": { "ns.v-1_é": 1 } })).is_ok());
+    }
+
+    #[test]
+    fn test_msgpack() {
+        let value = json!({ "id": 1, "name": "Leanne", "tags": ["a"], "deleted": null });
+
+        let bytes = json_to_msgpack(&value).unwrap();
+        let actual = msgpack_to_value(&bytes).unwrap();
+
+        assert_eq!(actual, ConstValue::from_json(value).unwrap());
+    }
+}
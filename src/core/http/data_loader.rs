@@ -5,12 +5,16 @@ use std::time::Duration;
 use async_graphql::async_trait;
 use async_graphql::futures_util::future::join_all;
 use async_graphql_value::ConstValue;
+use hyper::body::Bytes;
+use reqwest::Request;
 
 use crate::core::config::group_by::GroupBy;
-use crate::core::config::Batch;
+use crate::core::config::{Batch, Encoding};
 use crate::core::data_loader::{DataLoader, Loader};
+use crate::core::grpc::protobuf::ProtobufOperation;
 use crate::core::http::{DataLoaderRequest, Response};
 use crate::core::json::JsonLike;
+use crate::core::retry::{self, is_idempotent, RetryPolicy};
 use crate::core::runtime::TargetRuntime;
 
 fn get_body_value_single(body_value: &HashMap<String, Vec<&ConstValue>>, id: &str) -> ConstValue {
@@ -36,9 +40,19 @@ pub struct HttpDataLoader {
     pub runtime: TargetRuntime,
     pub group_by: Option<GroupBy>,
     pub body: fn(&HashMap<String, Vec<&ConstValue>>, &str) -> ConstValue,
+    pub retry: Option<RetryPolicy>,
+    pub encoding: Encoding,
+    pub protobuf: Option<ProtobufOperation>,
 }
 impl HttpDataLoader {
-    pub fn new(runtime: TargetRuntime, group_by: Option<GroupBy>, is_list: bool) -> Self {
+    pub fn new(
+        runtime: TargetRuntime,
+        group_by: Option<GroupBy>,
+        is_list: bool,
+        retry: Option<RetryPolicy>,
+        encoding: Encoding,
+        protobuf: Option<ProtobufOperation>,
+    ) -> Self {
         HttpDataLoader {
             runtime,
             group_by,
@@ -47,6 +61,9 @@ impl HttpDataLoader {
             } else {
                 get_body_value_single
             },
+            retry,
+            encoding,
+            protobuf,
         }
     }
 
@@ -55,6 +72,17 @@ impl HttpDataLoader {
             .delay(Duration::from_millis(batch.delay as u64))
             .max_batch_size(batch.max_size.unwrap_or_default())
     }
+
+    async fn execute(&self, request: Request) -> anyhow::Result<Response<Bytes>> {
+        let idempotent = is_idempotent(request.method());
+        retry::execute(
+            &*self.runtime.http,
+            request,
+            self.retry.as_ref(),
+            idempotent,
+        )
+        .await
+    }
 }
 
 #[async_trait::async_trait]
@@ -91,11 +119,9 @@ impl Loader<DataLoaderRequest> for HttpDataLoader {
 
             // Dispatch request
             let res = self
-                .runtime
-                .http
                 .execute(request)
                 .await?
-                .to_json::<ConstValue>()?;
+                .decode(&self.encoding, self.protobuf.as_ref())?;
 
             // Create a response HashMap
             #[allow(clippy::mutable_key_type)]
@@ -126,7 +152,7 @@ impl Loader<DataLoaderRequest> for HttpDataLoader {
             Ok(hashmap)
         } else {
             let results = keys.iter().map(|key| async {
-                let result = self.runtime.http.execute(key.to_request()).await;
+                let result = self.execute(key.to_request()).await;
                 (key.clone(), result)
             });
 
@@ -135,7 +161,7 @@ impl Loader<DataLoaderRequest> for HttpDataLoader {
             #[allow(clippy::mutable_key_type)]
             let mut hashmap = HashMap::new();
             for (key, value) in results {
-                hashmap.insert(key, value?.to_json()?);
+                hashmap.insert(key, value?.decode(&self.encoding, self.protobuf.as_ref())?);
             }
 
             Ok(hashmap)
@@ -0,0 +1,405 @@
+use std::collections::HashMap;
+use std::net::IpAddr;
+use std::sync::Arc;
+
+use futures_util::stream::{self, AbortHandle, BoxStream, Fuse, SelectAll};
+use futures_util::{select, Stream, StreamExt};
+use http::header::{HeaderMap, HeaderName, HeaderValue};
+use serde::Deserialize;
+use serde_json::json;
+
+use super::request_context::RequestContext;
+use super::request_handler::create_allowed_headers;
+use crate::core::app_context::AppContext;
+use crate::core::async_graphql_hyper::{GraphQLRequest, GraphQLRequestLike};
+use crate::core::jit::{AnyResponse, JITExecutor};
+
+/// Name of the websocket sub-protocol as defined by
+/// [graphql-transport-ws](https://github.com/enisdenjo/graphql-ws/blob/master/PROTOCOL.md)
+pub const GRAPHQL_TRANSPORT_WS: &str = "graphql-transport-ws";
+
+/// Messages sent by the client over the websocket connection.
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ClientMessage {
+    ConnectionInit {
+        #[serde(default)]
+        payload: Option<serde_json::Value>,
+    },
+    Ping {
+        #[serde(default)]
+        payload: Option<serde_json::Value>,
+    },
+    Pong {
+        #[serde(default)]
+        payload: Option<serde_json::Value>,
+    },
+    Subscribe {
+        id: String,
+        payload: async_graphql::Request,
+    },
+    Complete {
+        id: String,
+    },
+}
+
+/// Messages sent by the server over the websocket connection.
+#[derive(Debug, Clone, PartialEq)]
+pub enum WsMessage {
+    Text(String),
+    Close(u16, String),
+}
+
+impl WsMessage {
+    fn connection_ack() -> Self {
+        WsMessage::Text(json!({"type": "connection_ack"}).to_string())
+    }
+
+    fn pong() -> Self {
+        WsMessage::Text(json!({"type": "pong"}).to_string())
+    }
+
+    fn next(id: &str, response: &AnyResponse<Vec<u8>>) -> Self {
+        let payload = if response.body.is_empty() {
+            "null"
+        } else {
+            std::str::from_utf8(&response.body).unwrap_or("null")
+        };
+
+        WsMessage::Text(format!(
+            r#"{{"id":{},"type":"next","payload":{}}}"#,
+            json!(id),
+            payload
+        ))
+    }
+
+    fn complete(id: &str) -> Self {
+        WsMessage::Text(json!({"id": id, "type": "complete"}).to_string())
+    }
+
+    fn close(code: u16, reason: impl Into<String>) -> Self {
+        WsMessage::Close(code, reason.into())
+    }
+}
+
+type SubscriptionEvent = (String, Option<AnyResponse<Vec<u8>>>);
+
+enum Event {
+    Message(Option<String>),
+    Subscription(SubscriptionEvent),
+}
+
+/// A single websocket connection speaking the graphql-transport-ws protocol.
+/// The connection is authenticated with the headers of the upgrade request
+/// merged with the payload of the `connection_init` message, which is then
+/// used to execute every subscription started on the connection.
+pub struct GraphQLWs<S> {
+    app_ctx: Arc<AppContext>,
+    headers: HeaderMap,
+    client_ip: Option<IpAddr>,
+    req_ctx: Option<Arc<RequestContext>>,
+    incoming: Fuse<S>,
+    subscriptions: SelectAll<BoxStream<'static, SubscriptionEvent>>,
+    handles: HashMap<String, AbortHandle>,
+    closed: bool,
+}
+
+impl<S> GraphQLWs<S>
+where
+    S: Stream<Item = String> + Send + Unpin + 'static,
+{
+    pub fn new(app_ctx: Arc<AppContext>, headers: HeaderMap, incoming: S) -> Self {
+        Self {
+            app_ctx,
+            headers,
+            client_ip: None,
+            req_ctx: None,
+            incoming: incoming.fuse(),
+            subscriptions: SelectAll::new(),
+            handles: HashMap::new(),
+            closed: false,
+        }
+    }
+
+    /// Sets the address of the client, used to identify it in rate limits.
+    pub fn client_ip(mut self, client_ip: Option<IpAddr>) -> Self {
+        self.client_ip = client_ip;
+        self
+    }
+
+    /// Drives the connection, producing the messages that should be sent back
+    /// to the client. The stream ends once the connection is closed.
+    pub fn into_stream(self) -> impl Stream<Item = WsMessage> + Send + 'static {
+        stream::unfold(self, |mut ws| async move {
+            if ws.closed {
+                return None;
+            }
+
+            loop {
+                let event = select! {
+                    text = ws.incoming.next() => Event::Message(text),
+                    event = ws.subscriptions.select_next_some() => Event::Subscription(event),
+                };
+
+                let message = match event {
+                    Event::Message(Some(text)) => ws.on_message(&text),
+                    // the client has closed the connection
+                    Event::Message(None) => return None,
+                    Event::Subscription((id, response)) => Some(ws.on_event(id, response)),
+                };
+
+                if let Some(message) = message {
+                    if matches!(message, WsMessage::Close(..)) {
+                        ws.closed = true;
+                    }
+                    return Some((message, ws));
+                }
+            }
+        })
+    }
+
+    fn on_message(&mut self, text: &str) -> Option<WsMessage> {
+        let message = match serde_json::from_str::<ClientMessage>(text) {
+            Ok(message) => message,
+            Err(err) => return Some(WsMessage::close(4400, err.to_string())),
+        };
+
+        match message {
+            ClientMessage::ConnectionInit { payload } => {
+                if self.req_ctx.is_some() {
+                    return Some(WsMessage::close(4429, "Too many initialisation requests"));
+                }
+
+                let mut headers = self.headers.clone();
+                if let Some(serde_json::Value::Object(payload)) = payload {
+                    for (key, value) in payload {
+                        let value = match value {
+                            serde_json::Value::String(value) => value,
+                            value => value.to_string(),
+                        };
+                        if let (Ok(key), Ok(value)) =
+                            (HeaderName::try_from(key), HeaderValue::try_from(value))
+                        {
+                            headers.insert(key, value);
+                        }
+                    }
+                }
+
+                let allowed_headers = create_allowed_headers(
+                    &headers,
+                    &self.app_ctx.blueprint.upstream.allowed_headers,
+                );
+                let auth_headers =
+                    create_allowed_headers(&headers, &self.app_ctx.blueprint.server.auth_headers);
+                // the operations are identified with the merged headers too
+                self.headers = headers;
+                let req_ctx = RequestContext::from(self.app_ctx.as_ref())
+                    .allowed_headers(allowed_headers)
+                    .auth_headers(auth_headers)
+                    .client_ip(self.client_ip);
+                self.req_ctx = Some(Arc::new(req_ctx));
+
+                Some(WsMessage::connection_ack())
+            }
+            ClientMessage::Ping { .. } => Some(WsMessage::pong()),
+            ClientMessage::Pong { .. } => None,
+            ClientMessage::Subscribe { id, payload } => {
+                let Some(req_ctx) = self.req_ctx.clone() else {
+                    return Some(WsMessage::close(4401, "Unauthorized"));
+                };
+
+                if self.handles.contains_key(&id) {
+                    return Some(WsMessage::close(
+                        4409,
+                        format!("Subscriber for {} already exists", id),
+                    ));
+                }
+
+                let request = GraphQLRequest(payload);
+                let operation_id = request.operation_id(&self.headers);
+                let exec = JITExecutor::new(self.app_ctx.clone(), req_ctx, operation_id);
+                let event_id = id.clone();
+                let complete_id = id.clone();
+                let events = exec
+                    .subscribe(request.0)
+                    .map(move |response| (event_id.clone(), Some(response)))
+                    .chain(stream::once(async move { (complete_id, None) }));
+
+                // Aborting the stream on `complete` from the client skips the
+                // trailing `complete` message, as required by the protocol.
+                let (events, handle) = stream::abortable(events);
+                self.handles.insert(id, handle);
+                self.subscriptions.push(events.boxed());
+
+                None
+            }
+            ClientMessage::Complete { id } => {
+                if let Some(handle) = self.handles.remove(&id) {
+                    handle.abort();
+                }
+
+                None
+            }
+        }
+    }
+
+    fn on_event(&mut self, id: String, response: Option<AnyResponse<Vec<u8>>>) -> WsMessage {
+        match response {
+            Some(response) => WsMessage::next(&id, &response),
+            None => {
+                self.handles.remove(&id);
+                WsMessage::complete(&id)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tailcall_valid::Validator;
+
+    use super::*;
+    use crate::core::blueprint::Blueprint;
+    use crate::core::config::{Config, ConfigModule};
+    use crate::core::rest::EndpointSet;
+    use crate::core::runtime::test::init;
+
+    fn app_ctx() -> Arc<AppContext> {
+        let sdl = r#"
+            schema { query: Query subscription: Subscription }
+            type Query { hello: String @expr(body: "world") }
+            type Subscription { news: News @expr(body: {id: 1}) }
+            type News { id: Int }
+        "#;
+        let config = Config::from_sdl(sdl).to_result().unwrap();
+        let blueprint = Blueprint::try_from(&ConfigModule::from(config)).unwrap();
+        Arc::new(AppContext::new(
+            blueprint,
+            init(None),
+            EndpointSet::default(),
+        ))
+    }
+
+    fn text(message: &WsMessage) -> serde_json::Value {
+        let WsMessage::Text(text) = message else {
+            panic!("expected text message")
+        };
+        serde_json::from_str(text).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_subscription_flow() {
+        let incoming = stream::iter(vec![
+            r#"{"type":"connection_init","payload":{"authorization":"Bearer 123"}}"#.to_string(),
+            r#"{"id":"1","type":"subscribe","payload":{"query":"subscription { news { id } }"}}"#
+                .to_string(),
+        ])
+        .chain(stream::pending());
+
+        let messages = GraphQLWs::new(app_ctx(), HeaderMap::new(), incoming)
+            .into_stream()
+            .take(3)
+            .collect::<Vec<_>>()
+            .await;
+
+        assert_eq!(text(&messages[0]), json!({"type": "connection_ack"}));
+        assert_eq!(
+            text(&messages[1]),
+            json!({"id": "1", "type": "next", "payload": {"data": {"news": {"id": 1}}}})
+        );
+        assert_eq!(text(&messages[2]), json!({"id": "1", "type": "complete"}));
+    }
+
+    #[test]
+    fn test_subscribe_before_init() {
+        let mut ws = GraphQLWs::new(app_ctx(), HeaderMap::new(), stream::empty::<String>());
+
+        let message = ws.on_message(
+            r#"{"id":"1","type":"subscribe","payload":{"query":"subscription { news { id } }"}}"#,
+        );
+
+        assert_eq!(message, Some(WsMessage::close(4401, "Unauthorized")));
+    }
+
+    #[test]
+    fn test_connection_init_merges_headers() {
+        let mut ws = GraphQLWs::new(app_ctx(), HeaderMap::new(), stream::empty::<String>());
+
+        let message =
+            ws.on_message(r#"{"type":"connection_init","payload":{"authorization":"Bearer 123"}}"#);
+
+        assert_eq!(message, Some(WsMessage::connection_ack()));
+        assert_eq!(ws.headers.get("authorization").unwrap(), "Bearer 123");
+    }
+
+    #[test]
+    fn test_parse_connection_init() {
+        let message: ClientMessage = serde_json::from_str(
+            r#"{"type":"connection_init","payload":{"authorization":"Bearer 123"}}"#,
+        )
+        .unwrap();
+
+        assert!(matches!(
+            message,
+            ClientMessage::ConnectionInit { payload: Some(serde_json::Value::Object(_)) }
+        ));
+    }
+
+    #[test]
+    fn test_parse_subscribe() {
+        let message: ClientMessage = serde_json::from_str(
+            r#"{"id":"1","type":"subscribe","payload":{"query":"subscription { news { id } }","variables":{"a":1}}}"#,
+        )
+        .unwrap();
+
+        let ClientMessage::Subscribe { id, payload } = message else {
+            panic!("expected subscribe message")
+        };
+        assert_eq!(id, "1");
+        assert_eq!(payload.query, "subscription { news { id } }");
+        assert_eq!(payload.variables.len(), 1);
+    }
+
+    #[test]
+    fn test_parse_complete() {
+        let message: ClientMessage =
+            serde_json::from_str(r#"{"id":"1","type":"complete"}"#).unwrap();
+
+        assert!(matches!(message, ClientMessage::Complete { id } if id == "1"));
+    }
+
+    #[test]
+    fn test_parse_invalid() {
+        let message = serde_json::from_str::<ClientMessage>(r#"{"type":"start","id":"1"}"#);
+
+        assert!(message.is_err());
+    }
+
+    #[test]
+    fn test_next_message() {
+        let response = AnyResponse {
+            body: Arc::new(br#"{"data":{"news":{"id":1}}}"#.to_vec()),
+            ..Default::default()
+        };
+
+        let WsMessage::Text(text) = WsMessage::next("1", &response) else {
+            panic!("expected text message")
+        };
+        let actual: serde_json::Value = serde_json::from_str(&text).unwrap();
+
+        assert_eq!(
+            actual,
+            json!({"id": "1", "type": "next", "payload": {"data": {"news": {"id": 1}}}})
+        );
+    }
+
+    #[test]
+    fn test_complete_message() {
+        let WsMessage::Text(text) = WsMessage::complete("1") else {
+            panic!("expected text message")
+        };
+        let actual: serde_json::Value = serde_json::from_str(&text).unwrap();
+
+        assert_eq!(actual, json!({"id": "1", "type": "complete"}));
+    }
+}
@@ -1,24 +1,31 @@
 pub use cache::*;
 pub use data_loader::*;
 pub use data_loader_request::*;
+pub use graphql_ws::{GraphQLWs, WsMessage, GRAPHQL_TRANSPORT_WS};
 use http::header::HeaderValue;
 pub use method::Method;
 pub use query_encoder::QueryEncoder;
 pub use request_context::RequestContext;
-pub use request_handler::{handle_request, API_URL_PREFIX};
+pub use request_handler::{handle_request, ClientCertificate, ClientIp, API_URL_PREFIX};
 pub use request_template::RequestTemplate;
 pub use response::*;
+pub use sse::parse_events;
 
+mod admin;
 mod cache;
+pub mod codec;
 mod data_loader;
 mod data_loader_request;
+mod graphql_ws;
 mod method;
+pub mod multipart;
 mod query_encoder;
 mod request_context;
 mod request_handler;
 mod request_template;
 mod response;
 pub mod showcase;
+mod sse;
 mod telemetry;
 
 pub static TAILCALL_HTTPS_ORIGIN: HeaderValue = HeaderValue::from_static("https://tailcall.run");
@@ -36,3 +43,16 @@ impl HttpFilter {
         HttpFilter { on_request: on_request.to_owned() }
     }
 }
+
+/// Returns the error of reqwest behind an error returned by an [HttpIO]
+/// implementation, if any. Clients with middlewares wrap it in an error of
+/// `reqwest_middleware`.
+///
+/// [HttpIO]: crate::core::HttpIO
+pub fn reqwest_error(error: &anyhow::Error) -> Option<&reqwest::Error> {
+    match error.downcast_ref::<reqwest_middleware::Error>() {
+        Some(reqwest_middleware::Error::Reqwest(error)) => Some(error),
+        Some(_) => None,
+        None => error.downcast_ref::<reqwest::Error>(),
+    }
+}
@@ -0,0 +1,266 @@
+use anyhow::{anyhow, bail};
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// A file uploaded with a GraphQL multipart request. This is the value of the
+/// `Upload` scalar, the content of the file is encoded in base64 so that it can
+/// be used like any other JSON value.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct Upload {
+    pub filename: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub content_type: Option<String>,
+    pub content: String,
+}
+
+impl Upload {
+    pub fn new(filename: String, content_type: Option<String>, content: &[u8]) -> Self {
+        Self { filename, content_type, content: STANDARD.encode(content) }
+    }
+
+    pub fn bytes(&self) -> anyhow::Result<Vec<u8>> {
+        Ok(STANDARD.decode(&self.content)?)
+    }
+}
+
+struct Part {
+    name: String,
+    filename: Option<String>,
+    content_type: Option<String>,
+    content: Vec<u8>,
+}
+
+impl Part {
+    fn new(name: &str, value: &Value) -> anyhow::Result<Self> {
+        if let Ok(upload) = Upload::deserialize(value) {
+            // the content type is written as it is in the header of the part
+            if let Some(content_type) = upload
+                .content_type
+                .as_ref()
+                .filter(|content_type| content_type.chars().any(char::is_control))
+            {
+                bail!(
+                    "Invalid content type `{}` of an upload",
+                    content_type.escape_debug()
+                );
+            }
+            return Ok(Self {
+                name: name.to_string(),
+                content: upload.bytes()?,
+                filename: Some(upload.filename),
+                content_type: upload.content_type,
+            });
+        }
+
+        let content = match value {
+            Value::String(text) => text.clone().into_bytes(),
+            value => value.to_string().into_bytes(),
+        };
+
+        Ok(Self {
+            name: name.to_string(),
+            filename: None,
+            content_type: None,
+            content,
+        })
+    }
+
+    fn contains(&self, boundary: &str) -> bool {
+        let boundary = boundary.as_bytes();
+        self.content
+            .windows(boundary.len())
+            .any(|window| window == boundary)
+    }
+}
+
+/// Quotes a parameter of the `Content-Disposition` header the way browsers do,
+/// percent-encoding the quotes and the control characters
+fn quote(value: &str) -> String {
+    value
+        .chars()
+        .map(|c| match c {
+            '"' => "%22".to_string(),
+            c if c.is_ascii_control() => format!("%{:02X}", c as u8),
+            c => c.to_string(),
+        })
+        .collect()
+}
+
+/// Encodes an object as a `multipart/form-data` body, returning the boundary
+/// along with the body. Uploads are sent as files, lists of uploads as several
+/// files with the same name, `null` values are skipped and the other values are
+/// sent as text: strings as they are and everything else in JSON.
+pub fn to_form_data(value: &Value) -> anyhow::Result<(String, Vec<u8>)> {
+    let fields = value
+        .as_object()
+        .ok_or_else(|| anyhow!("The body of a multipart/form-data request has to be an object"))?;
+
+    let mut parts = Vec::new();
+    for (name, value) in fields {
+        match value {
+            Value::Null => {}
+            Value::Array(list) if list.iter().all(|item| Upload::deserialize(item).is_ok()) => {
+                for item in list {
+                    parts.push(Part::new(name, item)?);
+                }
+            }
+            value => parts.push(Part::new(name, value)?),
+        }
+    }
+
+    // the boundary only has to be missing from the parts, picking the first one
+    // that is keeps the encoding of the same body stable
+    let boundary = (0..)
+        .map(|i| format!("tailcall-boundary-{}", i))
+        .find(|boundary| parts.iter().all(|part| !part.contains(boundary)))
+        .unwrap_or_default();
+
+    let mut body = Vec::new();
+    for part in parts {
+        body.extend_from_slice(format!("--{}\r\n", boundary).as_bytes());
+        body.extend_from_slice(
+            format!(
+                "Content-Disposition: form-data; name=\"{}\"",
+                quote(&part.name)
+            )
+            .as_bytes(),
+        );
+        if let Some(filename) = &part.filename {
+            body.extend_from_slice(format!("; filename=\"{}\"", quote(filename)).as_bytes());
+        }
+        body.extend_from_slice(b"\r\n");
+        if let Some(content_type) = &part.content_type {
+            body.extend_from_slice(format!("Content-Type: {}\r\n", content_type).as_bytes());
+        }
+        body.extend_from_slice(b"\r\n");
+        body.extend_from_slice(&part.content);
+        body.extend_from_slice(b"\r\n");
+    }
+    body.extend_from_slice(format!("--{}--\r\n", boundary).as_bytes());
+
+    Ok((boundary, body))
+}
+
+/// Sets the value at a path like `variables.files.0` of the `map` of a GraphQL
+/// multipart request.
+pub fn set_path(target: &mut Value, path: &str, value: Value) -> anyhow::Result<()> {
+    let slot = path
+        .split('.')
+        .try_fold(target, |target, key| match target {
+            Value::Object(map) => map.get_mut(key),
+            Value::Array(list) => key.parse::<usize>().ok().and_then(|i| list.get_mut(i)),
+            _ => None,
+        });
+
+    match slot {
+        Some(slot) => {
+            *slot = value;
+            Ok(())
+        }
+        None => bail!(
+            "Invalid path `{}` in the map of the multipart request",
+            path
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn upload(filename: &str, content: &str) -> Value {
+        serde_json::to_value(Upload::new(
+            filename.to_string(),
+            Some("text/plain".to_string()),
+            content.as_bytes(),
+        ))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_to_form_data() {
+        let value = json!({
+            "title": "Report",
+            "tags": ["a", "b"],
+            "draft": null,
+            "file": upload("a.txt", "hello"),
+        });
+
+        let (boundary, body) = to_form_data(&value).unwrap();
+        let expected = format!(
+            "--{b}\r\nContent-Disposition: form-data; name=\"title\"\r\n\r\nReport\r\n\
+             --{b}\r\nContent-Disposition: form-data; name=\"tags\"\r\n\r\n[\"a\",\"b\"]\r\n\
+             --{b}\r\nContent-Disposition: form-data; name=\"file\"; filename=\"a.txt\"\r\n\
+             Content-Type: text/plain\r\n\r\nhello\r\n\
+             --{b}--\r\n",
+            b = boundary
+        );
+
+        assert_eq!(String::from_utf8(body).unwrap(), expected);
+    }
+
+    #[test]
+    fn test_to_form_data_list_of_uploads() {
+        let value = json!({ "files": [upload("a.txt", "a"), upload("b.txt", "b")] });
+
+        let (_, body) = to_form_data(&value).unwrap();
+        let body = String::from_utf8(body).unwrap();
+
+        assert_eq!(body.matches("name=\"files\"").count(), 2);
+        assert!(body.contains("filename=\"b.txt\""));
+    }
+
+    #[test]
+    fn test_to_form_data_escapes_filename() {
+        let value = json!({ "file": upload("a\"\r\nb\t.txt", "hello") });
+
+        let (_, body) = to_form_data(&value).unwrap();
+        let body = String::from_utf8(body).unwrap();
+
+        assert!(body.contains("filename=\"a%22%0D%0Ab%09.txt\""));
+    }
+
+    #[test]
+    fn test_to_form_data_rejects_invalid_content_type() {
+        let upload = Upload::new(
+            "a.txt".to_string(),
+            Some("text/plain\r\nX-Injected: 1".to_string()),
+            b"hello",
+        );
+        let value = json!({ "file": serde_json::to_value(upload).unwrap() });
+
+        assert!(to_form_data(&value).is_err());
+    }
+
+    #[test]
+    fn test_boundary_is_missing_from_content() {
+        let value = json!({ "text": "--tailcall-boundary-0" });
+
+        let (boundary, _) = to_form_data(&value).unwrap();
+
+        assert_eq!(boundary, "tailcall-boundary-1");
+    }
+
+    #[test]
+    fn test_to_form_data_requires_object() {
+        assert!(to_form_data(&json!("foo")).is_err());
+    }
+
+    #[test]
+    fn test_set_path() {
+        let mut operations = json!([{ "variables": { "files": [null, null] } }]);
+
+        set_path(&mut operations, "0.variables.files.1", json!("file")).unwrap();
+
+        assert_eq!(
+            operations,
+            json!([{ "variables": { "files": [null, "file"] } }])
+        );
+        assert!(set_path(&mut operations, "0.variables.file", json!("file")).is_err());
+    }
+}
@@ -1,3 +1,4 @@
+use std::net::IpAddr;
 use std::num::NonZeroU64;
 use std::str::FromStr;
 use std::sync::{Arc, Mutex};
@@ -12,7 +13,7 @@ use crate::core::blueprint::{Server, Upstream};
 use crate::core::data_loader::{DataLoader, DedupeResult};
 use crate::core::graphql::GraphqlDataLoader;
 use crate::core::grpc::data_loader::GrpcDataLoader;
-use crate::core::http::{DataLoaderRequest, HttpDataLoader};
+use crate::core::http::{ClientCertificate, DataLoaderRequest, HttpDataLoader};
 use crate::core::ir::model::IoId;
 use crate::core::ir::Error;
 use crate::core::runtime::TargetRuntime;
@@ -27,6 +28,9 @@ pub struct RequestContext {
     // A subset of all the headers received in the GraphQL Request that will be sent to the
     // upstream.
     pub allowed_headers: HeaderMap,
+    // Headers of the request the API keys are read from, never sent to the
+    // upstream.
+    pub auth_headers: HeaderMap,
     pub http_data_loaders: Arc<Vec<DataLoader<DataLoaderRequest, HttpDataLoader>>>,
     pub gql_data_loaders: Arc<Vec<DataLoader<DataLoaderRequest, GraphqlDataLoader>>>,
     pub grpc_data_loaders: Arc<Vec<DataLoader<grpc::DataLoaderRequest, GrpcDataLoader>>>,
@@ -35,6 +39,17 @@ pub struct RequestContext {
     pub runtime: TargetRuntime,
     pub cache: DedupeResult<IoId, ConstValue, Error>,
     pub dedupe_handler: Arc<DedupeResult<IoId, ConstValue, Error>>,
+    // Refreshes of stale cached values in progress, shared by all the requests.
+    pub dedupe_revalidation_handler: Arc<DedupeResult<IoId, ConstValue, Error>>,
+    // Address of the client that sent the request, when known.
+    pub client_ip: Option<IpAddr>,
+    // Claims of the credentials verified for the request, by a JWT, by an
+    // introspection endpoint or by an API key.
+    pub claims: Arc<Mutex<Option<serde_json::Value>>>,
+    // Certificate presented by the client over mutual TLS, when any.
+    pub client_cert: Option<ClientCertificate>,
+    // Query string of the URL of the request, when any.
+    pub query: Option<String>,
 }
 
 impl RequestContext {
@@ -52,9 +67,43 @@ impl RequestContext {
             runtime: target_runtime,
             cache: DedupeResult::new(true),
             dedupe_handler: Arc::new(DedupeResult::new(false)),
+            dedupe_revalidation_handler: Arc::new(DedupeResult::new(false)),
             allowed_headers: HeaderMap::new(),
+            auth_headers: HeaderMap::new(),
+            client_ip: None,
+            claims: Arc::new(Mutex::new(None)),
+            client_cert: None,
+            query: None,
         }
     }
+
+    /// Copies the context for the work that goes on once the request is
+    /// answered. The copy shares the data loaders and the runtime, while the
+    /// headers and the cache policy it collects are discarded.
+    pub fn detach(&self) -> RequestContext {
+        RequestContext {
+            server: self.server.clone(),
+            upstream: self.upstream.clone(),
+            x_response_headers: Arc::new(Mutex::new(HeaderMap::new())),
+            cookie_headers: None,
+            allowed_headers: self.allowed_headers.clone(),
+            auth_headers: self.auth_headers.clone(),
+            http_data_loaders: self.http_data_loaders.clone(),
+            gql_data_loaders: self.gql_data_loaders.clone(),
+            grpc_data_loaders: self.grpc_data_loaders.clone(),
+            min_max_age: Arc::new(Mutex::new(None)),
+            cache_public: Arc::new(Mutex::new(None)),
+            runtime: self.runtime.clone(),
+            cache: DedupeResult::new(true),
+            dedupe_handler: self.dedupe_handler.clone(),
+            dedupe_revalidation_handler: self.dedupe_revalidation_handler.clone(),
+            client_ip: self.client_ip,
+            claims: Arc::new(Mutex::new(self.claims.lock().unwrap().clone())),
+            client_cert: self.client_cert.clone(),
+            query: self.query.clone(),
+        }
+    }
+
     fn set_min_max_age_conc(&self, min_max_age: i32) {
         *self.min_max_age.lock().unwrap() = Some(min_max_age);
     }
@@ -193,6 +242,7 @@ impl From<&AppContext> for RequestContext {
             x_response_headers: Arc::new(Mutex::new(HeaderMap::new())),
             cookie_headers,
             allowed_headers: HeaderMap::new(),
+            auth_headers: HeaderMap::new(),
             http_data_loaders: app_ctx.http_data_loaders.clone(),
             gql_data_loaders: app_ctx.gql_data_loaders.clone(),
             grpc_data_loaders: app_ctx.grpc_data_loaders.clone(),
@@ -201,6 +251,11 @@ impl From<&AppContext> for RequestContext {
             runtime: app_ctx.runtime.clone(),
             cache: DedupeResult::new(true),
             dedupe_handler: app_ctx.dedupe_handler.clone(),
+            dedupe_revalidation_handler: app_ctx.dedupe_revalidation_handler.clone(),
+            client_ip: None,
+            claims: Arc::new(Mutex::new(None)),
+            client_cert: None,
+            query: None,
         }
     }
 }
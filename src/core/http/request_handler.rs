@@ -1,9 +1,16 @@
 use std::collections::BTreeSet;
+use std::convert::Infallible;
+use std::fmt::Display;
+use std::net::IpAddr;
 use std::ops::Deref;
 use std::sync::Arc;
 
 use anyhow::Result;
+use async_graphql::parser::types::{ExecutableDocument, OperationType, Selection, SelectionSet};
 use async_graphql::ServerError;
+use futures_util::future::ready;
+use futures_util::stream::{self, StreamExt};
+use hyper::body::Bytes;
 use hyper::header::{self, HeaderValue, CONTENT_TYPE};
 use hyper::http::request::Parts;
 use hyper::http::Method;
@@ -11,20 +18,44 @@ use hyper::{Body, HeaderMap, Request, Response, StatusCode};
 use opentelemetry::trace::SpanKind;
 use opentelemetry_semantic_conventions::trace::{HTTP_REQUEST_METHOD, HTTP_ROUTE};
 use prometheus::{Encoder, ProtobufEncoder, TextEncoder, TEXT_FORMAT};
+use rustls_pki_types::CertificateDer;
 use serde::de::DeserializeOwned;
 use tracing::Instrument;
 use tracing_opentelemetry::OpenTelemetrySpanExt;
 
 use super::request_context::RequestContext;
 use super::telemetry::{get_response_status_code, RequestCounter};
-use super::{showcase, telemetry, TAILCALL_HTTPS_ORIGIN, TAILCALL_HTTP_ORIGIN};
+use super::{admin, showcase, telemetry, TAILCALL_HTTPS_ORIGIN, TAILCALL_HTTP_ORIGIN};
 use crate::core::app_context::AppContext;
-use crate::core::async_graphql_hyper::{GraphQLRequestLike, GraphQLResponse};
+use crate::core::async_graphql_hyper::{
+    multipart_boundary, parse_multipart_request, GraphQLRequest, GraphQLRequestLike,
+    GraphQLResponse, UploadTooLarge,
+};
 use crate::core::blueprint::telemetry::TelemetryExporter;
 use crate::core::config::{PrometheusExporter, PrometheusFormat};
 use crate::core::jit::JITExecutor;
 
 pub const API_URL_PREFIX: &str = "/api";
+const TEXT_EVENT_STREAM: &str = "text/event-stream";
+const MULTIPART_MIXED: &str = "multipart/mixed";
+const MULTIPART_MIXED_BOUNDARY: &str = "graphql";
+const PREFLIGHT_HEADERS: [&str; 2] = ["apollo-require-preflight", "x-apollo-operation-name"];
+
+/// Address of the client that sent a request. Servers that know the address of
+/// the connection insert it in the extensions of the request.
+#[derive(Clone, Copy, Debug)]
+pub struct ClientIp(pub IpAddr);
+
+/// Details of the certificate presented by the client over mutual TLS. Servers
+/// that verify client certificates insert it in the extensions of the request.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ClientCertificate {
+    pub subject: String,
+    pub san: Vec<String>,
+    /// The CA certificates the chain presented by the client was verified
+    /// against
+    pub issuers: Vec<CertificateDer<'static>>,
+}
 
 fn prometheus_metrics(prometheus_exporter: &PrometheusExporter) -> Result<Response<Body>> {
     let metric_families = prometheus::default_registry().gather();
@@ -54,10 +85,45 @@ fn not_found() -> Result<Response<Body>> {
         .body(Body::empty())?)
 }
 
+/// Address of the client as reported by the proxy in front of the server, for
+/// the runtimes that don't know the address of the connection, like Cloudflare
+/// workers and Lambda functions. The last address of `X-Forwarded-For` is the
+/// one appended by the nearest proxy, the others can be set by the client.
+fn forwarded_ip(headers: &HeaderMap) -> Option<IpAddr> {
+    let parse = |value: &str| value.trim().parse().ok();
+
+    headers
+        .get("cf-connecting-ip")
+        .and_then(|value| value.to_str().ok())
+        .and_then(parse)
+        .or_else(|| {
+            headers
+                .get_all("x-forwarded-for")
+                .iter()
+                .filter_map(|value| value.to_str().ok())
+                .flat_map(|value| value.split(','))
+                .last()
+                .and_then(parse)
+        })
+}
+
 fn create_request_context(req: &Request<Body>, app_ctx: &AppContext) -> RequestContext {
     let allowed_headers =
         create_allowed_headers(req.headers(), &app_ctx.blueprint.upstream.allowed_headers);
-    RequestContext::from(app_ctx).allowed_headers(allowed_headers)
+    let auth_headers =
+        create_allowed_headers(req.headers(), &app_ctx.blueprint.server.auth_headers);
+    let client_ip = req
+        .extensions()
+        .get::<ClientIp>()
+        .map(|ip| ip.0)
+        .or_else(|| forwarded_ip(req.headers()));
+    let client_cert = req.extensions().get::<ClientCertificate>().cloned();
+    RequestContext::from(app_ctx)
+        .allowed_headers(allowed_headers)
+        .auth_headers(auth_headers)
+        .client_ip(client_ip)
+        .client_cert(client_cert)
+        .query(req.uri().query().map(str::to_owned))
 }
 
 pub fn update_response_headers(
@@ -90,27 +156,294 @@ pub async fn graphql_request<T: DeserializeOwned + GraphQLRequestLike>(
     req_counter.set_http_route("/graphql");
     let req_ctx = Arc::new(create_request_context(&req, app_ctx));
     let (req, body) = req.into_parts();
-    let bytes = hyper::body::to_bytes(body).await?;
-    let graphql_request = serde_json::from_slice::<T>(&bytes);
+    match read_graphql_request::<T>(&req, body, app_ctx).await? {
+        Ok(request) => execute_query(app_ctx, &req_ctx, request, req).await,
+        Err(response) => Ok(response),
+    }
+}
+
+/// Reads the GraphQL request from the body and resolves its persisted query,
+/// if any. Fails with the response to send back when the request can't be
+/// executed.
+async fn read_graphql_request<T: DeserializeOwned + GraphQLRequestLike>(
+    req: &Parts,
+    body: Body,
+    app_ctx: &AppContext,
+) -> Result<std::result::Result<T, Response<Body>>> {
+    let graphql_request = match multipart_boundary(&req.headers) {
+        Some(boundary) => {
+            if !is_preflighted(&req.headers) {
+                return unexpected_request(
+                    StatusCode::BAD_REQUEST,
+                    format!(
+                        "Multipart requests require one of the {} headers",
+                        PREFLIGHT_HEADERS.join(", ")
+                    ),
+                )
+                .map(Err);
+            }
+
+            let max_size = app_ctx.blueprint.server.max_upload_size;
+            match parse_multipart_request::<T>(boundary, body, max_size).await {
+                Err(err) if err.is::<UploadTooLarge>() => {
+                    return unexpected_request(StatusCode::PAYLOAD_TOO_LARGE, err).map(Err);
+                }
+                result => result,
+            }
+        }
+        None => {
+            let bytes = hyper::body::to_bytes(body).await?;
+            serde_json::from_slice::<T>(&bytes).map_err(|err| {
+                tracing::error!(
+                    "Failed to parse request: {}",
+                    String::from_utf8_lossy(&bytes)
+                );
+                anyhow::Error::from(err)
+            })
+        }
+    };
     match graphql_request {
-        Ok(request) => {
-            let resp = execute_query(app_ctx, &req_ctx, request, req).await?;
-            Ok(resp)
+        Ok(mut request) => {
+            if let Err(response) = resolve_persisted_query(&mut request, app_ctx).await {
+                return response.into_response().map(Err);
+            }
+
+            Ok(Ok(request))
         }
-        Err(err) => {
-            tracing::error!(
-                "Failed to parse request: {}",
-                String::from_utf8(bytes.to_vec()).unwrap()
-            );
+        Err(err) => unexpected_request(StatusCode::OK, err).map(Err),
+    }
+}
+
+/// Responds to a request that can't be executed with the reason as a GraphQL
+/// error
+fn unexpected_request(status: StatusCode, err: impl Display) -> Result<Response<Body>> {
+    let mut response = async_graphql::Response::default();
+    let server_error = ServerError::new(format!("Unexpected GraphQL Request: {}", err), None);
+    response.errors = vec![server_error];
+
+    let mut response = GraphQLResponse::from(response).into_response()?;
+    *response.status_mut() = status;
+    Ok(response)
+}
 
+/// Tells if the request has one of the [PREFLIGHT_HEADERS]. Browsers send
+/// `multipart/form-data` requests across origins without a CORS preflight, so
+/// they're only accepted along with a header that requires one.
+fn is_preflighted(headers: &HeaderMap) -> bool {
+    PREFLIGHT_HEADERS
+        .iter()
+        .any(|name| headers.get(*name).is_some_and(|value| !value.is_empty()))
+}
+
+fn accepts_event_stream(headers: &HeaderMap) -> bool {
+    headers
+        .get(header::ACCEPT)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value.contains(TEXT_EVENT_STREAM))
+}
+
+fn accepts_multipart_mixed(headers: &HeaderMap) -> bool {
+    headers
+        .get(header::ACCEPT)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value.contains(MULTIPART_MIXED))
+}
+
+/// Resolves the documents sent with Automatic Persisted Queries. Skipped when
+/// only trusted documents are allowed, since those are looked up while planning
+/// and registering new documents is not possible.
+async fn resolve_persisted_query<T: GraphQLRequestLike>(
+    request: &mut T,
+    app_ctx: &AppContext,
+) -> Result<(), GraphQLResponse> {
+    if app_ctx.blueprint.server.trusted_documents.is_some() {
+        return Ok(());
+    }
+
+    let cache = app_ctx.runtime.persisted_queries.as_ref();
+    request.resolve_persisted_query(cache).await
+}
+
+fn is_subscription(request: &async_graphql::Request) -> bool {
+    let Ok(document) = async_graphql::parser::parse_query(&request.query) else {
+        return false;
+    };
+
+    let operation = match &request.operation_name {
+        Some(name) => document
+            .operations
+            .iter()
+            .find(|(operation_name, _)| operation_name.map(|n| n.as_str()) == Some(name.as_str()))
+            .map(|(_, operation)| operation),
+        None => document
+            .operations
+            .iter()
+            .next()
+            .map(|(_, operation)| operation),
+    };
+
+    operation.is_some_and(|operation| operation.node.ty == OperationType::Subscription)
+}
+
+/// Checks if the document uses `@defer` or `@stream`
+fn is_incremental(document: &ExecutableDocument) -> bool {
+    fn has_incremental_directive(selection_set: &SelectionSet) -> bool {
+        selection_set.items.iter().any(|selection| {
+            let (directives, selection_set) = match &selection.node {
+                Selection::Field(field) => (&field.node.directives, &field.node.selection_set),
+                Selection::FragmentSpread(spread) => {
+                    let directives = &spread.node.directives;
+                    return directives.iter().any(|d| d.node.name.node == "defer");
+                }
+                Selection::InlineFragment(fragment) => {
+                    (&fragment.node.directives, &fragment.node.selection_set)
+                }
+            };
+
+            directives
+                .iter()
+                .any(|d| matches!(d.node.name.node.as_str(), "defer" | "stream"))
+                || has_incremental_directive(&selection_set.node)
+        })
+    }
+
+    document
+        .operations
+        .iter()
+        .any(|(_, operation)| has_incremental_directive(&operation.node.selection_set.node))
+        || document
+            .fragments
+            .values()
+            .any(|fragment| has_incremental_directive(&fragment.node.selection_set.node))
+}
+
+fn event(name: &str, data: &[u8]) -> Bytes {
+    let mut event = format!("event: {}\ndata: ", name).into_bytes();
+    event.extend_from_slice(data);
+    event.extend_from_slice(b"\n\n");
+    Bytes::from(event)
+}
+
+/// Executes the operation and streams the responses back as Server-Sent
+/// Events, following the distinct connections mode of the
+/// [GraphQL over SSE](https://github.com/enisdenjo/graphql-sse/blob/master/PROTOCOL.md)
+/// protocol. Subscriptions produce an event for every value of the source
+/// stream, while queries and mutations produce a single one.
+#[tracing::instrument(skip_all, fields(otel.name = "graphQL", otel.kind = ?SpanKind::Server))]
+async fn event_stream_request(
+    req: Request<Body>,
+    app_ctx: &Arc<AppContext>,
+    req_counter: &mut RequestCounter,
+) -> Result<Response<Body>> {
+    req_counter.set_http_route("/graphql");
+    let req_ctx = Arc::new(create_request_context(&req, app_ctx));
+    let (req, body) = req.into_parts();
+    let bytes = hyper::body::to_bytes(body).await?;
+    let mut request = match serde_json::from_slice::<GraphQLRequest>(&bytes) {
+        Ok(request) => request,
+        Err(err) => {
             let mut response = async_graphql::Response::default();
             let server_error =
                 ServerError::new(format!("Unexpected GraphQL Request: {}", err), None);
             response.errors = vec![server_error];
 
-            Ok(GraphQLResponse::from(response).into_response()?)
+            return Ok(GraphQLResponse::from(response).into_response()?);
         }
+    };
+
+    if let Err(response) = resolve_persisted_query(&mut request, app_ctx).await {
+        return response.into_response();
     }
+
+    let operation_id = request.operation_id(&req.headers);
+    let exec =
+        JITExecutor::new(app_ctx.clone(), req_ctx.clone(), operation_id).with_headers(&req.headers);
+    let responses = if is_subscription(&request.0) {
+        exec.subscribe(request.0)
+    } else if request.0.parsed_query().is_ok_and(is_incremental) {
+        exec.execute_incremental(request.0)
+    } else {
+        let response = exec.execute(request.0).await;
+        stream::once(ready(response)).boxed()
+    };
+
+    let events = responses
+        .map(|response| event("next", &response.body))
+        .chain(stream::once(ready(event("complete", b""))))
+        .map(Ok::<_, Infallible>);
+
+    let mut response = Response::builder()
+        .status(StatusCode::OK)
+        .header(CONTENT_TYPE, TEXT_EVENT_STREAM)
+        .header(header::CACHE_CONTROL, "no-cache")
+        .body(Body::wrap_stream(events))?;
+
+    update_response_headers(&mut response, &req_ctx, app_ctx);
+    Ok(response)
+}
+
+fn multipart_part(body: &[u8]) -> Bytes {
+    let mut part = format!(
+        "\r\n--{}\r\nContent-Type: application/json; charset=utf-8\r\n\r\n",
+        MULTIPART_MIXED_BOUNDARY
+    )
+    .into_bytes();
+    part.extend_from_slice(body);
+    Bytes::from(part)
+}
+
+/// Executes an operation with `@defer` or `@stream` and streams the payloads
+/// back as the parts of a `multipart/mixed` response, following the
+/// [incremental delivery over HTTP](https://github.com/graphql/graphql-over-http/blob/main/rfcs/IncrementalDelivery.md)
+/// RFC. Other operations are handled as usual.
+#[tracing::instrument(skip_all, fields(otel.name = "graphQL", otel.kind = ?SpanKind::Server))]
+async fn incremental_request<T: DeserializeOwned + GraphQLRequestLike>(
+    req: Request<Body>,
+    app_ctx: &Arc<AppContext>,
+    req_counter: &mut RequestCounter,
+) -> Result<Response<Body>> {
+    req_counter.set_http_route("/graphql");
+    let req_ctx = Arc::new(create_request_context(&req, app_ctx));
+    let (parts, body) = req.into_parts();
+    let mut request = match read_graphql_request::<T>(&parts, body, app_ctx).await? {
+        Ok(request) => request,
+        Err(response) => return Ok(response),
+    };
+
+    // the parsed document is kept by the request for its execution
+    if !request.parse_query().is_some_and(is_incremental) {
+        return execute_query(app_ctx, &req_ctx, request, parts).await;
+    }
+    let operation_id = request.operation_id(&parts.headers);
+    let request = match request.into_single() {
+        Ok(request) => request,
+        Err(request) => return execute_query(app_ctx, &req_ctx, request, parts).await,
+    };
+
+    let exec = JITExecutor::new(app_ctx.clone(), req_ctx.clone(), operation_id);
+    let parts = exec
+        .execute_incremental(request)
+        .map(|response| multipart_part(&response.body))
+        .chain(stream::once(ready(Bytes::from(format!(
+            "\r\n--{}--\r\n",
+            MULTIPART_MIXED_BOUNDARY
+        )))))
+        .map(Ok::<_, Infallible>);
+
+    let mut response = Response::builder()
+        .status(StatusCode::OK)
+        .header(
+            CONTENT_TYPE,
+            format!(
+                "{}; boundary=\"{}\"; deferSpec=20220824",
+                MULTIPART_MIXED, MULTIPART_MIXED_BOUNDARY
+            ),
+        )
+        .header(header::CACHE_CONTROL, "no-cache")
+        .body(Body::wrap_stream(parts))?;
+
+    update_response_headers(&mut response, &req_ctx, app_ctx);
+    Ok(response)
 }
 
 async fn execute_query<T: DeserializeOwned + GraphQLRequestLike>(
@@ -121,7 +454,8 @@ async fn execute_query<T: DeserializeOwned + GraphQLRequestLike>(
 ) -> anyhow::Result<Response<Body>> {
     let mut response = if app_ctx.blueprint.server.enable_jit {
         let operation_id = request.operation_id(&req.headers);
-        let exec = JITExecutor::new(app_ctx.clone(), req_ctx.clone(), operation_id);
+        let exec = JITExecutor::new(app_ctx.clone(), req_ctx.clone(), operation_id)
+            .with_headers(&req.headers);
         request
             .execute_with_jit(exec)
             .await
@@ -148,7 +482,7 @@ async fn execute_query<T: DeserializeOwned + GraphQLRequestLike>(
     Ok(response)
 }
 
-fn create_allowed_headers(headers: &HeaderMap, allowed: &BTreeSet<String>) -> HeaderMap {
+pub fn create_allowed_headers(headers: &HeaderMap, allowed: &BTreeSet<String>) -> HeaderMap {
     let mut new_headers = HeaderMap::with_capacity(allowed.len());
     for (k, v) in headers.iter() {
         if allowed
@@ -294,6 +628,12 @@ async fn handle_request_inner<T: DeserializeOwned + GraphQLRequestLike>(
         return handle_rest_apis(req, app_ctx, req_counter).await;
     }
 
+    if let Some(admin) = &app_ctx.blueprint.server.admin {
+        if admin::is_admin_path(req.uri().path(), app_ctx.blueprint.server.routes.admin()) {
+            return admin::handle_admin(req, &app_ctx, admin).await;
+        }
+    }
+
     let health_check_endpoint = app_ctx.blueprint.server.routes.status();
     let graphql_endpoint = app_ctx.blueprint.server.routes.graphql();
 
@@ -302,7 +642,13 @@ async fn handle_request_inner<T: DeserializeOwned + GraphQLRequestLike>(
         // The first check for the route should be for `/graphql`
         // This is always going to be the most used route.
         Method::POST if req.uri().path() == graphql_endpoint => {
-            graphql_request::<T>(req, &app_ctx, req_counter).await
+            if accepts_event_stream(req.headers()) {
+                event_stream_request(req, &app_ctx, req_counter).await
+            } else if accepts_multipart_mixed(req.headers()) {
+                incremental_request::<T>(req, &app_ctx, req_counter).await
+            } else {
+                graphql_request::<T>(req, &app_ctx, req_counter).await
+            }
         }
         Method::POST
             if app_ctx.blueprint.server.enable_showcase
@@ -377,15 +723,44 @@ pub async fn handle_request<T: DeserializeOwned + GraphQLRequestLike>(
 
 #[cfg(test)]
 mod test {
+    use async_graphql::parser::parse_query;
     use tailcall_valid::Validator;
 
     use super::*;
-    use crate::core::async_graphql_hyper::GraphQLRequest;
+    use crate::core::async_graphql_hyper::GraphQLBatchRequest;
     use crate::core::blueprint::Blueprint;
     use crate::core::config::{Config, ConfigModule, Routes};
     use crate::core::rest::EndpointSet;
     use crate::core::runtime::test::init;
 
+    #[test]
+    fn test_forwarded_ip() {
+        let headers = |pairs: &[(&'static str, &'static str)]| {
+            let mut headers = HeaderMap::new();
+            for (name, value) in pairs {
+                headers.append(*name, HeaderValue::from_static(value));
+            }
+            headers
+        };
+
+        assert_eq!(forwarded_ip(&headers(&[])), None);
+        assert_eq!(
+            forwarded_ip(&headers(&[("cf-connecting-ip", "203.0.113.7")])),
+            "203.0.113.7".parse().ok()
+        );
+        assert_eq!(
+            forwarded_ip(&headers(&[
+                ("x-forwarded-for", "10.0.0.1, 198.51.100.2"),
+                ("x-forwarded-for", "203.0.113.9")
+            ])),
+            "203.0.113.9".parse().ok()
+        );
+        assert_eq!(
+            forwarded_ip(&headers(&[("x-forwarded-for", "unknown")])),
+            None
+        );
+    }
+
     #[tokio::test]
     async fn test_health_endpoint() -> anyhow::Result<()> {
         let sdl = tokio::fs::read_to_string(tailcall_fixtures::configs::JSONPLACEHOLDER).await?;
@@ -442,6 +817,156 @@ mod test {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_graphql_event_stream() -> anyhow::Result<()> {
+        let sdl = tokio::fs::read_to_string(tailcall_fixtures::configs::JSONPLACEHOLDER).await?;
+        let config = Config::from_sdl(&sdl).to_result()?;
+        let blueprint = Blueprint::try_from(&ConfigModule::from(config))?;
+        let app_ctx = Arc::new(AppContext::new(
+            blueprint,
+            init(None),
+            EndpointSet::default(),
+        ));
+
+        let query = r#"{"query": "{ __schema { queryType { name } } }"}"#;
+        let req = Request::builder()
+            .method(Method::POST)
+            .uri("http://localhost:8000/graphql".to_string())
+            .header("Content-Type", "application/json")
+            .header("Accept", "text/event-stream")
+            .body(Body::from(query))?;
+
+        let resp = handle_request::<GraphQLRequest>(req, app_ctx).await?;
+
+        assert_eq!(resp.status(), StatusCode::OK);
+        assert_eq!(resp.headers().get(CONTENT_TYPE).unwrap(), TEXT_EVENT_STREAM);
+        let body = hyper::body::to_bytes(resp.into_body()).await?;
+        let body_str = String::from_utf8(body.to_vec())?;
+        assert!(body_str.starts_with("event: next\ndata: {"));
+        assert!(body_str.contains("queryType"));
+        assert!(body_str.ends_with("event: complete\ndata: \n\n"));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_graphql_multipart_mixed() -> anyhow::Result<()> {
+        let sdl = r#"
+            schema { query: Query }
+            type Query { product: Product @expr(body: {id: 1}) }
+            type Product { id: Int reviews: [Review] @expr(body: [{body: "a"}, {body: "b"}]) }
+            type Review { body: String }
+        "#;
+        let config = Config::from_sdl(sdl).to_result()?;
+        let blueprint = Blueprint::try_from(&ConfigModule::from(config))?;
+        let app_ctx = Arc::new(AppContext::new(
+            blueprint,
+            init(None),
+            EndpointSet::default(),
+        ));
+
+        let query = r#"{"query": "{ product { id ... @defer(label: \"reviews\") { reviews { body } } } }"}"#;
+        let req = Request::builder()
+            .method(Method::POST)
+            .uri("http://localhost:8000/graphql".to_string())
+            .header("Content-Type", "application/json")
+            .header(
+                "Accept",
+                "multipart/mixed; deferSpec=20220824, application/json",
+            )
+            .body(Body::from(query))?;
+
+        let resp = handle_request::<GraphQLRequest>(req, app_ctx).await?;
+
+        assert_eq!(resp.status(), StatusCode::OK);
+        assert_eq!(
+            resp.headers().get(CONTENT_TYPE).unwrap(),
+            "multipart/mixed; boundary=\"graphql\"; deferSpec=20220824"
+        );
+        let body = hyper::body::to_bytes(resp.into_body()).await?;
+        let expected = [
+            "\r\n--graphql\r\nContent-Type: application/json; charset=utf-8\r\n\r\n",
+            r#"{"data":{"product":{"id":1}},"hasNext":true}"#,
+            "\r\n--graphql\r\nContent-Type: application/json; charset=utf-8\r\n\r\n",
+            r#"{"incremental":[{"data":{"reviews":[{"body":"a"},{"body":"b"}]},"path":["product"],"label":"reviews"}],"hasNext":false}"#,
+            "\r\n--graphql--\r\n",
+        ]
+        .concat();
+        assert_eq!(String::from_utf8(body.to_vec())?, expected);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_graphql_multipart_mixed_nested() -> anyhow::Result<()> {
+        let sdl = r#"
+            schema { query: Query }
+            type Query { product: Product @expr(body: {id: 1}) }
+            type Product { id: Int seller: Seller @expr(body: {name: "acme"}) }
+            type Seller { name: String rating: Int @expr(body: 5) }
+        "#;
+        let config = Config::from_sdl(sdl).to_result()?;
+        let blueprint = Blueprint::try_from(&ConfigModule::from(config))?;
+        let app_ctx = Arc::new(AppContext::new(
+            blueprint,
+            init(None),
+            EndpointSet::default(),
+        ));
+
+        let query = r#"{"query": "{ product { id ... @defer(label: \"seller\") { seller { name ... @defer(label: \"rating\") { rating } } } } }"}"#;
+        let req = Request::builder()
+            .method(Method::POST)
+            .uri("http://localhost:8000/graphql".to_string())
+            .header("Content-Type", "application/json")
+            .header("Accept", "multipart/mixed; deferSpec=20220824")
+            .body(Body::from(query))?;
+
+        let resp = handle_request::<GraphQLBatchRequest>(req, app_ctx).await?;
+
+        let body = hyper::body::to_bytes(resp.into_body()).await?;
+        let expected = [
+            "\r\n--graphql\r\nContent-Type: application/json; charset=utf-8\r\n\r\n",
+            r#"{"data":{"product":{"id":1}},"hasNext":true}"#,
+            "\r\n--graphql\r\nContent-Type: application/json; charset=utf-8\r\n\r\n",
+            r#"{"incremental":[{"data":{"seller":{"name":"acme"}},"path":["product"],"label":"seller"}],"hasNext":true}"#,
+            "\r\n--graphql\r\nContent-Type: application/json; charset=utf-8\r\n\r\n",
+            r#"{"incremental":[{"data":{"rating":5},"path":["product","seller"],"label":"rating"}],"hasNext":false}"#,
+            "\r\n--graphql--\r\n",
+        ]
+        .concat();
+        assert_eq!(String::from_utf8(body.to_vec())?, expected);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_is_incremental() {
+        let document = parse_query("{ product { id ... @defer { reviews } } }").unwrap();
+        assert!(is_incremental(&document));
+
+        let document =
+            parse_query("fragment R on Product { reviews @stream { id } } { product { ...R } }")
+                .unwrap();
+        assert!(is_incremental(&document));
+
+        let document = parse_query("{ product { id reviews { id } } }").unwrap();
+        assert!(!is_incremental(&document));
+    }
+
+    #[test]
+    fn test_is_subscription() {
+        let request = async_graphql::Request::new("subscription { news { id } }");
+        assert!(is_subscription(&request));
+
+        let request = async_graphql::Request::new("query { news { id } }");
+        assert!(!is_subscription(&request));
+
+        let request =
+            async_graphql::Request::new("query A { news { id } } subscription B { news { id } }")
+                .operation_name("B");
+        assert!(is_subscription(&request));
+    }
+
     #[test]
     fn test_create_allowed_headers() {
         use std::collections::BTreeSet;
@@ -6,9 +6,12 @@ use http::header::{HeaderMap, HeaderValue};
 use tailcall_hasher::TailcallHasher;
 use url::Url;
 
+use super::codec::{json_to_msgpack, json_to_xml, XmlEscaped};
+use super::multipart::to_form_data;
 use super::query_encoder::QueryEncoder;
 use crate::core::config::Encoding;
 use crate::core::endpoint::Endpoint;
+use crate::core::grpc::protobuf::ProtobufOperation;
 use crate::core::has_headers::HasHeaders;
 use crate::core::helpers::headers::MustacheHeaders;
 use crate::core::ir::model::{CacheKey, IoId};
@@ -29,6 +32,9 @@ pub struct RequestTemplate {
     pub endpoint: Endpoint,
     pub encoding: Encoding,
     pub query_encoder: QueryEncoder,
+    /// The operation whose messages encode the body and decode the response
+    /// with the `ApplicationProtobuf` encoding
+    pub protobuf: Option<ProtobufOperation>,
 }
 
 #[derive(Setters, Debug, Clone)]
@@ -146,6 +152,54 @@ impl RequestTemplate {
 
                     req.body_mut().replace(form_data.into());
                 }
+                Encoding::MultipartFormData => {
+                    let body: String = body_path.render(ctx);
+                    let (boundary, form_data) = to_form_data(&serde_json::from_str(&body)?)?;
+                    req.headers_mut().insert(
+                        reqwest::header::CONTENT_TYPE,
+                        HeaderValue::from_str(&format!(
+                            "multipart/form-data; boundary={}",
+                            boundary
+                        ))?,
+                    );
+                    req.body_mut().replace(form_data.into());
+                }
+                Encoding::ApplicationXml => {
+                    // a template made of a single expression renders JSON that's
+                    // converted to XML, or text that's escaped like the values
+                    // rendered into the other templates
+                    let xml = match body_path.segments().as_slice() {
+                        [Segment::Expression(_)] => {
+                            let body: String = body_path.render(ctx);
+                            match serde_json::from_str::<serde_json::Value>(&body) {
+                                Ok(value) => json_to_xml(&value)?,
+                                Err(_) => body_path.render(&XmlEscaped(ctx)),
+                            }
+                        }
+                        _ => body_path.render(&XmlEscaped(ctx)),
+                    };
+
+                    req.body_mut().replace(xml.into());
+                }
+                Encoding::TextPlain => {
+                    req.body_mut().replace(body_path.render(ctx).into());
+                }
+                Encoding::ApplicationMsgpack => {
+                    let body: String = body_path.render(ctx);
+                    let msgpack = json_to_msgpack(&serde_json::from_str(&body)?)?;
+
+                    req.body_mut().replace(msgpack.into());
+                }
+                Encoding::ApplicationProtobuf => {
+                    let operation = self.protobuf.as_ref().ok_or_else(|| {
+                        anyhow::anyhow!(
+                            "The ApplicationProtobuf encoding requires a protobuf method"
+                        )
+                    })?;
+                    let body = operation.encode_input(&body_path.render(ctx))?;
+
+                    req.body_mut().replace(body.into());
+                }
             }
         }
         Ok(req)
@@ -174,6 +228,14 @@ impl RequestTemplate {
                     Encoding::ApplicationXWwwFormUrlencoded => {
                         HeaderValue::from_static("application/x-www-form-urlencoded")
                     }
+                    // the boundary is added once the body is encoded
+                    Encoding::MultipartFormData => HeaderValue::from_static("multipart/form-data"),
+                    Encoding::ApplicationXml => HeaderValue::from_static("application/xml"),
+                    Encoding::TextPlain => HeaderValue::from_static("text/plain"),
+                    Encoding::ApplicationMsgpack => HeaderValue::from_static("application/msgpack"),
+                    Encoding::ApplicationProtobuf => {
+                        HeaderValue::from_static("application/x-protobuf")
+                    }
                 },
             );
         }
@@ -192,6 +254,7 @@ impl RequestTemplate {
             endpoint: Endpoint::new(root_url.to_string()),
             encoding: Default::default(),
             query_encoder: Default::default(),
+            protobuf: Default::default(),
         })
     }
 
@@ -243,6 +306,7 @@ impl TryFrom<Endpoint> for RequestTemplate {
             endpoint,
             encoding,
             query_encoder: Default::default(),
+            protobuf: Default::default(),
         })
     }
 }
@@ -312,6 +376,9 @@ mod tests {
     use serde_json::json;
 
     use super::{Query, RequestTemplate};
+    use crate::core::blueprint::GrpcMethod;
+    use crate::core::grpc::protobuf::tests::get_proto_file;
+    use crate::core::grpc::protobuf::ProtobufSet;
     use crate::core::has_headers::HasHeaders;
     use crate::core::json::JsonLike;
     use crate::core::mustache::Mustache;
@@ -644,6 +711,154 @@ mod tests {
         assert_eq!(body, "baz");
     }
 
+    #[test]
+    fn test_body_encoding_multipart_form_data() {
+        let tmpl = RequestTemplate::new("http://localhost:3000")
+            .unwrap()
+            .method(reqwest::Method::POST)
+            .encoding(crate::core::config::Encoding::MultipartFormData)
+            .body_path(Some(Mustache::parse("{{foo}}")));
+        let ctx = Context::default().value(json!({
+          "foo": {
+            "name": "report",
+            "file": {
+              "filename": "a.txt",
+              "content": "aGVsbG8="
+            }
+          }
+        }));
+        let req = tmpl.to_request(&ctx).unwrap();
+        let content_type = req.headers().get("Content-Type").unwrap().to_str().unwrap();
+        let boundary = content_type
+            .strip_prefix("multipart/form-data; boundary=")
+            .unwrap();
+        let body = tmpl.to_body(&ctx).unwrap();
+        let expected = format!(
+            "--{b}\r\nContent-Disposition: form-data; name=\"name\"\r\n\r\nreport\r\n\
+             --{b}\r\nContent-Disposition: form-data; name=\"file\"; filename=\"a.txt\"\r\n\r\nhello\r\n\
+             --{b}--\r\n",
+            b = boundary
+        );
+        assert_eq!(body, expected);
+    }
+
+    #[test]
+    fn test_body_encoding_application_xml() {
+        let tmpl = RequestTemplate::new("http://localhost:3000")
+            .unwrap()
+            .method(reqwest::Method::POST)
+            .encoding(crate::core::config::Encoding::ApplicationXml)
+            .body_path(Some(Mustache::parse("{{foo}}")));
+        let ctx = Context::default().value(json!({
+          "foo": {
+            "user": { "@id": 1, "name": "foo" }
+          }
+        }));
+        let req = tmpl.to_request(&ctx).unwrap();
+        let body = tmpl.to_body(&ctx).unwrap();
+        assert_eq!(
+            req.headers().get("Content-Type").unwrap(),
+            "application/xml"
+        );
+        assert_eq!(
+            body,
+            r#"<?xml version="1.0" encoding="UTF-8"?><user id="1"><name>foo</name></user>"#
+        );
+    }
+
+    #[test]
+    fn test_body_encoding_application_xml_text() {
+        let tmpl = RequestTemplate::new("http://localhost:3000")
+            .unwrap()
+            .method(reqwest::Method::POST)
+            .encoding(crate::core::config::Encoding::ApplicationXml)
+            .body_path(Some(Mustache::parse("{{foo}}")));
+        let ctx = Context::default().value(json!({ "foo": "<admin>true</admin>" }));
+        let body = tmpl.to_body(&ctx).unwrap();
+        assert_eq!(body, "&lt;admin&gt;true&lt;/admin&gt;");
+    }
+
+    #[test]
+    fn test_body_encoding_application_xml_template() {
+        let tmpl = RequestTemplate::new("http://localhost:3000")
+            .unwrap()
+            .method(reqwest::Method::POST)
+            .encoding(crate::core::config::Encoding::ApplicationXml)
+            .body_path(Some(Mustache::parse("<user><id>{{foo}}</id></user>")));
+        let ctx = Context::default().value(json!({ "foo": 1 }));
+        let body = tmpl.to_body(&ctx).unwrap();
+        assert_eq!(body, "<user><id>1</id></user>");
+
+        let ctx = Context::default().value(json!({ "foo": "1</id><admin>true</admin><id>" }));
+        let body = tmpl.to_body(&ctx).unwrap();
+        assert_eq!(
+            body,
+            "<user><id>1&lt;/id&gt;&lt;admin&gt;true&lt;/admin&gt;&lt;id&gt;</id></user>"
+        );
+    }
+
+    #[test]
+    fn test_body_encoding_text_plain() {
+        let tmpl = RequestTemplate::new("http://localhost:3000")
+            .unwrap()
+            .method(reqwest::Method::POST)
+            .encoding(crate::core::config::Encoding::TextPlain)
+            .body_path(Some(Mustache::parse("{{foo}}")));
+        let ctx = Context::default().value(json!({ "foo": "hello world" }));
+        let req = tmpl.to_request(&ctx).unwrap();
+        let body = tmpl.to_body(&ctx).unwrap();
+        assert_eq!(req.headers().get("Content-Type").unwrap(), "text/plain");
+        assert_eq!(body, "hello world");
+    }
+
+    #[test]
+    fn test_body_encoding_application_msgpack() {
+        let tmpl = RequestTemplate::new("http://localhost:3000")
+            .unwrap()
+            .method(reqwest::Method::POST)
+            .encoding(crate::core::config::Encoding::ApplicationMsgpack)
+            .body_path(Some(Mustache::parse("{{foo}}")));
+        let ctx = Context::default().value(json!({ "foo": { "name": "Ervin" } }));
+        let req = tmpl.to_request(&ctx).unwrap();
+        let body = req.body().and_then(|body| body.as_bytes()).unwrap();
+        assert_eq!(
+            req.headers().get("Content-Type").unwrap(),
+            "application/msgpack"
+        );
+        assert_eq!(body, b"\x81\xa4name\xa5Ervin");
+    }
+
+    #[tokio::test]
+    async fn test_body_encoding_application_protobuf() {
+        let method = GrpcMethod::try_from("news.NewsService.GetNews").unwrap();
+        let file = get_proto_file(tailcall_fixtures::protobuf::NEWS)
+            .await
+            .unwrap();
+        let operation = ProtobufSet::from_proto_file(file)
+            .unwrap()
+            .find_service(&method)
+            .unwrap()
+            .find_operation(&method)
+            .unwrap();
+        let tmpl = RequestTemplate::new("http://localhost:3000")
+            .unwrap()
+            .method(reqwest::Method::POST)
+            .encoding(crate::core::config::Encoding::ApplicationProtobuf)
+            .protobuf(Some(operation))
+            .body_path(Some(Mustache::parse("{{foo}}")));
+        let ctx = Context::default().value(json!({ "foo": { "id": 1 } }));
+        let req = tmpl.to_request(&ctx).unwrap();
+        let body = req.body().and_then(|body| body.as_bytes()).unwrap();
+        assert_eq!(
+            req.headers().get("Content-Type").unwrap(),
+            "application/x-protobuf"
+        );
+        assert_eq!(body, b"\x08\x01");
+
+        let tmpl = tmpl.protobuf(None);
+        assert!(tmpl.to_request(&ctx).is_err());
+    }
+
     mod endpoint {
         use http::header::HeaderMap;
         use serde_json::json;
@@ -1,4 +1,4 @@
-use anyhow::Result;
+use anyhow::{anyhow, Result};
 use async_graphql_value::{ConstValue, Name};
 use derive_setters::Setters;
 use hyper::body::Bytes;
@@ -8,6 +8,8 @@ use prost::Message;
 use tonic::Status;
 use tonic_types::Status as GrpcStatus;
 
+use super::codec::{msgpack_to_value, xml_to_json};
+use crate::core::config::Encoding;
 use crate::core::grpc::protobuf::ProtobufOperation;
 use crate::core::ir::Error;
 
@@ -87,6 +89,36 @@ impl Response<Bytes> {
         Ok(Response { status: self.status, headers: self.headers, body })
     }
 
+    /// Decodes the body in the format of the encoding of the request. The
+    /// encodings that only apply to request bodies are decoded as JSON.
+    pub fn decode(
+        self,
+        encoding: &Encoding,
+        protobuf: Option<&ProtobufOperation>,
+    ) -> Result<Response<ConstValue>> {
+        if self.body.is_empty() {
+            return self.to_json();
+        }
+
+        let body = match encoding {
+            Encoding::ApplicationXml => ConstValue::from_json(xml_to_json(&self.body)?)?,
+            Encoding::TextPlain => {
+                ConstValue::String(String::from_utf8_lossy(&self.body).into_owned())
+            }
+            Encoding::ApplicationMsgpack => msgpack_to_value(&self.body)?,
+            Encoding::ApplicationProtobuf => protobuf
+                .ok_or_else(|| {
+                    anyhow!("The ApplicationProtobuf encoding requires a protobuf method")
+                })?
+                .decode_output(&self.body)?,
+            Encoding::ApplicationJson
+            | Encoding::ApplicationXWwwFormUrlencoded
+            | Encoding::MultipartFormData => return self.to_json(),
+        };
+
+        Ok(Response { status: self.status, headers: self.headers, body })
+    }
+
     pub fn to_grpc_value(
         self,
         operation: &ProtobufOperation,
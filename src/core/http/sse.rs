@@ -0,0 +1,193 @@
+use futures_util::future::ready;
+use futures_util::stream::{self, Stream};
+use futures_util::StreamExt;
+use hyper::body::Bytes;
+
+/// The largest event accepted from the stream, in bytes, so that a stream
+/// that never ends its events can't exhaust the memory.
+const MAX_EVENT_SIZE: usize = 1024 * 1024;
+
+/// Incremental parser for the `text/event-stream` format as described in the
+/// [HTML specification](https://html.spec.whatwg.org/multipage/server-sent-events.html#event-stream-interpretation).
+/// Only the `data` field of the events is retained, every other field is
+/// ignored.
+#[derive(Default)]
+struct EventParser {
+    buffer: Vec<u8>,
+    data: Option<String>,
+}
+
+impl EventParser {
+    /// Feeds a chunk of the stream and returns the data of the events that
+    /// were completed by it. Fails when the pending event grows past
+    /// `MAX_EVENT_SIZE`.
+    fn feed(&mut self, chunk: &[u8]) -> anyhow::Result<Vec<String>> {
+        self.buffer.extend_from_slice(chunk);
+
+        let mut events = Vec::new();
+        let mut start = 0;
+        let mut i = 0;
+
+        while i < self.buffer.len() {
+            let next = match self.buffer[i] {
+                b'\n' => i + 1,
+                b'\r' => match self.buffer.get(i + 1) {
+                    Some(b'\n') => i + 2,
+                    Some(_) => i + 1,
+                    // wait for the next chunk to know if it's a `\r\n`
+                    None => break,
+                },
+                _ => {
+                    i += 1;
+                    continue;
+                }
+            };
+
+            let line = String::from_utf8_lossy(&self.buffer[start..i]).into_owned();
+            if let Some(data) = self.process_line(&line) {
+                events.push(data);
+            }
+
+            start = next;
+            i = next;
+        }
+
+        self.buffer.drain(..start);
+
+        let pending = self.buffer.len() + self.data.as_ref().map_or(0, String::len);
+        if pending > MAX_EVENT_SIZE {
+            anyhow::bail!("Event exceeds the maximum size of {MAX_EVENT_SIZE} bytes");
+        }
+
+        Ok(events)
+    }
+
+    fn process_line(&mut self, line: &str) -> Option<String> {
+        // an empty line dispatches the event
+        if line.is_empty() {
+            return self.data.take();
+        }
+
+        // lines starting with a colon are comments
+        if line.starts_with(':') {
+            return None;
+        }
+
+        let (field, value) = match line.split_once(':') {
+            Some((field, value)) => (field, value.strip_prefix(' ').unwrap_or(value)),
+            None => (line, ""),
+        };
+
+        if field == "data" {
+            match self.data {
+                Some(ref mut data) => {
+                    data.push('\n');
+                    data.push_str(value);
+                }
+                None => self.data = Some(value.to_string()),
+            }
+        }
+
+        None
+    }
+}
+
+/// Converts a stream of `text/event-stream` chunks into a stream of the data
+/// carried by each event. The stream ends after an event that is too large.
+pub fn parse_events<S>(chunks: S) -> impl Stream<Item = anyhow::Result<String>> + Send + 'static
+where
+    S: Stream<Item = anyhow::Result<Bytes>> + Send + 'static,
+{
+    chunks
+        .scan(Some(EventParser::default()), |state, chunk| {
+            let Some(parser) = state else {
+                return ready(None);
+            };
+            let events = match chunk {
+                Ok(chunk) => match parser.feed(&chunk) {
+                    Ok(events) => events.into_iter().map(Ok).collect(),
+                    Err(err) => {
+                        *state = None;
+                        vec![Err(err)]
+                    }
+                },
+                Err(err) => vec![Err(err)],
+            };
+
+            ready(Some(stream::iter(events)))
+        })
+        .flatten()
+}
+
+#[cfg(test)]
+mod tests {
+    use futures_util::{stream, StreamExt};
+    use hyper::body::Bytes;
+
+    use super::*;
+
+    async fn parse(chunks: &[&'static str]) -> Vec<String> {
+        let chunks = stream::iter(
+            chunks
+                .iter()
+                .map(|chunk| Ok(Bytes::from_static(chunk.as_bytes())))
+                .collect::<Vec<_>>(),
+        );
+
+        parse_events(chunks)
+            .map(|event| event.unwrap())
+            .collect()
+            .await
+    }
+
+    #[tokio::test]
+    async fn test_single_event() {
+        let actual = parse(&["data: {\"id\":1}\n\n"]).await;
+
+        assert_eq!(actual, vec!["{\"id\":1}"]);
+    }
+
+    #[tokio::test]
+    async fn test_multiline_data() {
+        let actual = parse(&["data: a\ndata:b\n\n"]).await;
+
+        assert_eq!(actual, vec!["a\nb"]);
+    }
+
+    #[tokio::test]
+    async fn test_event_split_across_chunks() {
+        let actual = parse(&["da", "ta: 1\r", "\n\r\ndata: 2\n", "\n"]).await;
+
+        assert_eq!(actual, vec!["1", "2"]);
+    }
+
+    #[tokio::test]
+    async fn test_ignores_comments_and_other_fields() {
+        let actual = parse(&[": keep-alive\n\nevent: update\nid: 1\nretry: 10\ndata: 1\n\n"]).await;
+
+        assert_eq!(actual, vec!["1"]);
+    }
+
+    #[tokio::test]
+    async fn test_incomplete_event_is_not_dispatched() {
+        let actual = parse(&["data: 1\n\ndata: 2\n"]).await;
+
+        assert_eq!(actual, vec!["1"]);
+    }
+
+    #[tokio::test]
+    async fn test_event_too_large() {
+        let data = format!("data: {}", "a".repeat(MAX_EVENT_SIZE));
+        let chunks = stream::iter(vec![
+            Ok(Bytes::from_static(b"data: 1\n\n")),
+            Ok(Bytes::from(data)),
+            Ok(Bytes::from_static(b"\n\ndata: 2\n\n")),
+        ]);
+
+        let actual = parse_events(chunks).collect::<Vec<_>>().await;
+
+        assert_eq!(actual.len(), 2);
+        assert_eq!(actual[0].as_ref().unwrap(), "1");
+        assert!(actual[1].is_err());
+    }
+}
@@ -1,5 +1,6 @@
 use std::fmt::Display;
 use std::sync::Arc;
+use std::time::Duration;
 
 use async_graphql::Value as ConstValue;
 use derive_more::From;
@@ -35,6 +36,27 @@ pub enum Error {
 
     #[from(ignore)]
     Entity(String),
+
+    #[from(ignore)]
+    RateLimited {
+        retry_after: Duration,
+    },
+
+    #[from(ignore)]
+    UnidentifiedClient,
+
+    #[from(ignore)]
+    Timeout {
+        timeout: Duration,
+    },
+}
+
+impl Error {
+    /// Seconds the client has to wait before calling a rate limited field
+    /// again.
+    fn retry_after_secs(retry_after: &Duration) -> u64 {
+        (retry_after.as_millis() as u64).div_ceil(1000).max(1)
+    }
 }
 
 impl Display for Error {
@@ -67,26 +89,39 @@ impl From<Error> for Errata {
             }
             Error::Worker(err) => Errata::new("Worker Error").description(err.to_string()),
             Error::Cache(err) => Errata::new("Cache Error").description(err.to_string()),
-            Error::Entity(message) => Errata::new("Entity Resolver Error").description(message)
+            Error::Entity(message) => Errata::new("Entity Resolver Error").description(message),
+            Error::RateLimited { retry_after } => Errata::new("Rate Limit Exceeded")
+                .description(format!("Retry after {} seconds", Error::retry_after_secs(&retry_after))),
+            Error::UnidentifiedClient => Errata::new("Rate Limit Error")
+                .description("The client the limit applies to can't be identified"),
+            Error::Timeout { timeout } => Errata::new("Timeout Error")
+                .description(format!("Timed out after {} ms", timeout.as_millis())),
         }
     }
 }
 
 impl ErrorExtensions for Error {
     fn extend(&self) -> ExtensionError {
-        ExtensionError::new(format!("{}", self)).extend_with(|_err, e| {
-            if let Error::GRPC {
+        ExtensionError::new(format!("{}", self)).extend_with(|_err, e| match self {
+            Error::GRPC {
                 grpc_code,
                 grpc_description,
                 grpc_status_message,
                 grpc_status_details,
-            } = self
-            {
+            } => {
                 e.set("grpcCode", *grpc_code);
                 e.set("grpcDescription", grpc_description);
                 e.set("grpcStatusMessage", grpc_status_message);
                 e.set("grpcStatusDetails", grpc_status_details.clone());
             }
+            Error::RateLimited { retry_after } => {
+                e.set("code", "RATE_LIMITED");
+                e.set("retryAfter", Error::retry_after_secs(retry_after));
+            }
+            Error::Timeout { .. } => {
+                e.set("code", "TIMEOUT");
+            }
+            _ => {}
         })
     }
 }
@@ -1,15 +1,16 @@
 use std::collections::HashMap;
 use std::future::Future;
-use std::ops::Deref;
 
 use async_graphql_value::ConstValue;
 use futures_util::future::join_all;
 use indexmap::IndexMap;
 
+use super::eval_cache::eval_cache;
 use super::eval_io::eval_io;
-use super::model::{Cache, CacheKey, Map, IR};
+use super::model::{Invalidate, Map, IR};
 use super::{Error, EvalContext, ResolverContextLike, TypedValue};
 use crate::core::auth::verify::{AuthVerifier, Verify};
+use crate::core::cache::TagIndex;
 use crate::core::json::{JsonLike, JsonObjectLike};
 use crate::core::serde_value_ext::ValueExt;
 
@@ -42,25 +43,27 @@ impl IR {
 
                     expr.eval(ctx).await
                 }
+                IR::RateLimit(rate_limit, expr) => {
+                    rate_limit.acquire(ctx).await?;
+
+                    expr.eval(ctx).await
+                }
                 IR::IO(io) => eval_io(io, ctx).await,
-                IR::Cache(Cache { max_age, io }) => {
-                    let io = io.deref();
-                    let key = io.cache_key(ctx);
-                    if let Some(key) = key {
-                        if let Some(val) = ctx.request_ctx.runtime.cache.get(&key).await? {
-                            Ok(val)
-                        } else {
-                            let val = eval_io(io, ctx).await?;
-                            ctx.request_ctx
-                                .runtime
-                                .cache
-                                .set(key, val.clone(), max_age.to_owned())
-                                .await?;
-                            Ok(val)
-                        }
-                    } else {
-                        eval_io(io, ctx).await
+                IR::Cache(cache) => eval_cache(cache, ctx).await,
+                IR::Invalidate(Invalidate { tags, max_age }, expr) => {
+                    let val = expr.eval(ctx).await?;
+                    let tag_ctx = ctx.with_value(val.clone());
+                    let tags = tags
+                        .iter()
+                        .map(|tag| tag.render(&tag_ctx))
+                        .collect::<Vec<_>>();
+                    if let Err(error) = TagIndex::new(ctx.request_ctx.runtime.cache.as_ref())
+                        .invalidate(&tags, max_age.to_owned())
+                        .await
+                    {
+                        tracing::warn!("Failed to invalidate the cache: {}", error);
                     }
+                    Ok(val)
                 }
                 IR::Map(Map { input, map }) => {
                     fn recursive_map_enum(
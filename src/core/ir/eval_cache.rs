@@ -0,0 +1,368 @@
+use std::hash::{Hash, Hasher};
+use std::num::NonZeroU64;
+
+use async_graphql_value::ConstValue;
+use tailcall_hasher::TailcallHasher;
+
+use super::eval_io::eval_io;
+use super::eval_rate_limit::KeyContext;
+use super::model::{Cache, CacheKey, IoId};
+use super::{EvalContext, ResolverContextLike};
+use crate::core::cache::TagIndex;
+use crate::core::http::RequestContext;
+use crate::core::ir::Error;
+
+/// Tells if a value expired for `staleness` milliseconds can still be served
+/// within the window
+fn is_within(window: Option<NonZeroU64>, staleness: u64) -> bool {
+    window.is_some_and(|window| staleness < window.get())
+}
+
+/// Identifies the authenticated client, by the subject of its token or of its
+/// certificate
+fn identity(request_ctx: &RequestContext) -> Option<String> {
+    let claims = request_ctx.claims.lock().unwrap();
+    let subject = claims
+        .as_ref()
+        .and_then(|claims| claims.get("sub"))
+        .and_then(|subject| subject.as_str());
+
+    match (subject, &request_ctx.client_cert) {
+        (Some(subject), _) => Some(format!("jwt:{}", subject)),
+        (None, Some(cert)) => Some(format!("cert:{}", cert.subject)),
+        (None, None) => None,
+    }
+}
+
+/// The key of a cached value. It's derived from the request made to fetch the
+/// value unless a key is configured, and varies with the configured values
+/// and, for private values, with the identity of the client. Private values
+/// of anonymous clients aren't cached.
+fn cache_key<Ctx>(cache: &Cache, ctx: &EvalContext<'_, Ctx>) -> Option<IoId>
+where
+    Ctx: ResolverContextLike + Sync,
+{
+    if cache.key.is_none() && cache.vary_by.is_empty() && !cache.private {
+        return cache.io.cache_key(ctx);
+    }
+
+    let key_ctx = KeyContext { ctx };
+    let mut hasher = TailcallHasher::default();
+    match &cache.key {
+        Some(key) => {
+            cache.scope.hash(&mut hasher);
+            cache.position.hash(&mut hasher);
+            key.render(&key_ctx).hash(&mut hasher);
+        }
+        None => cache.io.cache_key(ctx)?.hash(&mut hasher),
+    }
+    for value in &cache.vary_by {
+        value.render(&key_ctx).hash(&mut hasher);
+    }
+    if cache.private {
+        identity(ctx.request_ctx)?.hash(&mut hasher);
+    }
+
+    Some(IoId::new(hasher.finish()))
+}
+
+pub async fn eval_cache<Ctx>(
+    cache: &Cache,
+    ctx: &mut EvalContext<'_, Ctx>,
+) -> Result<ConstValue, Error>
+where
+    Ctx: ResolverContextLike + Sync,
+{
+    if cache.private {
+        ctx.request_ctx.set_cache_public_false();
+    }
+
+    let Some(key) = cache_key(cache, ctx) else {
+        return eval_io(&cache.io, ctx).await;
+    };
+
+    let index = TagIndex::new(ctx.request_ctx.runtime.cache.as_ref());
+    // a cache that can't be reached, like a remote one that is down, is
+    // treated as a miss instead of failing the field
+    let cached = index
+        .get(&key, !cache.tags.is_empty())
+        .await
+        .unwrap_or_else(|error| {
+            tracing::warn!("Failed to read from the cache: {}", error);
+            None
+        });
+
+    let Some((value, age)) = cached else {
+        return fetch(cache, key, ctx).await;
+    };
+    let Some(staleness) = age.checked_sub(cache.max_age.get()) else {
+        return Ok(value);
+    };
+
+    if is_within(cache.stale_while_revalidate, staleness) {
+        revalidate(cache, key, ctx);
+        Ok(value)
+    } else if is_within(cache.stale_if_error, staleness) {
+        fetch(cache, key, ctx).await.or_else(|error| {
+            tracing::warn!(
+                "Serving a stale value after failing to refresh it: {}",
+                error
+            );
+            Ok(value)
+        })
+    } else {
+        fetch(cache, key, ctx).await
+    }
+}
+
+/// Fetches the value and caches it along with its tags
+async fn fetch<Ctx>(
+    cache: &Cache,
+    key: IoId,
+    ctx: &mut EvalContext<'_, Ctx>,
+) -> Result<ConstValue, Error>
+where
+    Ctx: ResolverContextLike + Sync,
+{
+    let fetched_at = TagIndex::now();
+    let value = eval_io(&cache.io, ctx).await?;
+
+    let tag_ctx = ctx.with_value(value.clone());
+    let tags = cache.tags.iter().map(|tag| tag.render(&tag_ctx)).collect();
+    if let Err(error) = TagIndex::new(ctx.request_ctx.runtime.cache.as_ref())
+        .set(key, value.clone(), tags, fetched_at, cache.ttl)
+        .await
+    {
+        tracing::warn!("Failed to write to the cache: {}", error);
+    }
+
+    Ok(value)
+}
+
+/// Refreshes a cached value in the background, while the request is answered
+/// with the stale one. Requests served the same stale value wait for the
+/// refresh in progress instead of starting their own.
+fn revalidate<Ctx>(cache: &Cache, key: IoId, ctx: &EvalContext<'_, Ctx>)
+where
+    Ctx: ResolverContextLike + Sync,
+{
+    let cache = cache.clone();
+    let detached = ctx.detach();
+    let revalidations = ctx.request_ctx.dedupe_revalidation_handler.clone();
+    let task = async move {
+        let id = key.clone();
+        let refresh = || async move {
+            let mut ctx = detached.eval_context();
+            fetch(&cache, id, &mut ctx).await
+        };
+        if let Err(error) = revalidations.dedupe(&key, refresh).await {
+            tracing::warn!("Failed to refresh a stale value of the cache: {}", error);
+        }
+    };
+
+    #[cfg(not(target_arch = "wasm32"))]
+    tokio::spawn(task);
+    #[cfg(target_arch = "wasm32")]
+    async_std::task::spawn_local(task);
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    use http::header::HeaderMap;
+    use serde_json::json;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    use super::*;
+    use crate::core::http::RequestTemplate;
+    use crate::core::ir::model::IO;
+    use crate::core::ir::EmptyResolverContext;
+    use crate::core::mustache::Mustache;
+    use crate::core::Cache as _;
+
+    /// Caches the responses of the upstream at `url`
+    fn upstream(url: &str) -> Cache {
+        Cache {
+            max_age: NonZeroU64::new(1).unwrap(),
+            stale_while_revalidate: None,
+            stale_if_error: None,
+            ttl: NonZeroU64::new(60_000).unwrap(),
+            io: Box::new(IO::Http {
+                req_template: RequestTemplate::new(url).unwrap(),
+                group_by: None,
+                dl_id: None,
+                http_filter: None,
+                is_list: false,
+                dedupe: false,
+                retry: None,
+                timeout: None,
+                tls: None,
+            }),
+            scope: "Query.user".to_string(),
+            position: 1,
+            key: None,
+            vary_by: vec![],
+            private: false,
+            tags: vec![],
+        }
+    }
+
+    /// Caches the responses of an upstream that can't be reached
+    async fn unreachable(stale_if_error: Option<NonZeroU64>) -> Cache {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        drop(listener);
+
+        let url = format!("http://127.0.0.1:{}/users/1", port);
+        Cache { stale_if_error, ..upstream(&url) }
+    }
+
+    /// Caches the responses of a slow upstream, counting the requests it
+    /// receives
+    async fn slow(hits: Arc<AtomicUsize>) -> Cache {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        tokio::spawn(async move {
+            while let Ok((mut stream, _)) = listener.accept().await {
+                let hits = hits.clone();
+                tokio::spawn(async move {
+                    let mut buf = [0; 1024];
+                    let _ = stream.read(&mut buf).await;
+                    hits.fetch_add(1, Ordering::SeqCst);
+                    tokio::time::sleep(Duration::from_millis(50)).await;
+                    let response = "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\n\
+                                    content-length: 7\r\nconnection: close\r\n\r\n\"Ervin\"";
+                    let _ = stream.write_all(response.as_bytes()).await;
+                });
+            }
+        });
+
+        let url = format!("http://127.0.0.1:{}/users/1", port);
+        Cache {
+            stale_while_revalidate: NonZeroU64::new(60_000),
+            ..upstream(&url)
+        }
+    }
+
+    /// Caches a value that expires right away
+    async fn expired(cache: &Cache, ctx: &EvalContext<'_, EmptyResolverContext>) {
+        let key = cache.io.cache_key(ctx).unwrap();
+        ctx.request_ctx
+            .runtime
+            .cache
+            .set(key, ConstValue::from("Leanne"), cache.ttl)
+            .await
+            .unwrap();
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+
+    #[test]
+    fn test_is_within() {
+        let window = NonZeroU64::new(100);
+
+        assert!(is_within(window, 0));
+        assert!(is_within(window, 99));
+        assert!(!is_within(window, 100));
+        assert!(!is_within(None, 0));
+    }
+
+    #[tokio::test]
+    async fn test_stale_if_error() {
+        let req_ctx = RequestContext::default();
+        let mut ctx = EvalContext::new(&req_ctx, &EmptyResolverContext);
+        let cache = unreachable(NonZeroU64::new(60_000)).await;
+        expired(&cache, &ctx).await;
+
+        let actual = eval_cache(&cache, &mut ctx).await.unwrap();
+
+        assert_eq!(actual, ConstValue::from("Leanne"));
+    }
+
+    #[tokio::test]
+    async fn test_expired() {
+        let req_ctx = RequestContext::default();
+        let mut ctx = EvalContext::new(&req_ctx, &EmptyResolverContext);
+        let cache = unreachable(None).await;
+        expired(&cache, &ctx).await;
+
+        assert!(eval_cache(&cache, &mut ctx).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_stale_while_revalidate() {
+        let hits = Arc::new(AtomicUsize::new(0));
+        let req_ctx = RequestContext::default();
+        let mut ctx = EvalContext::new(&req_ctx, &EmptyResolverContext);
+        let cache = slow(hits.clone()).await;
+        expired(&cache, &ctx).await;
+
+        let first = eval_cache(&cache, &mut ctx).await.unwrap();
+        let second = eval_cache(&cache, &mut ctx).await.unwrap();
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        let key = cache.io.cache_key(&ctx).unwrap();
+        let refreshed = req_ctx.runtime.cache.get(&key).await.unwrap();
+
+        assert_eq!(first, ConstValue::from("Leanne"));
+        assert_eq!(second, ConstValue::from("Leanne"));
+        assert_eq!(refreshed, Some(ConstValue::from("Ervin")));
+        assert_eq!(hits.load(Ordering::SeqCst), 1);
+    }
+
+    fn request_ctx(tenant: &str, token: &str) -> RequestContext {
+        let mut headers = HeaderMap::new();
+        headers.insert("x-tenant", tenant.parse().unwrap());
+        headers.insert("authorization", token.parse().unwrap());
+        RequestContext::default().allowed_headers(headers)
+    }
+
+    #[tokio::test]
+    async fn test_key_template() {
+        let mut cache = unreachable(None).await;
+        cache.key = Some(Mustache::parse("{{.headers.x-tenant}}"));
+        let key = |req_ctx: &RequestContext| {
+            cache_key(&cache, &EvalContext::new(req_ctx, &EmptyResolverContext))
+        };
+
+        assert_eq!(
+            key(&request_ctx("acme", "Bearer a")),
+            key(&request_ctx("acme", "Bearer b"))
+        );
+        assert_ne!(
+            key(&request_ctx("acme", "Bearer a")),
+            key(&request_ctx("globex", "Bearer a"))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_key_template_position() {
+        let mut first = unreachable(None).await;
+        first.key = Some(Mustache::parse("{{.headers.x-tenant}}"));
+        let second = Cache { position: 2, ..first.clone() };
+        let req_ctx = request_ctx("acme", "Bearer a");
+        let ctx = EvalContext::new(&req_ctx, &EmptyResolverContext);
+
+        assert_ne!(cache_key(&first, &ctx), cache_key(&second, &ctx));
+    }
+
+    #[tokio::test]
+    async fn test_private_key() {
+        let mut cache = unreachable(None).await;
+        cache.private = true;
+        let req_ctx = RequestContext::default();
+        let key = || cache_key(&cache, &EvalContext::new(&req_ctx, &EmptyResolverContext));
+
+        assert_eq!(key(), None);
+
+        *req_ctx.claims.lock().unwrap() = Some(json!({ "sub": "alice" }));
+        let alice = key();
+        *req_ctx.claims.lock().unwrap() = Some(json!({ "sub": "bob" }));
+        let bob = key();
+
+        assert!(alice.is_some());
+        assert_ne!(alice, bob);
+    }
+}
@@ -5,9 +5,13 @@ use std::sync::Arc;
 use async_graphql::{ServerError, Value};
 use http::header::HeaderMap;
 
-use super::{GraphQLOperationContext, RelatedFields, ResolverContextLike, SelectionField};
+use super::{
+    DetachedResolverContext, GraphQLOperationContext, RelatedFields, ResolverContextLike,
+    SelectionField,
+};
 use crate::core::document::print_directives;
 use crate::core::http::RequestContext;
+use crate::core::path::PathString;
 
 // TODO: rename to ResolverContext
 #[derive(Clone)]
@@ -103,9 +107,61 @@ impl<'a, Ctx: ResolverContextLike> EvalContext<'a, Ctx> {
         &self.request_ctx.server.vars
     }
 
+    /// Reads the subject or the SANs, separated by commas, of the certificate
+    /// presented by the client
+    pub fn client_cert(&self, key: &str) -> Option<Cow<'_, str>> {
+        let cert = self.request_ctx.client_cert.as_ref()?;
+
+        match key {
+            "subject" => Some(Cow::Borrowed(cert.subject.as_str())),
+            "san" => Some(Cow::Owned(cert.san.join(","))),
+            _ => None,
+        }
+    }
+
+    /// Reads a claim, or all the claims when the path is empty, of the token
+    /// verified for the request
+    pub fn claims<T: AsRef<str>>(&self, path: &[T]) -> Option<Cow<'_, str>> {
+        let claims = self.request_ctx.claims.lock().unwrap();
+        let value = claims.as_ref()?.path_string(path)?.into_owned();
+
+        Some(Cow::Owned(value))
+    }
+
     pub fn add_error(&self, error: ServerError) {
         self.graphql_ctx.add_error(error)
     }
+
+    /// Copies the context, along with the request and the resolver contexts it
+    /// refers to, so that it can outlive the request
+    pub fn detach(&self) -> DetachedEvalContext {
+        DetachedEvalContext {
+            request_ctx: self.request_ctx.detach(),
+            graphql_ctx: DetachedResolverContext::new(self.graphql_ctx),
+            graphql_ctx_value: self.graphql_ctx_value.clone(),
+            graphql_ctx_args: self.graphql_ctx_args.clone(),
+        }
+    }
+}
+
+/// An evaluation context owning all it refers to, for the work that goes on
+/// once the request is answered, like refreshing a stale value of the cache
+pub struct DetachedEvalContext {
+    request_ctx: RequestContext,
+    graphql_ctx: DetachedResolverContext,
+    graphql_ctx_value: Option<Arc<Value>>,
+    graphql_ctx_args: Option<Arc<Value>>,
+}
+
+impl DetachedEvalContext {
+    pub fn eval_context(&self) -> EvalContext<'_, DetachedResolverContext> {
+        EvalContext {
+            request_ctx: &self.request_ctx,
+            graphql_ctx: &self.graphql_ctx,
+            graphql_ctx_value: self.graphql_ctx_value.clone(),
+            graphql_ctx_args: self.graphql_ctx_args.clone(),
+        }
+    }
 }
 
 impl<Ctx: ResolverContextLike> GraphQLOperationContext for EvalContext<'_, Ctx> {
@@ -6,6 +6,7 @@ use tailcall_valid::Validator;
 
 use super::model::DataLoaderId;
 use super::{EvalContext, ResolverContextLike};
+use crate::core::blueprint::Tls;
 use crate::core::data_loader::{DataLoader, Loader};
 use crate::core::grpc::protobuf::ProtobufOperation;
 use crate::core::grpc::request::execute_grpc_request;
@@ -15,6 +16,7 @@ use crate::core::http::{
 };
 use crate::core::ir::Error;
 use crate::core::json::JsonLike;
+use crate::core::retry::{self, is_idempotent, RetryPolicy};
 use crate::core::{grpc, http, worker, WorkerIO};
 
 ///
@@ -26,6 +28,8 @@ pub struct EvalHttp<'a, 'ctx, Context: ResolverContextLike + Sync> {
     evaluation_ctx: &'ctx EvalContext<'a, Context>,
     data_loader: Option<&'a DataLoader<DataLoaderRequest, HttpDataLoader>>,
     request_template: &'a http::RequestTemplate,
+    retry: Option<&'a RetryPolicy>,
+    tls: Option<&'a Tls>,
 }
 
 impl<'a, 'ctx, Context: ResolverContextLike + Sync> EvalHttp<'a, 'ctx, Context> {
@@ -33,6 +37,8 @@ impl<'a, 'ctx, Context: ResolverContextLike + Sync> EvalHttp<'a, 'ctx, Context>
         evaluation_ctx: &'ctx EvalContext<'a, Context>,
         request_template: &'a RequestTemplate,
         id: &Option<DataLoaderId>,
+        retry: Option<&'a RetryPolicy>,
+        tls: Option<&'a Tls>,
     ) -> Self {
         let data_loader = if evaluation_ctx.request_ctx.is_batching_enabled() {
             id.and_then(|id| {
@@ -45,7 +51,7 @@ impl<'a, 'ctx, Context: ResolverContextLike + Sync> EvalHttp<'a, 'ctx, Context>
             None
         };
 
-        Self { evaluation_ctx, data_loader, request_template }
+        Self { evaluation_ctx, data_loader, request_template, retry, tls }
     }
 
     pub fn init_request(&self) -> Result<Request, Error> {
@@ -59,7 +65,15 @@ impl<'a, 'ctx, Context: ResolverContextLike + Sync> EvalHttp<'a, 'ctx, Context>
         let response = if is_get && dl.is_some() {
             execute_request_with_dl(ctx, req, self.data_loader).await?
         } else {
-            execute_raw_request(ctx, req).await?
+            let idempotent = is_idempotent(req.method());
+            let http = ctx.request_ctx.runtime.http_with_tls(self.tls);
+            retry::execute(&*http, req, self.retry, idempotent)
+                .await
+                .map_err(Error::from)?
+                .decode(
+                    &self.request_template.encoding,
+                    self.request_template.protobuf.as_ref(),
+                )?
         };
 
         if ctx.request_ctx.server.get_enable_http_validation() {
@@ -178,12 +192,10 @@ fn set_cookie_headers<Ctx: ResolverContextLike>(
 pub async fn execute_raw_request<Ctx: ResolverContextLike>(
     ctx: &EvalContext<'_, Ctx>,
     req: Request,
+    retry: Option<&RetryPolicy>,
+    idempotent: bool,
 ) -> Result<Response<async_graphql::Value>, Error> {
-    let response = ctx
-        .request_ctx
-        .runtime
-        .http
-        .execute(req)
+    let response = retry::execute(&*ctx.request_ctx.runtime.http, req, retry, idempotent)
         .await
         .map_err(Error::from)?
         .to_json()?;
@@ -195,8 +207,12 @@ pub async fn execute_raw_grpc_request<Ctx: ResolverContextLike>(
     ctx: &EvalContext<'_, Ctx>,
     req: Request,
     operation: &ProtobufOperation,
+    retry: Option<&RetryPolicy>,
+    tls: Option<&Tls>,
+    idempotent: bool,
 ) -> Result<Response<async_graphql::Value>, Error> {
-    execute_grpc_request(&ctx.request_ctx.runtime, operation, req)
+    let runtime = ctx.request_ctx.runtime.with_tls(tls);
+    execute_grpc_request(&runtime, operation, req, retry, idempotent)
         .await
         .map_err(Error::from)
 }
@@ -1,9 +1,15 @@
+use std::pin::pin;
+
 use async_graphql_value::ConstValue;
+use futures_timer::Delay;
+use futures_util::future::{select, Either};
+use futures_util::StreamExt;
 
 use super::eval_http::{
     execute_grpc_request_with_dl, execute_raw_grpc_request, execute_raw_request,
     execute_request_with_dl, parse_graphql_response, set_headers, EvalHttp,
 };
+use super::eval_stream::eval_sse;
 use super::model::{CacheKey, IO};
 use super::{EvalContext, ResolverContextLike};
 use crate::core::config::GraphQLOperationType;
@@ -15,6 +21,22 @@ use crate::core::http::DataLoaderRequest;
 use crate::core::ir::Error;
 
 pub async fn eval_io<Ctx>(io: &IO, ctx: &mut EvalContext<'_, Ctx>) -> Result<ConstValue, Error>
+where
+    Ctx: ResolverContextLike + Sync,
+{
+    match io.timeout() {
+        Some(timeout) => {
+            // futures_timer is used instead of tokio to also support wasm targets
+            match select(pin!(eval_io_dedupe(io, ctx)), Delay::new(timeout)).await {
+                Either::Left((result, _)) => result,
+                Either::Right(_) => Err(Error::Timeout { timeout }),
+            }
+        }
+        None => eval_io_dedupe(io, ctx).await,
+    }
+}
+
+async fn eval_io_dedupe<Ctx>(io: &IO, ctx: &mut EvalContext<'_, Ctx>) -> Result<ConstValue, Error>
 where
     Ctx: ResolverContextLike + Sync,
 {
@@ -45,9 +67,9 @@ where
     Ctx: ResolverContextLike + Sync,
 {
     match io {
-        IO::Http { req_template, dl_id, http_filter, .. } => {
+        IO::Http { req_template, dl_id, http_filter, retry, tls, .. } => {
             let worker = &ctx.request_ctx.runtime.cmd_worker;
-            let eval_http = EvalHttp::new(ctx, req_template, dl_id);
+            let eval_http = EvalHttp::new(ctx, req_template, dl_id, retry.as_ref(), tls.as_ref());
             let request = eval_http.init_request()?;
             let response = match (&worker, http_filter) {
                 (Some(worker), Some(http_filter)) => {
@@ -60,7 +82,7 @@ where
 
             Ok(response.body)
         }
-        IO::GraphQL { req_template, field_name, dl_id, .. } => {
+        IO::GraphQL { req_template, field_name, dl_id, retry, .. } => {
             let req = req_template.to_request(ctx)?;
 
             let res = if ctx.request_ctx.upstream.batch.is_some()
@@ -70,13 +92,14 @@ where
                     dl_id.and_then(|dl| ctx.request_ctx.gql_data_loaders.get(dl.as_usize()));
                 execute_request_with_dl(ctx, req, data_loader).await?
             } else {
-                execute_raw_request(ctx, req).await?
+                let idempotent = matches!(req_template.operation_type, GraphQLOperationType::Query);
+                execute_raw_request(ctx, req, retry.as_ref(), idempotent).await?
             };
 
             set_headers(ctx, &res);
             parse_graphql_response(ctx, res, field_name)
         }
-        IO::Grpc { req_template, dl_id, .. } => {
+        IO::Grpc { req_template, dl_id, retry, tls, .. } => {
             let rendered = req_template.render(ctx)?;
 
             let res = if ctx.request_ctx.upstream.batch.is_some() &&
@@ -88,7 +111,16 @@ where
                 execute_grpc_request_with_dl(ctx, rendered, data_loader).await?
             } else {
                 let req = rendered.to_request()?;
-                execute_raw_grpc_request(ctx, req, &req_template.operation).await?
+                let idempotent = matches!(req_template.operation_type, GraphQLOperationType::Query);
+                execute_raw_grpc_request(
+                    ctx,
+                    req,
+                    &req_template.operation,
+                    retry.as_ref(),
+                    tls.as_ref(),
+                    idempotent,
+                )
+                .await?
             };
 
             set_headers(ctx, &res);
@@ -109,5 +141,10 @@ where
                 Ok(ConstValue::Null)
             }
         }
+        IO::Sse { req_template } => {
+            // outside of a subscription only the first event is resolved
+            let mut values = eval_sse(req_template, ctx).await?;
+            values.next().await.unwrap_or(Ok(ConstValue::Null))
+        }
     }
 }
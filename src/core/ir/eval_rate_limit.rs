@@ -0,0 +1,163 @@
+use std::borrow::Cow;
+
+use super::model::RateLimit;
+use super::{Error, EvalContext, ResolverContextLike};
+use crate::core::path::PathString;
+use crate::core::rate_limit::Decision;
+
+/// Context used to render the keys of rate limits and cached values. On top of
+/// the usual context it exposes the address of the client as `client.ip`.
+pub(super) struct KeyContext<'a, 'b, Ctx: ResolverContextLike> {
+    pub(super) ctx: &'b EvalContext<'a, Ctx>,
+}
+
+impl<Ctx: ResolverContextLike> PathString for KeyContext<'_, '_, Ctx> {
+    fn path_string<T: AsRef<str>>(&self, path: &[T]) -> Option<Cow<'_, str>> {
+        let request_ctx = self.ctx.request_ctx;
+
+        match path.split_first() {
+            Some((head, [tail])) if head.as_ref() == "client" && tail.as_ref() == "ip" => {
+                request_ctx.client_ip.map(|ip| Cow::Owned(ip.to_string()))
+            }
+            _ => self.ctx.path_string(path),
+        }
+    }
+}
+
+impl RateLimit {
+    /// Counts a call of the client against the limit, failing once the limit
+    /// is exceeded.
+    pub async fn acquire<Ctx>(&self, ctx: &EvalContext<'_, Ctx>) -> Result<(), Error>
+    where
+        Ctx: ResolverContextLike + Sync,
+    {
+        let client = match &self.key {
+            Some(key) => {
+                let key_ctx = KeyContext { ctx };
+                let resolved = key.expression_segments().iter().all(|path| {
+                    key_ctx
+                        .path_string(path)
+                        .is_some_and(|value| !value.is_empty())
+                });
+                resolved.then(|| key.render(&key_ctx))
+            }
+            None => ctx.request_ctx.client_ip.map(|ip| ip.to_string()),
+        };
+        // the calls of the clients that can't be identified would otherwise
+        // share a single limit, so that a client could exhaust it for all
+        let Some(client) = client else {
+            return Err(Error::UnidentifiedClient);
+        };
+        let key = format!("{}:{}", self.scope, client);
+
+        match ctx
+            .request_ctx
+            .runtime
+            .rate_limiter
+            .acquire(&key, &self.policy)
+            .await
+        {
+            Ok(Decision::Allowed) => Ok(()),
+            Ok(Decision::Limited { retry_after }) => Err(Error::RateLimited { retry_after }),
+            Err(err) => {
+                // limits are not enforced while the store is unavailable
+                tracing::warn!("Failed to check the rate limit: {}", err);
+                Ok(())
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::{IpAddr, Ipv4Addr};
+    use std::num::NonZeroU64;
+
+    use super::*;
+    use crate::core::config::RateLimitAlgorithm;
+    use crate::core::http::RequestContext;
+    use crate::core::ir::EmptyResolverContext;
+    use crate::core::mustache::Mustache;
+    use crate::core::rate_limit::RateLimitPolicy;
+
+    fn rate_limit(key: Option<&str>) -> RateLimit {
+        RateLimit {
+            scope: "Query.users".to_string(),
+            key: key.map(Mustache::parse),
+            policy: RateLimitPolicy {
+                requests: NonZeroU64::new(1).unwrap(),
+                window: NonZeroU64::new(60_000).unwrap(),
+                algorithm: RateLimitAlgorithm::SlidingWindow,
+            },
+        }
+    }
+
+    fn request_ctx(ip: [u8; 4], sub: &str) -> RequestContext {
+        let req_ctx = RequestContext::default().client_ip(Some(IpAddr::V4(Ipv4Addr::from(ip))));
+        *req_ctx.claims.lock().unwrap() = Some(serde_json::json!({ "sub": sub }));
+        req_ctx
+    }
+
+    #[test]
+    fn test_key_context() {
+        let req_ctx = request_ctx([127, 0, 0, 1], "alice");
+        let ctx = EvalContext::new(&req_ctx, &EmptyResolverContext);
+        let key_ctx = KeyContext { ctx: &ctx };
+
+        let actual = Mustache::parse("{{.client.ip}}/{{.claims.sub}}").render(&key_ctx);
+
+        assert_eq!(actual, "127.0.0.1/alice");
+    }
+
+    #[tokio::test]
+    async fn test_limits_by_client_ip() {
+        let rate_limit = rate_limit(None);
+        let first = request_ctx([10, 0, 0, 1], "alice");
+        let second = request_ctx([10, 0, 0, 2], "alice");
+        let second = second.runtime(first.runtime.clone());
+
+        let ctx = EvalContext::new(&first, &EmptyResolverContext);
+        assert!(rate_limit.acquire(&ctx).await.is_ok());
+        assert!(matches!(
+            rate_limit.acquire(&ctx).await,
+            Err(Error::RateLimited { .. })
+        ));
+
+        let ctx = EvalContext::new(&second, &EmptyResolverContext);
+        assert!(rate_limit.acquire(&ctx).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_limits_by_key() {
+        let rate_limit = rate_limit(Some("{{.claims.sub}}"));
+        let first = request_ctx([10, 0, 0, 1], "alice");
+        let second = request_ctx([10, 0, 0, 2], "alice");
+        let second = second.runtime(first.runtime.clone());
+
+        let ctx = EvalContext::new(&first, &EmptyResolverContext);
+        assert!(rate_limit.acquire(&ctx).await.is_ok());
+
+        let ctx = EvalContext::new(&second, &EmptyResolverContext);
+        assert!(matches!(
+            rate_limit.acquire(&ctx).await,
+            Err(Error::RateLimited { .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_rejects_unidentified_clients() {
+        let req_ctx = RequestContext::default();
+        let ctx = EvalContext::new(&req_ctx, &EmptyResolverContext);
+
+        assert!(matches!(
+            rate_limit(None).acquire(&ctx).await,
+            Err(Error::UnidentifiedClient)
+        ));
+        assert!(matches!(
+            rate_limit(Some("{{.headers.x-api-key}}"))
+                .acquire(&ctx)
+                .await,
+            Err(Error::UnidentifiedClient)
+        ));
+    }
+}
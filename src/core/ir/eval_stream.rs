@@ -0,0 +1,101 @@
+use std::future::Future;
+
+use async_graphql_value::ConstValue;
+use futures_util::future::ready;
+use futures_util::stream::{self, BoxStream};
+use futures_util::StreamExt;
+use reqwest::header::{HeaderValue, ACCEPT};
+
+use super::model::{IO, IR};
+use super::{Error, EvalContext, ResolverContextLike};
+use crate::core::auth::verify::{AuthVerifier, Verify};
+use crate::core::http::{parse_events, RequestTemplate};
+
+/// Stream of values produced by the root field of a subscription
+pub type ValueStream = BoxStream<'static, Result<ConstValue, Error>>;
+
+impl IR {
+    /// Evaluates the IR as the source of a subscription. Resolvers that
+    /// produce a single value resolve into a stream with exactly one event.
+    pub fn eval_stream<'a, 'b, Ctx>(
+        &'a self,
+        ctx: &'b mut EvalContext<'a, Ctx>,
+    ) -> impl Future<Output = Result<ValueStream, Error>> + Send + use<'a, 'b, Ctx>
+    where
+        Ctx: ResolverContextLike + Sync,
+    {
+        Box::pin(async move {
+            match self {
+                IR::Protect(auth, expr) => {
+                    let verifier = AuthVerifier::from(auth.clone());
+                    verifier.verify(ctx.request_ctx).await.to_result()?;
+
+                    expr.eval_stream(ctx).await
+                }
+                IR::RateLimit(rate_limit, expr) => {
+                    rate_limit.acquire(ctx).await?;
+
+                    expr.eval_stream(ctx).await
+                }
+                IR::IO(IO::Sse { req_template }) => eval_sse(req_template, ctx).await,
+                ir => {
+                    let value = ir.eval(ctx).await?;
+                    Ok(stream::once(ready(Ok(value))).boxed())
+                }
+            }
+        })
+    }
+}
+
+/// Opens the event stream described by the request template. The data of every
+/// event is parsed as JSON, falling back to a plain string when it isn't.
+pub async fn eval_sse<Ctx>(
+    req_template: &RequestTemplate,
+    ctx: &EvalContext<'_, Ctx>,
+) -> Result<ValueStream, Error>
+where
+    Ctx: ResolverContextLike + Sync,
+{
+    let mut request = req_template.to_request(ctx)?;
+    request
+        .headers_mut()
+        .entry(ACCEPT)
+        .or_insert(HeaderValue::from_static("text/event-stream"));
+
+    let chunks = ctx.request_ctx.runtime.http.execute_stream(request).await?;
+    let values = parse_events(chunks).map(|event| -> Result<ConstValue, Error> {
+        let data = event?;
+        Ok(serde_json::from_str(&data).unwrap_or(ConstValue::String(data)))
+    });
+
+    Ok(values.boxed())
+}
+
+#[cfg(test)]
+mod tests {
+    use async_graphql_value::ConstValue;
+    use futures_util::StreamExt;
+
+    use crate::core::blueprint::DynamicValue;
+    use crate::core::http::RequestContext;
+    use crate::core::ir::model::IR;
+    use crate::core::ir::{EmptyResolverContext, EvalContext};
+
+    #[tokio::test]
+    async fn test_single_value_stream() {
+        let req_ctx = RequestContext::default();
+        let res_ctx = EmptyResolverContext {};
+        let mut ctx = EvalContext::new(&req_ctx, &res_ctx);
+        let ir = IR::Dynamic(DynamicValue::Value(ConstValue::from(1)));
+
+        let values = ir
+            .eval_stream(&mut ctx)
+            .await
+            .unwrap()
+            .collect::<Vec<_>>()
+            .await;
+
+        assert_eq!(values.len(), 1);
+        assert_eq!(values[0].as_ref().unwrap(), &ConstValue::from(1));
+    }
+}
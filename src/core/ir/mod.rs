@@ -1,9 +1,12 @@
 mod discriminator;
 mod error;
 mod eval;
+mod eval_cache;
 mod eval_context;
 mod eval_http;
 mod eval_io;
+mod eval_rate_limit;
+mod eval_stream;
 mod resolver_context_like;
 
 pub mod model;
@@ -12,9 +15,11 @@ use std::ops::Deref;
 
 pub use discriminator::*;
 pub use error::*;
-pub use eval_context::EvalContext;
+pub use eval_context::{DetachedEvalContext, EvalContext};
+pub use eval_stream::ValueStream;
 pub use resolver_context_like::{
-    EmptyResolverContext, ResolverContext, ResolverContextLike, SelectionField,
+    DetachedResolverContext, EmptyResolverContext, ResolverContext, ResolverContextLike,
+    SelectionField,
 };
 
 /// Contains all the nested fields that are resolved with current parent
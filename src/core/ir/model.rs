@@ -1,17 +1,21 @@
 use std::collections::HashMap;
 use std::fmt::Debug;
 use std::num::NonZeroU64;
+use std::time::Duration;
 
 use async_graphql::Value;
 use strum_macros::Display;
 
 use super::discriminator::Discriminator;
 use super::{EvalContext, ResolverContextLike};
-use crate::core::blueprint::{Auth, DynamicValue};
+use crate::core::blueprint::{Auth, DynamicValue, Tls};
 use crate::core::config::group_by::GroupBy;
 use crate::core::graphql::{self};
 use crate::core::http::HttpFilter;
-use crate::core::{grpc, http};
+use crate::core::mustache::Mustache;
+use crate::core::rate_limit::RateLimitPolicy;
+use crate::core::retry::RetryPolicy;
+use crate::core::{config, grpc, http};
 
 #[derive(Clone, Debug, Display)]
 pub enum IR {
@@ -23,6 +27,8 @@ pub enum IR {
     Path(Box<IR>, Vec<String>),
     ContextPath(Vec<String>),
     Protect(Auth, Box<IR>),
+    RateLimit(RateLimit, Box<IR>),
+    Invalidate(Invalidate, Box<IR>),
     Map(Map),
     Pipe(Box<IR>, Box<IR>),
     Discriminate(Discriminator, Box<IR>),
@@ -48,6 +54,9 @@ pub enum IO {
         http_filter: Option<HttpFilter>,
         is_list: bool,
         dedupe: bool,
+        retry: Option<RetryPolicy>,
+        timeout: Option<Duration>,
+        tls: Option<Tls>,
     },
     GraphQL {
         req_template: graphql::RequestTemplate,
@@ -55,16 +64,24 @@ pub enum IO {
         batch: bool,
         dl_id: Option<DataLoaderId>,
         dedupe: bool,
+        retry: Option<RetryPolicy>,
+        timeout: Option<Duration>,
     },
     Grpc {
         req_template: grpc::RequestTemplate,
         group_by: Option<GroupBy>,
         dl_id: Option<DataLoaderId>,
         dedupe: bool,
+        retry: Option<RetryPolicy>,
+        timeout: Option<Duration>,
+        tls: Option<Tls>,
     },
     Js {
         name: String,
     },
+    Sse {
+        req_template: http::RequestTemplate,
+    },
 }
 
 impl IO {
@@ -74,7 +91,30 @@ impl IO {
             IO::GraphQL { dedupe, .. } => *dedupe,
             IO::Grpc { dedupe, .. } => *dedupe,
             IO::Js { .. } => false,
+            IO::Sse { .. } => false,
+        }
+    }
+
+    /// The time after which evaluating the IO is abandoned
+    pub fn timeout(&self) -> Option<Duration> {
+        match self {
+            IO::Http { timeout, .. } => *timeout,
+            IO::GraphQL { timeout, .. } => *timeout,
+            IO::Grpc { timeout, .. } => *timeout,
+            IO::Js { .. } => None,
+            IO::Sse { .. } => None,
+        }
+    }
+
+    /// Replaces the timeout of the IO, if it supports one
+    pub fn with_timeout(mut self, duration: Duration) -> Self {
+        match &mut self {
+            IO::Http { timeout, .. } | IO::GraphQL { timeout, .. } | IO::Grpc { timeout, .. } => {
+                *timeout = Some(duration)
+            }
+            IO::Js { .. } | IO::Sse { .. } => {}
         }
+        self
     }
 }
 
@@ -94,6 +134,12 @@ impl DataLoaderId {
 #[derive(PartialEq, Eq, Clone, Hash, Debug)]
 pub struct IoId(u64);
 
+impl std::fmt::Display for IoId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
 impl IoId {
     pub fn new(id: u64) -> Self {
         Self(id)
@@ -111,7 +157,30 @@ pub trait CacheKey<Ctx> {
 #[derive(Clone, Debug)]
 pub struct Cache {
     pub max_age: NonZeroU64,
+    /// How long an expired value is served for while it's refreshed in the
+    /// background
+    pub stale_while_revalidate: Option<NonZeroU64>,
+    /// How long an expired value is served for when refreshing it fails
+    pub stale_if_error: Option<NonZeroU64>,
+    /// How long the values are kept in the cache, including the time they can
+    /// be served stale
+    pub ttl: NonZeroU64,
     pub io: Box<IO>,
+    /// The field or type the cache is configured on, keeping apart the keys
+    /// rendered for each of them
+    pub scope: String,
+    /// The position of the IO among the ones the cache wraps, keeping apart
+    /// the keys rendered for each of them
+    pub position: usize,
+    /// Renders the key of the values, used instead of the request made to
+    /// fetch them
+    pub key: Option<Mustache>,
+    /// Values the key varies with
+    pub vary_by: Vec<Mustache>,
+    /// The values are cached for each client separately
+    pub private: bool,
+    /// Tags of the cached value, rendered once the value is fetched
+    pub tags: Vec<Mustache>,
 }
 
 impl Cache {
@@ -119,14 +188,63 @@ impl Cache {
     /// Wraps an expression with the cache primitive.
     /// Performance DFS on the cache on the expression and identifies all the IO
     /// nodes. Then wraps each IO node with the cache primitive.
-    pub fn wrap(max_age: NonZeroU64, expr: IR) -> IR {
+    pub fn wrap(cache: &config::Cache, scope: String, expr: IR) -> IR {
+        let ttl = cache.ttl();
+        let key = cache.key.as_deref().map(Mustache::parse);
+        let vary_by = cache
+            .vary_by
+            .iter()
+            .map(|path| Mustache::parse(&format!("{{{{.{}}}}}", path)))
+            .collect::<Vec<_>>();
+        let tags = cache
+            .tags
+            .iter()
+            .map(|tag| Mustache::parse(tag))
+            .collect::<Vec<_>>();
+        let mut position = 0;
         expr.modify(&mut move |expr| match expr {
-            IR::IO(io) => Some(IR::Cache(Cache { max_age, io: Box::new(io.to_owned()) })),
+            IR::IO(io) => {
+                position += 1;
+                Some(IR::Cache(Cache {
+                    max_age: cache.max_age,
+                    stale_while_revalidate: cache.stale_while_revalidate,
+                    stale_if_error: cache.stale_if_error,
+                    ttl,
+                    io: Box::new(io.to_owned()),
+                    scope: scope.clone(),
+                    position,
+                    key: key.clone(),
+                    vary_by: vary_by.clone(),
+                    private: cache.private,
+                    tags: tags.clone(),
+                }))
+            }
             _ => None,
         })
     }
 }
 
+/// Invalidates the tags of the cached values once the wrapped expression
+/// succeeds
+#[derive(Clone, Debug)]
+pub struct Invalidate {
+    pub tags: Vec<Mustache>,
+    /// How long the invalidation is kept, the longest `maxAge` of the tagged
+    /// values
+    pub max_age: NonZeroU64,
+}
+
+/// Limits the calls made by a client to the wrapped expression
+#[derive(Clone, Debug)]
+pub struct RateLimit {
+    /// The field or type the limit is configured on, so that the calls made to
+    /// each of them are counted separately
+    pub scope: String,
+    /// Identifies the client, the address of the client is used when not set
+    pub key: Option<Mustache>,
+    pub policy: RateLimitPolicy,
+}
+
 impl IR {
     pub fn pipe(self, next: Self) -> Self {
         IR::Pipe(Box::new(self), Box::new(next))
@@ -153,15 +271,21 @@ impl IR {
                     IR::ContextPath(path) => IR::ContextPath(path),
                     IR::Dynamic(_) => expr,
                     IR::IO(_) => expr,
-                    IR::Cache(Cache { io, max_age }) => {
-                        let expr = *IR::IO(*io).modify_box(modifier);
+                    IR::Cache(cache) => {
+                        let expr = *IR::IO(*cache.io.clone()).modify_box(modifier);
                         match expr {
-                            IR::IO(io) => IR::Cache(Cache { io: Box::new(io), max_age }),
+                            IR::IO(io) => IR::Cache(Cache { io: Box::new(io), ..cache }),
                             expr => expr,
                         }
                     }
                     IR::Path(expr, path) => IR::Path(expr.modify_box(modifier), path),
                     IR::Protect(auth, expr) => IR::Protect(auth, expr.modify_box(modifier)),
+                    IR::RateLimit(rate_limit, expr) => {
+                        IR::RateLimit(rate_limit, expr.modify_box(modifier))
+                    }
+                    IR::Invalidate(invalidate, expr) => {
+                        IR::Invalidate(invalidate, expr.modify_box(modifier))
+                    }
                     IR::Map(Map { input, map }) => {
                         IR::Map(Map { input: input.modify_box(modifier), map })
                     }
@@ -187,6 +311,7 @@ impl<'a, Ctx: ResolverContextLike + Sync> CacheKey<EvalContext<'a, Ctx>> for IO
             IO::Grpc { req_template, .. } => req_template.cache_key(ctx),
             IO::GraphQL { req_template, .. } => req_template.cache_key(ctx),
             IO::Js { .. } => None,
+            IO::Sse { .. } => None,
         }
     }
 }
@@ -64,10 +64,71 @@ impl Conditions {
     }
 }
 
+fn get_condition(dir: &Directive) -> Option<Condition> {
+    let arg = dir.get_argument("if").map(|pos| &pos.node);
+    match arg {
+        None => None,
+        Some(value) => match value {
+            Value::Boolean(bool) => {
+                let condition = if *bool {
+                    Condition::True
+                } else {
+                    Condition::False
+                };
+                Some(condition)
+            }
+            Value::Variable(var) => {
+                Some(Condition::Variable(Variable::new(var.deref().to_owned())))
+            }
+            _ => None,
+        },
+    }
+}
+
+/// Reads the `if` argument of `@defer` and `@stream`. Returns `None` when the
+/// directive is disabled with `if: false`.
+fn incremental_condition(directive: &Directive) -> Option<Option<Variable>> {
+    match get_condition(directive).unwrap_or(Condition::True) {
+        Condition::True => Some(None),
+        Condition::False => None,
+        Condition::Variable(var) => Some(Some(var)),
+    }
+}
+
+fn get_label(directive: &Directive) -> Option<String> {
+    match directive.get_argument("label").map(|pos| &pos.node) {
+        Some(Value::String(label)) => Some(label.clone()),
+        _ => None,
+    }
+}
+
+fn find_directive<'a>(
+    directives: &'a [Positioned<Directive>],
+    name: &str,
+) -> Option<&'a Directive> {
+    directives
+        .iter()
+        .find(|d| d.node.name.node.as_str() == name)
+        .map(|d| &d.node)
+}
+
+/// Marks the fields of a deferred fragment, fields that are part of a nested
+/// deferred fragment keep their own [Defer]
+fn with_defer(fields: Vec<Field<Value>>, defer: Option<Defer>) -> Vec<Field<Value>> {
+    match defer {
+        Some(defer) => fields
+            .into_iter()
+            .map(|field| Field { defer: field.defer.or_else(|| Some(defer.clone())), ..field })
+            .collect(),
+        None => fields,
+    }
+}
+
 pub struct Builder {
     pub index: Arc<Index>,
     pub arg_id: Counter<usize>,
     pub field_id: Counter<usize>,
+    pub defer_id: Counter<usize>,
     pub document: ExecutableDocument,
 }
 
@@ -80,6 +141,7 @@ impl Builder {
             index,
             arg_id: Counter::default(),
             field_id: Counter::default(),
+            defer_id: Counter::default(),
         }
     }
 
@@ -88,26 +150,6 @@ impl Builder {
         &self,
         directives: &[Positioned<async_graphql::parser::types::Directive>],
     ) -> Conditions {
-        fn get_condition(dir: &Directive) -> Option<Condition> {
-            let arg = dir.get_argument("if").map(|pos| &pos.node);
-            match arg {
-                None => None,
-                Some(value) => match value {
-                    Value::Boolean(bool) => {
-                        let condition = if *bool {
-                            Condition::True
-                        } else {
-                            Condition::False
-                        };
-                        Some(condition)
-                    }
-                    Value::Variable(var) => {
-                        Some(Condition::Variable(Variable::new(var.deref().to_owned())))
-                    }
-                    _ => None,
-                },
-            }
-        }
         Conditions {
             skip: directives
                 .iter()
@@ -124,6 +166,34 @@ impl Builder {
         }
     }
 
+    /// Reads `@defer` of a fragment. The id is taken before the fields of the
+    /// fragment are built, so that nested deferred fragments get a greater id.
+    fn defer(&self, directives: &[Positioned<Directive>]) -> Option<Defer> {
+        let directive = find_directive(directives, "defer")?;
+        let condition = incremental_condition(directive)?;
+
+        Some(Defer {
+            id: self.defer_id.next(),
+            label: get_label(directive),
+            condition,
+        })
+    }
+
+    /// Reads `@stream` of a field, it only applies to lists
+    fn stream(&self, directives: &[Positioned<Directive>], type_of: &Type) -> Option<Stream> {
+        if !type_of.is_list() {
+            return None;
+        }
+        let directive = find_directive(directives, "stream")?;
+        let condition = incremental_condition(directive)?;
+        let initial_count = match directive.get_argument("initialCount").map(|pos| &pos.node) {
+            Some(Value::Number(count)) => count.as_u64().unwrap_or_default() as usize,
+            _ => 0,
+        };
+
+        Some(Stream { label: get_label(directive), initial_count, condition })
+    }
+
     #[allow(clippy::too_many_arguments)]
     #[inline(always)]
     fn iter(
@@ -147,7 +217,10 @@ impl Builder {
                     let mut directives = Vec::with_capacity(gql_field.directives.len());
                     for directive in &gql_field.directives {
                         let directive = &directive.node;
-                        if directive.name.node == "skip" || directive.name.node == "include" {
+                        if matches!(
+                            directive.name.node.as_str(),
+                            "skip" | "include" | "defer" | "stream"
+                        ) {
                             continue;
                         }
                         let arguments = directive
@@ -216,6 +289,8 @@ impl Builder {
                             None
                         };
 
+                        let stream = self.stream(&gql_field.directives, &type_of);
+
                         // Create the field with its child fields in `selection`
                         let field = Field {
                             id,
@@ -236,6 +311,8 @@ impl Builder {
                             pos: selection.pos.into(),
                             directives,
                             scalar,
+                            defer: None,
+                            stream,
                         };
 
                         fields.push(field);
@@ -255,6 +332,8 @@ impl Builder {
                             directives,
                             is_enum: false,
                             scalar: Some(scalar::Scalar::Empty),
+                            defer: None,
+                            stream: None,
                         };
 
                         fields.push(typename_field);
@@ -264,11 +343,13 @@ impl Builder {
                     if let Some(fragment) =
                         fragments.get(fragment_spread.fragment_name.node.as_str())
                     {
-                        fields.extend(self.iter(
+                        let defer = self.defer(&fragment_spread.directives);
+                        let fragment_fields = self.iter(
                             &fragment.selection_set.node,
                             fragment.type_condition.node.on.node.as_str(),
                             fragments,
-                        ));
+                        );
+                        fields.extend(with_defer(fragment_fields, defer));
                     }
                 }
                 Selection::InlineFragment(Positioned { node: fragment, .. }) => {
@@ -278,7 +359,10 @@ impl Builder {
                         .map(|cond| cond.node.on.node.as_str())
                         .unwrap_or(type_condition);
 
-                    fields.extend(self.iter(&fragment.selection_set.node, type_of, fragments));
+                    let defer = self.defer(&fragment.directives);
+                    let fragment_fields =
+                        self.iter(&fragment.selection_set.node, type_of, fragments);
+                    fields.extend(with_defer(fragment_fields, defer));
                 }
             }
         }
@@ -290,7 +374,7 @@ impl Builder {
         match ty {
             OperationType::Query => Some(self.index.get_query()),
             OperationType::Mutation => self.index.get_mutation(),
-            OperationType::Subscription => None,
+            OperationType::Subscription => self.index.get_subscription(),
         }
     }
 
@@ -336,6 +420,10 @@ impl Builder {
             .ok_or(BuildError::RootOperationTypeNotDefined { operation: operation.ty })?;
         let fields = self.iter(&operation.selection_set.node, name, &fragments);
 
+        if operation.ty == OperationType::Subscription && fields.len() != 1 {
+            return Err(BuildError::SubscriptionSingleRootField);
+        }
+
         let is_introspection_query = operation.selection_set.node.items.iter().any(|f| {
             if let Selection::Field(Positioned { node: gql_field, .. }) = &f.node {
                 let query = gql_field.name.node.as_str();
@@ -458,6 +546,39 @@ mod tests {
         insta::assert_debug_snapshot!(plan.selection);
     }
 
+    #[test]
+    fn test_subscription() {
+        let plan = plan(
+            r#"
+            subscription {
+                postUpdated(id: 1) { id title }
+            }
+        "#,
+        );
+
+        assert_eq!(plan.operation_type(), OperationType::Subscription);
+        assert_eq!(plan.root_name(), "Subscription");
+        assert_eq!(plan.size(), 3);
+    }
+
+    #[test]
+    fn test_subscription_multiple_root_fields() {
+        let config = Config::from_sdl(CONFIG).to_result().unwrap();
+        let blueprint = Blueprint::try_from(&config.into()).unwrap();
+        let document = async_graphql::parser::parse_query(
+            r#"
+            subscription {
+                first: postUpdated(id: 1) { id }
+                second: postUpdated(id: 2) { id }
+            }
+        "#,
+        )
+        .unwrap();
+        let error = Builder::new(&blueprint, document).build(None).unwrap_err();
+
+        assert_eq!(error, BuildError::SubscriptionSingleRootField);
+    }
+
     #[test]
     fn test_fragments() {
         let plan = plan(
@@ -681,4 +802,76 @@ mod tests {
         assert!(plan.is_query());
         insta::assert_debug_snapshot!(plan.selection);
     }
+
+    #[test]
+    fn test_defer() {
+        let plan = plan(
+            r#"
+            fragment UserPII on User {
+                email
+                ... @defer(label: "phone") { phone }
+            }
+
+            query($deferName: Boolean!) {
+                user(id: 1) {
+                    id
+                    ...UserPII @defer(label: "pii")
+                    ... @defer(if: $deferName) { name }
+                    ... @defer(if: false) { username }
+                }
+            }
+            "#,
+        );
+
+        let user = &plan.selection[0];
+        let defer = |name: &str| {
+            user.iter()
+                .find(|field| field.name == name)
+                .and_then(|field| field.defer.clone())
+        };
+
+        assert_eq!(defer("id"), None);
+        assert_eq!(
+            defer("email"),
+            Some(Defer { id: 0, label: Some("pii".to_string()), condition: None })
+        );
+        assert_eq!(
+            defer("phone"),
+            Some(Defer { id: 1, label: Some("phone".to_string()), condition: None })
+        );
+        assert_eq!(
+            defer("name"),
+            Some(Defer {
+                id: 2,
+                label: None,
+                condition: Some(Variable::new("deferName".to_string()))
+            })
+        );
+        assert_eq!(defer("username"), None);
+        assert!(user.iter().all(|field| field.directives.is_empty()));
+    }
+
+    #[test]
+    fn test_stream() {
+        let plan = plan(
+            r#"
+            query {
+                posts @stream(initialCount: 2, label: "posts") { id }
+                user(id: 1) @stream { id }
+            }
+            "#,
+        );
+
+        assert_eq!(
+            plan.selection[0].stream,
+            Some(Stream {
+                label: Some("posts".to_string()),
+                initial_count: 2,
+                condition: None
+            })
+        );
+        assert!(plan.selection[0].directives.is_empty());
+        // only lists can be streamed
+        assert_eq!(plan.selection[1].stream, None);
+    }
 }
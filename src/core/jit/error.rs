@@ -14,6 +14,8 @@ pub enum BuildError {
     OperationNotFound(String),
     #[error("Operation name required in request")]
     OperationNameRequired,
+    #[error("Subscription operations must select exactly one top level field")]
+    SubscriptionSingleRootField,
 }
 
 #[derive(Error, Debug, Clone, PartialEq, Eq)]
@@ -28,6 +30,18 @@ pub enum ResolveInputError {
     },
 }
 
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum LimitError {
+    #[error("Query depth of {actual} exceeds the maximum depth of {max}")]
+    Depth { actual: usize, max: usize },
+    #[error("Query selects {actual} fields, exceeding the maximum of {max}")]
+    Fields { actual: usize, max: usize },
+    #[error("Query uses {actual} aliases, exceeding the maximum of {max}")]
+    Aliases { actual: usize, max: usize },
+    #[error("Query complexity of {actual} exceeds the maximum complexity of {max}")]
+    Complexity { actual: usize, max: usize },
+}
+
 #[derive(Error, Debug, Clone)]
 pub enum ValidationError {
     // TODO: replace with sane error message. Right now, it's defined as is only for compatibility
@@ -52,6 +66,10 @@ pub enum Error {
     Validation(#[from] ValidationError),
     #[error("{0}")]
     ServerError(async_graphql::ServerError),
+    #[error(transparent)]
+    Limit(#[from] LimitError),
+    #[error("Only trusted documents are allowed to be executed")]
+    UntrustedDocument,
     #[error("Unexpected error")]
     Unknown,
 }
@@ -63,8 +81,11 @@ impl ErrorExtensions for Error {
             Error::ParseError(error) => error.extend(),
             Error::IR(error) => error.extend(),
             Error::Validation(error) => error.extend(),
+            Error::Limit(error) => error.extend(),
             Error::ServerError(error) => error.extend(),
-            Error::Unknown => super::graphql_error::Error::new(self.to_string()),
+            Error::UntrustedDocument | Error::Unknown => {
+                super::graphql_error::Error::new(self.to_string())
+            }
         }
     }
 }
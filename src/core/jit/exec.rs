@@ -34,7 +34,17 @@ where
     }
 
     pub async fn store(&self) -> Store<Result<Value, Positioned<jit::Error>>> {
-        let store = Arc::new(Mutex::new(Store::new()));
+        self.store_with(Store::new()).await
+    }
+
+    /// Executes the plan on top of a store that already holds the values of
+    /// some fields. The IR of those fields isn't executed again, only their
+    /// nested fields are resolved.
+    pub async fn store_with(
+        &self,
+        store: Store<Result<Value, Positioned<jit::Error>>>,
+    ) -> Store<Result<Value, Positioned<jit::Error>>> {
+        let store = Arc::new(Mutex::new(store));
         let mut ctx = ExecutorInner::new(store.clone(), &self.exec, &self.ctx);
         ctx.init().await;
 
@@ -104,6 +114,19 @@ where
         let field = ctx.field();
 
         if let Some(ir) = &field.ir {
+            let stored = self
+                .store
+                .lock()
+                .unwrap()
+                .get(&field.id)
+                .map(|result| result.as_ref().ok().cloned());
+            if let Some(value) = stored {
+                if let Some(value) = value {
+                    self.iter_field(ctx, &value).await?;
+                }
+                return Ok(());
+            }
+
             let result = self.ir_exec.execute(ir, ctx).await;
 
             if let Ok(value) = &result {
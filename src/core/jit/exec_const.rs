@@ -1,14 +1,21 @@
 use std::sync::Arc;
 
 use async_graphql_value::{ConstValue, Value};
-use futures_util::future::join_all;
+use futures_util::future::{join_all, ready};
+use futures_util::stream::{self, BoxStream, FuturesUnordered};
+use futures_util::StreamExt;
 use tailcall_valid::Validator;
 
 use super::context::Context;
 use super::exec::{Executor, IRExecutor};
 use super::graphql_error::GraphQLError;
-use super::{transform, AnyResponse, BuildError, Error, OperationPlan, Request, Response, Result};
+use super::incremental::{take_streams, DeferredPlan, IncrementalPlan};
+use super::{
+    transform, AnyResponse, BuildError, Error, Incremental, InitialPayload, OperationPlan,
+    Positioned, Request, Response, Result, Store, SubsequentPayload, Variables,
+};
 use crate::core::app_context::AppContext;
+use crate::core::blueprint::DynamicValue;
 use crate::core::http::RequestContext;
 use crate::core::ir::model::IR;
 use crate::core::ir::{self, EmptyResolverContext, EvalContext};
@@ -40,6 +47,249 @@ impl ConstValueExecutor {
         req_ctx: &RequestContext,
         request: Request<ConstValue>,
     ) -> AnyResponse<Vec<u8>> {
+        let is_introspection_query =
+            req_ctx.server.get_enable_introspection() && self.plan.is_introspection_query;
+
+        let plan = match self.prepare(req_ctx, &request).await {
+            Ok(plan) => plan,
+            Err(resp) => return resp,
+        };
+
+        // PERF: remove this particular clone?
+        let vars = request.variables.clone();
+        let introspection = if is_introspection_query {
+            let async_req = async_graphql::Request::from(request).only_introspection();
+            Some(app_ctx.execute(async_req).await)
+        } else {
+            None
+        };
+
+        Self::execute_plan(&plan, req_ctx, vars, introspection.as_ref()).await
+    }
+
+    /// Executes a subscription operation. The root field of the subscription
+    /// is evaluated into a stream of values and the rest of the selection is
+    /// resolved for every value produced by that stream.
+    pub fn subscribe(
+        self,
+        req_ctx: Arc<RequestContext>,
+        request: Request<ConstValue>,
+    ) -> BoxStream<'static, AnyResponse<Vec<u8>>> {
+        let source = async move {
+            let plan = match self.prepare(&req_ctx, &request).await {
+                Ok(plan) => plan,
+                Err(resp) => return stream::once(ready(resp)).boxed(),
+            };
+
+            let Some(ir) = plan.selection.first().and_then(|field| field.ir.clone()) else {
+                let resp: Response<ConstValue> = Response::default();
+                return stream::once(ready(
+                    resp.with_errors(vec![GraphQLError::new(
+                        BuildError::SubscriptionSingleRootField.to_string(),
+                        None,
+                    )])
+                    .into(),
+                ))
+                .boxed();
+            };
+
+            let values = {
+                let env = super::context::RequestContext::new(&plan);
+                let ctx = Context::new(&plan.selection[0], &env);
+                let mut eval_ctx = EvalContext::new(&req_ctx, &ctx);
+                ir.eval_stream(&mut eval_ctx).await
+            };
+
+            let values = match values {
+                Ok(values) => values,
+                Err(err) => {
+                    let resp: Response<ConstValue> = Response::default();
+                    return stream::once(ready(
+                        resp.with_errors(vec![Positioned::new(
+                            Error::from(err),
+                            plan.selection[0].pos,
+                        )])
+                        .into(),
+                    ))
+                    .boxed();
+                }
+            };
+
+            let plan = Arc::new(plan);
+            values
+                .then(move |value| {
+                    let plan = plan.clone();
+                    let req_ctx = req_ctx.clone();
+                    let variables = request.variables.clone();
+                    async move {
+                        match value {
+                            Ok(value) => {
+                                // Replace the resolver of the root field with the value
+                                // produced by the source stream, so that the nested fields
+                                // are resolved against it.
+                                let mut plan = plan.as_ref().clone();
+                                plan.selection[0].ir =
+                                    Some(IR::Dynamic(DynamicValue::Value(value)));
+                                Self::execute_plan(&plan, &req_ctx, variables, None).await
+                            }
+                            Err(err) => {
+                                let resp: Response<ConstValue> = Response::default();
+                                resp.with_errors(vec![Positioned::new(
+                                    Error::from(err),
+                                    plan.selection[0].pos,
+                                )])
+                                .into()
+                            }
+                        }
+                    }
+                })
+                .boxed()
+        };
+
+        stream::once(source).flatten().boxed()
+    }
+
+    /// Executes an operation with `@defer` or `@stream`. The initial payload
+    /// is produced without the deferred fragments and the streamed items, that
+    /// follow in subsequent payloads. Deferred fragments are sent as soon as
+    /// they're resolved, after the fragment that contains them if any.
+    ///
+    /// NOTE: the lists marked with `@stream` are resolved entirely before the
+    /// initial payload is sent, only their delivery is split.
+    pub fn execute_incremental(
+        self,
+        app_ctx: Arc<AppContext>,
+        req_ctx: Arc<RequestContext>,
+        request: Request<ConstValue>,
+    ) -> BoxStream<'static, AnyResponse<Vec<u8>>> {
+        let source = async move {
+            let is_introspection_query =
+                req_ctx.server.get_enable_introspection() && self.plan.is_introspection_query;
+
+            let plan = match self.prepare(&req_ctx, &request).await {
+                Ok(plan) => plan,
+                Err(resp) => return stream::once(ready(resp)).boxed(),
+            };
+
+            let variables = request.variables.clone();
+            let plan = IncrementalPlan::new(plan, &variables);
+            let introspection = if is_introspection_query {
+                let async_req = async_graphql::Request::from(request).only_introspection();
+                Some(app_ctx.execute(async_req).await)
+            } else {
+                None
+            };
+
+            let exec = ConstValueExec::new(&plan.initial, &req_ctx);
+            let exe = Executor::new(&plan.initial, exec);
+            let store = exe.store().await;
+            let synth = Synth::new(&plan.initial, store.clone(), variables.clone());
+            let mut response: Response<ConstValue> = exe.execute(&synth).await;
+            if let Some(introspection) = &introspection {
+                response = response.merge_with(introspection);
+            }
+
+            let mut streams = Vec::new();
+            take_streams(
+                &plan.initial.selection,
+                &mut response.data,
+                &mut Vec::new(),
+                &variables,
+                &mut streams,
+            );
+
+            let has_deferred = !plan.deferred.is_empty();
+            let mut payloads: Vec<AnyResponse<Vec<u8>>> =
+                vec![
+                    InitialPayload { response, has_next: has_deferred || !streams.is_empty() }
+                        .into(),
+                ];
+            if !streams.is_empty() {
+                payloads.push(
+                    SubsequentPayload { incremental: streams, has_next: has_deferred }.into(),
+                );
+            }
+
+            // fragments nested in other deferred fragments wait for their parent
+            let (ready_plans, waiting): (Vec<_>, Vec<_>) = plan
+                .deferred
+                .into_iter()
+                .partition(|deferred| deferred.parent.is_none());
+            let running = ready_plans
+                .into_iter()
+                .map(|deferred| {
+                    Self::execute_deferred(
+                        deferred,
+                        req_ctx.clone(),
+                        store.clone(),
+                        variables.clone(),
+                    )
+                })
+                .collect::<FuturesUnordered<_>>();
+
+            let deferred = stream::unfold((running, waiting), move |(mut running, waiting)| {
+                let req_ctx = req_ctx.clone();
+                let variables = variables.clone();
+                async move {
+                    let (id, store, incremental) = running.next().await?;
+
+                    // the nested fragments are resolved on top of the store of
+                    // their parent, that holds the fields on the way to them
+                    let (children, waiting): (Vec<_>, Vec<_>) = waiting
+                        .into_iter()
+                        .partition(|deferred| deferred.parent == Some(id));
+                    for deferred in children {
+                        running.push(Self::execute_deferred(
+                            deferred,
+                            req_ctx.clone(),
+                            store.clone(),
+                            variables.clone(),
+                        ));
+                    }
+
+                    let payload: AnyResponse<Vec<u8>> =
+                        SubsequentPayload { incremental, has_next: !running.is_empty() }.into();
+                    Some((payload, (running, waiting)))
+                }
+            });
+
+            stream::iter(payloads).chain(deferred).boxed()
+        };
+
+        stream::once(source).flatten().boxed()
+    }
+
+    /// Resolves a deferred fragment on top of the store of the payload that
+    /// contains it, so that the fields on the way to the fragment aren't
+    /// resolved again. Returns the store along with the payload, for the
+    /// fragments nested in this one.
+    async fn execute_deferred(
+        deferred: DeferredPlan<ConstValue>,
+        req_ctx: Arc<RequestContext>,
+        store: Store<std::result::Result<ConstValue, Positioned<Error>>>,
+        variables: Variables<ConstValue>,
+    ) -> (
+        usize,
+        Store<std::result::Result<ConstValue, Positioned<Error>>>,
+        Vec<Incremental<ConstValue>>,
+    ) {
+        let exec = ConstValueExec::new(&deferred.plan, &req_ctx);
+        let exe = Executor::new(&deferred.plan, exec);
+        let store = exe.store_with(store).await;
+        let synth = Synth::new(&deferred.plan, store.clone(), variables.clone());
+        let response: Response<ConstValue> = exe.execute(&synth).await;
+
+        let incremental = deferred.incremental(response.data, response.errors, &variables);
+        (deferred.defer.id, store, incremental)
+    }
+
+    /// Runs the `before` chain and resolves variables and skipped fields of
+    /// the plan.
+    async fn prepare(
+        self,
+        req_ctx: &RequestContext,
+        request: &Request<ConstValue>,
+    ) -> std::result::Result<OperationPlan<ConstValue>, AnyResponse<Vec<u8>>> {
         // Run all the IRs in the before chain
         if let Some(ir) = &self.plan.before {
             let mut eval_context = EvalContext::new(req_ctx, &EmptyResolverContext {});
@@ -47,15 +297,13 @@ impl ConstValueExecutor {
                 Ok(_) => (),
                 Err(err) => {
                     let resp: Response<ConstValue> = Response::default();
-                    return resp
+                    return Err(resp
                         .with_errors(vec![GraphQLError::new(err.to_string(), None)])
-                        .into();
+                        .into());
                 }
             }
         }
 
-        let is_introspection_query =
-            req_ctx.server.get_enable_introspection() && self.plan.is_introspection_query;
         let variables = &request.variables;
 
         // Attempt to skip unnecessary fields
@@ -65,9 +313,9 @@ impl ConstValueExecutor {
         else {
             let resp: Response<ConstValue> = Response::default();
             // this shouldn't actually ever happen
-            return resp
+            return Err(resp
                 .with_errors(vec![GraphQLError::new(Error::Unknown.to_string(), None)])
-                .into();
+                .into());
         };
 
         // Attempt to replace variables in the plan with the actual values
@@ -76,33 +324,35 @@ impl ConstValueExecutor {
         // [InputResolver] to resolve defaults properly
         let result = InputResolver::new(plan).resolve_input(variables);
 
-        let plan = match result {
-            Ok(plan) => plan,
+        match result {
+            Ok(plan) => Ok(plan),
             Err(err) => {
                 let resp: Response<ConstValue> = Response::default();
-                return resp
+                Err(resp
                     .with_errors(vec![GraphQLError::new(
                         BuildError::from(err).to_string(),
                         None,
                     )])
-                    .into();
+                    .into())
             }
-        };
+        }
+    }
 
-        let exec = ConstValueExec::new(&plan, req_ctx);
-        // PERF: remove this particular clone?
-        let vars = request.variables.clone();
-        let exe = Executor::new(&plan, exec);
+    async fn execute_plan(
+        plan: &OperationPlan<ConstValue>,
+        req_ctx: &RequestContext,
+        vars: Variables<ConstValue>,
+        introspection: Option<&async_graphql::Response>,
+    ) -> AnyResponse<Vec<u8>> {
+        let exec = ConstValueExec::new(plan, req_ctx);
+        let exe = Executor::new(plan, exec);
         let store = exe.store().await;
-        let synth = Synth::new(&plan, store, vars);
+        let synth = Synth::new(plan, store, vars);
 
         let resp: Response<serde_json_borrow::Value> = exe.execute(&synth).await;
 
-        if is_introspection_query {
-            let async_req = async_graphql::Request::from(request).only_introspection();
-            let async_resp = app_ctx.execute(async_req).await;
-
-            resp.merge_with(&async_resp).into()
+        if let Some(introspection) = introspection {
+            resp.merge_with(introspection).into()
         } else {
             resp.into()
         }
@@ -5,21 +5,27 @@ use std::sync::Arc;
 
 use async_graphql::{BatchRequest, Value};
 use async_graphql_value::{ConstValue, Extensions};
-use futures_util::stream::FuturesOrdered;
+use futures_util::future::ready;
+use futures_util::stream::{self, BoxStream, FuturesOrdered};
 use futures_util::StreamExt;
+use http::HeaderMap;
 use tailcall_hasher::TailcallHasher;
 
 use super::{AnyResponse, BatchResponse, Response};
 use crate::core::app_context::AppContext;
 use crate::core::async_graphql_hyper::OperationId;
 use crate::core::http::RequestContext;
-use crate::core::jit::{self, ConstValueExecutor, OPHash, Pos, Positioned};
+use crate::core::jit::{self, ConstValueExecutor, OPHash, Pos, Positioned, ResponseCache};
+use crate::core::persisted_query;
 
 #[derive(Clone)]
 pub struct JITExecutor {
     app_ctx: Arc<AppContext>,
     req_ctx: Arc<RequestContext>,
     operation_id: OperationId,
+    // Hash of the headers the cached responses vary with, known for the
+    // requests whose responses can be cached.
+    vary: Option<u64>,
 }
 
 impl JITExecutor {
@@ -28,7 +34,20 @@ impl JITExecutor {
         req_ctx: Arc<RequestContext>,
         operation_id: OperationId,
     ) -> Self {
-        Self { app_ctx, req_ctx, operation_id }
+        Self { app_ctx, req_ctx, operation_id, vary: None }
+    }
+
+    /// Enables the response cache, if configured, for a request made with the
+    /// headers
+    pub fn with_headers(mut self, headers: &HeaderMap) -> Self {
+        self.vary = self
+            .app_ctx
+            .blueprint
+            .server
+            .response_cache
+            .as_ref()
+            .map(|config| ResponseCache::vary(headers, &self.req_ctx, config));
+        self
     }
 
     #[inline(always)]
@@ -66,8 +85,31 @@ impl JITExecutor {
         let mut hasher = TailcallHasher::default();
         request.query.hash(&mut hasher);
 
+        // trusted documents can be referenced without sending the document
+        if request.query.is_empty() {
+            request.operation_name.hash(&mut hasher);
+            persisted_query::hash(&request.extensions.0).hash(&mut hasher);
+        }
+
         OPHash::new(hasher.finish())
     }
+
+    #[inline(always)]
+    fn executor(
+        &self,
+        hash: &OPHash,
+        jit_request: &jit::Request<ConstValue>,
+    ) -> jit::Result<ConstValueExecutor> {
+        if let Some(op) = self.app_ctx.operation_plans.get(hash) {
+            Ok(ConstValueExecutor::from(op.value().clone()))
+        } else {
+            let exec = ConstValueExecutor::try_new(jit_request, &self.app_ctx)?;
+            self.app_ctx
+                .operation_plans
+                .insert(hash.clone(), exec.plan.clone());
+            Ok(exec)
+        }
+    }
 }
 
 impl JITExecutor {
@@ -82,26 +124,34 @@ impl JITExecutor {
                 return response.clone();
             }
 
+            let response_key = self
+                .vary
+                .map(|vary| ResponseCache::key(&hash, &request, vary));
+            if let Some(key) = &response_key {
+                if let Some(response) = self.app_ctx.response_cache.get(key, &self.req_ctx).await {
+                    return response;
+                }
+            }
+
             let jit_request = jit::Request::from(request);
-            let exec = if let Some(op) = self.app_ctx.operation_plans.get(&hash) {
-                ConstValueExecutor::from(op.value().clone())
-            } else {
-                let exec = match ConstValueExecutor::try_new(&jit_request, &self.app_ctx) {
-                    Ok(exec) => exec,
-                    Err(error) => {
-                        return Response::<async_graphql::Value>::default()
-                            .with_errors(vec![Positioned::new(error, Pos::default())])
-                            .into()
-                    }
-                };
-                self.app_ctx
-                    .operation_plans
-                    .insert(hash.clone(), exec.plan.clone());
-                exec
+            let exec = match self.executor(&hash, &jit_request) {
+                Ok(exec) => exec,
+                Err(error) => {
+                    return Response::<async_graphql::Value>::default()
+                        .with_errors(vec![Positioned::new(error, Pos::default())])
+                        .into()
+                }
             };
 
             let is_const = exec.plan.is_const;
             let is_protected = exec.plan.is_protected;
+            // the responses of the operations that authenticate the requests
+            // aren't cached, since cached responses are served without
+            // authenticating them
+            let cache_ttl = exec
+                .plan
+                .min_cache_ttl
+                .filter(|_| exec.plan.is_query() && exec.plan.before.is_none());
 
             let response = if exec.plan.can_dedupe() {
                 self.dedupe_and_exec(exec, jit_request).await
@@ -114,12 +164,60 @@ impl JITExecutor {
                 self.app_ctx
                     .const_execution_cache
                     .insert(hash, response.clone());
+            } else if let (Some(key), Some(ttl)) = (response_key, cache_ttl) {
+                self.app_ctx
+                    .response_cache
+                    .set(key, &response, ttl, &self.req_ctx)
+                    .await;
             }
 
             response
         }
     }
 
+    /// Execute a GraphQL subscription. A response is produced for every event
+    /// emitted by the root field of the subscription.
+    pub fn subscribe(
+        &self,
+        request: async_graphql::Request,
+    ) -> BoxStream<'static, AnyResponse<Vec<u8>>> {
+        let hash = Self::req_hash(&request);
+        let jit_request = jit::Request::from(request);
+
+        match self.executor(&hash, &jit_request) {
+            Ok(exec) => exec.subscribe(self.req_ctx.clone(), jit_request),
+            Err(error) => {
+                let response: AnyResponse<Vec<u8>> = Response::<async_graphql::Value>::default()
+                    .with_errors(vec![Positioned::new(error, Pos::default())])
+                    .into();
+                stream::once(ready(response)).boxed()
+            }
+        }
+    }
+
+    /// Execute a GraphQL operation with `@defer` or `@stream`. The initial
+    /// payload is followed by the payloads of the deferred fragments and of the
+    /// streamed lists.
+    pub fn execute_incremental(
+        &self,
+        request: async_graphql::Request,
+    ) -> BoxStream<'static, AnyResponse<Vec<u8>>> {
+        let hash = Self::req_hash(&request);
+        let jit_request = jit::Request::from(request);
+
+        match self.executor(&hash, &jit_request) {
+            Ok(exec) => {
+                exec.execute_incremental(self.app_ctx.clone(), self.req_ctx.clone(), jit_request)
+            }
+            Err(error) => {
+                let response: AnyResponse<Vec<u8>> = Response::<async_graphql::Value>::default()
+                    .with_errors(vec![Positioned::new(error, Pos::default())])
+                    .into();
+                stream::once(ready(response)).boxed()
+            }
+        }
+    }
+
     /// Execute a GraphQL batch query.
     pub async fn execute_batch(&self, batch_request: BatchRequest) -> BatchResponse<Vec<u8>> {
         match batch_request {
@@ -0,0 +1,445 @@
+use std::borrow::Cow;
+
+use async_graphql_value::ConstValue;
+
+use super::graphql_error::GraphQLError;
+use super::{Defer, Field, Incremental, OperationPlan, PathSegment, Variables};
+
+/// A fragment marked with `@defer` and the plan that resolves it
+#[derive(Clone, Debug)]
+pub struct DeferredPlan<Input> {
+    pub defer: Defer,
+    /// Id of the deferred fragment that contains the fields on the way to this
+    /// one. It has to be delivered first.
+    pub parent: Option<usize>,
+    /// Output names of the fields on the way to the fragment
+    pub path: Vec<String>,
+    /// Contains only the fields on the way to the fragment and the fields of
+    /// the fragment
+    pub plan: OperationPlan<Input>,
+}
+
+/// Splits a plan into the plan of the initial payload, without the fields of
+/// the active deferred fragments, and a plan for every deferred fragment.
+pub struct IncrementalPlan<Input> {
+    pub initial: OperationPlan<Input>,
+    pub deferred: Vec<DeferredPlan<Input>>,
+}
+
+impl IncrementalPlan<ConstValue> {
+    pub fn new(plan: OperationPlan<ConstValue>, variables: &Variables<ConstValue>) -> Self {
+        let mut groups = Vec::new();
+        collect_deferred(
+            &plan.selection,
+            variables,
+            None,
+            &mut Vec::new(),
+            &mut groups,
+        );
+
+        let deferred = groups
+            .into_iter()
+            .map(|(defer, parent, path)| {
+                let selection = select_deferred(&plan.selection, defer.id, &path, variables);
+                DeferredPlan {
+                    plan: OperationPlan { selection, ..plan.clone() },
+                    defer,
+                    parent,
+                    path,
+                }
+            })
+            .collect();
+
+        let selection = without_deferred(&plan.selection, variables);
+        Self { initial: OperationPlan { selection, ..plan }, deferred }
+    }
+}
+
+impl DeferredPlan<ConstValue> {
+    /// Builds the results of the fragment from the data resolved by its plan.
+    /// When the fragment is nested in lists there is a result for every item.
+    pub fn incremental(
+        &self,
+        data: ConstValue,
+        errors: Vec<GraphQLError>,
+        variables: &Variables<ConstValue>,
+    ) -> Vec<Incremental<ConstValue>> {
+        let mut found = Vec::new();
+        extract(
+            &self.plan.selection,
+            data,
+            &self.path,
+            &mut Vec::new(),
+            &mut found,
+        );
+
+        let mut incremental = Vec::with_capacity(found.len());
+        let mut streams = Vec::new();
+        // the errors are reported once, with the first result
+        let mut errors = Some(errors);
+
+        for (mut path, mut data, fields) in found {
+            take_streams(fields, &mut data, &mut path, variables, &mut streams);
+            incremental.push(Incremental {
+                data: Some(data),
+                items: None,
+                path,
+                label: self.defer.label.clone(),
+                errors: errors.take().unwrap_or_default(),
+            });
+        }
+
+        if let Some(errors) = errors.filter(|errors| !errors.is_empty()) {
+            incremental.push(Incremental {
+                data: Some(ConstValue::Null),
+                items: None,
+                path: self
+                    .path
+                    .iter()
+                    .map(|name| PathSegment::Field(Cow::Owned(name.clone())))
+                    .collect(),
+                label: self.defer.label.clone(),
+                errors,
+            });
+        }
+
+        incremental.extend(streams);
+        incremental
+    }
+}
+
+fn active_defer<'a>(
+    field: &'a Field<ConstValue>,
+    variables: &Variables<ConstValue>,
+) -> Option<&'a Defer> {
+    field
+        .defer
+        .as_ref()
+        .filter(|defer| defer.is_active(variables))
+}
+
+/// Removes the fields of the active deferred fragments
+fn without_deferred(
+    fields: &[Field<ConstValue>],
+    variables: &Variables<ConstValue>,
+) -> Vec<Field<ConstValue>> {
+    fields
+        .iter()
+        .filter(|field| active_defer(field, variables).is_none())
+        .map(|field| Field {
+            selection: without_deferred(&field.selection, variables),
+            ..field.clone()
+        })
+        .collect()
+}
+
+/// Collects the active deferred fragments with the fragment that contains
+/// them and their path
+fn collect_deferred(
+    fields: &[Field<ConstValue>],
+    variables: &Variables<ConstValue>,
+    parent: Option<usize>,
+    path: &mut Vec<String>,
+    groups: &mut Vec<(Defer, Option<usize>, Vec<String>)>,
+) {
+    for field in fields {
+        let mut parent = parent;
+        if let Some(defer) = active_defer(field, variables) {
+            if !groups.iter().any(|(group, ..)| group.id == defer.id) {
+                groups.push((defer.clone(), parent, path.clone()));
+            }
+            parent = Some(defer.id);
+        }
+
+        path.push(field.output_name.clone());
+        collect_deferred(&field.selection, variables, parent, path, groups);
+        path.pop();
+    }
+}
+
+/// Keeps the fields on the way to a deferred fragment and the fields of the
+/// fragment, without the fragments deferred inside of it
+fn select_deferred(
+    fields: &[Field<ConstValue>],
+    id: usize,
+    path: &[String],
+    variables: &Variables<ConstValue>,
+) -> Vec<Field<ConstValue>> {
+    match path.split_first() {
+        None => fields
+            .iter()
+            .filter(|field| field.defer.as_ref().is_some_and(|defer| defer.id == id))
+            .map(|field| Field {
+                selection: without_deferred(&field.selection, variables),
+                ..field.clone()
+            })
+            .collect(),
+        Some((name, rest)) => fields
+            .iter()
+            .filter(|field| &field.output_name == name)
+            .filter_map(|field| {
+                let selection = select_deferred(&field.selection, id, rest, variables);
+                (!selection.is_empty()).then(|| Field { selection, ..field.clone() })
+            })
+            .collect(),
+    }
+}
+
+/// Finds the objects at the path of a deferred fragment, the lists on the way
+/// are expanded into every item
+fn extract<'a>(
+    fields: &'a [Field<ConstValue>],
+    value: ConstValue,
+    path: &[String],
+    current: &mut Vec<PathSegment<'static>>,
+    found: &mut Vec<(
+        Vec<PathSegment<'static>>,
+        ConstValue,
+        &'a [Field<ConstValue>],
+    )>,
+) {
+    match value {
+        ConstValue::List(items) => {
+            for (index, item) in items.into_iter().enumerate() {
+                current.push(PathSegment::Index(index));
+                extract(fields, item, path, current, found);
+                current.pop();
+            }
+        }
+        ConstValue::Object(mut object) => match path.split_first() {
+            None => {
+                // objects that don't match the type condition of the fragment are
+                // empty
+                if !object.is_empty() {
+                    found.push((current.clone(), ConstValue::Object(object), fields));
+                }
+            }
+            Some((name, rest)) => {
+                let field = fields.iter().find(|field| &field.output_name == name);
+                let value = object.shift_remove(name.as_str());
+                if let (Some(field), Some(value)) = (field, value) {
+                    current.push(PathSegment::Field(Cow::Owned(name.clone())));
+                    extract(&field.selection, value, rest, current, found);
+                    current.pop();
+                }
+            }
+        },
+        _ => {}
+    }
+}
+
+/// Moves the items past `initialCount` of the active streamed lists out of the
+/// value, into results that are sent after the payload of the value.
+pub fn take_streams(
+    fields: &[Field<ConstValue>],
+    value: &mut ConstValue,
+    path: &mut Vec<PathSegment<'static>>,
+    variables: &Variables<ConstValue>,
+    streams: &mut Vec<Incremental<ConstValue>>,
+) {
+    match value {
+        ConstValue::List(items) => {
+            for (index, item) in items.iter_mut().enumerate() {
+                path.push(PathSegment::Index(index));
+                take_streams(fields, item, path, variables, streams);
+                path.pop();
+            }
+        }
+        ConstValue::Object(object) => {
+            for field in fields {
+                let Some(value) = object.get_mut(field.output_name.as_str()) else {
+                    continue;
+                };
+                path.push(PathSegment::Field(Cow::Owned(field.output_name.clone())));
+
+                let stream = field
+                    .stream
+                    .as_ref()
+                    .filter(|stream| stream.is_active(variables));
+                let items = match (stream, &mut *value) {
+                    (Some(stream), ConstValue::List(items))
+                        if items.len() > stream.initial_count =>
+                    {
+                        Some((stream, items.split_off(stream.initial_count)))
+                    }
+                    _ => None,
+                };
+
+                // the items that are streamed are sent as they are
+                take_streams(&field.selection, value, path, variables, streams);
+
+                if let Some((stream, items)) = items {
+                    let mut path = path.clone();
+                    path.push(PathSegment::Index(stream.initial_count));
+                    streams.push(Incremental {
+                        data: None,
+                        items: Some(items),
+                        path,
+                        label: stream.label.clone(),
+                        errors: Vec::new(),
+                    });
+                }
+
+                path.pop();
+            }
+        }
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use async_graphql::Request;
+    use serde_json::json;
+
+    use super::*;
+    use crate::core::blueprint::Blueprint;
+    use crate::core::config::ConfigModule;
+    use crate::core::jit;
+    use crate::core::jit::transform::InputResolver;
+    use crate::include_config;
+
+    fn plan(query: &str, variables: &Variables<ConstValue>) -> IncrementalPlan<ConstValue> {
+        let config = include_config!("./fixtures/jsonplaceholder-mutation.graphql").unwrap();
+        let module = ConfigModule::from(config);
+        let bp = Blueprint::try_from(&module).unwrap();
+
+        let request = Request::new(query);
+        let jit_request = jit::Request::from(request);
+        let plan = jit_request.create_plan(&bp).unwrap();
+        let plan = InputResolver::new(plan).resolve_input(variables).unwrap();
+
+        IncrementalPlan::new(plan, variables)
+    }
+
+    fn names(fields: &[Field<ConstValue>]) -> Vec<String> {
+        fields
+            .iter()
+            .map(|field| {
+                if field.selection.is_empty() {
+                    field.output_name.clone()
+                } else {
+                    format!(
+                        "{} {{ {} }}",
+                        field.output_name,
+                        names(&field.selection).join(" ")
+                    )
+                }
+            })
+            .collect()
+    }
+
+    fn value(value: serde_json::Value) -> ConstValue {
+        ConstValue::from_json(value).unwrap()
+    }
+
+    #[test]
+    fn test_split() {
+        let plan = plan(
+            r#"
+            query {
+                posts {
+                    id
+                    ... @defer(label: "user") {
+                        title
+                        user {
+                            id
+                            ... @defer(label: "name") { name }
+                        }
+                    }
+                }
+            }
+            "#,
+            &Variables::new(),
+        );
+
+        assert_eq!(names(&plan.initial.selection), vec!["posts { id }"]);
+        assert_eq!(plan.deferred.len(), 2);
+
+        let user = &plan.deferred[0];
+        assert_eq!(user.defer.label.as_deref(), Some("user"));
+        assert_eq!(user.parent, None);
+        assert_eq!(user.path, vec!["posts"]);
+        assert_eq!(
+            names(&user.plan.selection),
+            vec!["posts { title user { id } }"]
+        );
+
+        let name = &plan.deferred[1];
+        assert_eq!(name.defer.label.as_deref(), Some("name"));
+        assert_eq!(name.parent, Some(user.defer.id));
+        assert_eq!(name.path, vec!["posts", "user"]);
+        assert_eq!(names(&name.plan.selection), vec!["posts { user { name } }"]);
+    }
+
+    #[test]
+    fn test_split_inactive() {
+        let variables = Variables::from_iter([("defer".to_string(), ConstValue::Boolean(false))]);
+        let plan = plan(
+            r#"
+            query($defer: Boolean) {
+                posts { id ... @defer(if: $defer) { title } }
+            }
+            "#,
+            &variables,
+        );
+
+        assert_eq!(names(&plan.initial.selection), vec!["posts { id title }"]);
+        assert!(plan.deferred.is_empty());
+    }
+
+    #[test]
+    fn test_deferred_incremental() {
+        let variables = Variables::new();
+        let plan = plan(
+            r#"
+            query {
+                posts { id ... @defer(label: "title") { title } }
+            }
+            "#,
+            &variables,
+        );
+
+        let data = value(json!({ "posts": [{ "title": "a" }, { "title": "b" }] }));
+        let incremental = plan.deferred[0].incremental(data, vec![], &variables);
+
+        assert_eq!(
+            serde_json::to_value(incremental).unwrap(),
+            json!([
+                { "data": { "title": "a" }, "path": ["posts", 0], "label": "title" },
+                { "data": { "title": "b" }, "path": ["posts", 1], "label": "title" }
+            ])
+        );
+    }
+
+    #[test]
+    fn test_take_streams() {
+        let variables = Variables::new();
+        let plan = plan(
+            r#"
+            query {
+                posts @stream(initialCount: 1, label: "posts") { id }
+            }
+            "#,
+            &variables,
+        );
+
+        let mut data = value(json!({ "posts": [{ "id": 1 }, { "id": 2 }, { "id": 3 }] }));
+        let mut streams = Vec::new();
+        take_streams(
+            &plan.initial.selection,
+            &mut data,
+            &mut Vec::new(),
+            &variables,
+            &mut streams,
+        );
+
+        assert_eq!(data, value(json!({ "posts": [{ "id": 1 }] })));
+        assert_eq!(
+            serde_json::to_value(streams).unwrap(),
+            json!([
+                { "items": [{ "id": 2 }, { "id": 3 }], "path": ["posts", 1], "label": "posts" }
+            ])
+        );
+    }
+}
@@ -10,8 +10,10 @@ use store::*;
 mod context;
 mod error;
 mod exec_const;
+mod incremental;
 mod request;
 mod response;
+mod response_cache;
 
 // NOTE: Only used in tests and benchmarks
 mod builder;
@@ -25,3 +27,4 @@ pub use graphql_executor::*;
 pub use model::*;
 pub use request::*;
 pub use response::*;
+pub use response_cache::*;
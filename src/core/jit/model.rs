@@ -167,6 +167,58 @@ pub struct Field<Input> {
     pub directives: Vec<Directive<Input>>,
     pub is_enum: bool,
     pub scalar: Option<Scalar>,
+    /// Set when the field is part of a fragment marked with `@defer`
+    pub defer: Option<Defer>,
+    /// Set when the field is a list marked with `@stream`
+    pub stream: Option<Stream>,
+}
+
+/// A fragment marked with `@defer`. Its fields are left out of the initial
+/// payload and sent in a subsequent one.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Defer {
+    /// Identifies the fragment in the operation, fragments nested in other
+    /// deferred fragments have a greater id
+    pub id: usize,
+    pub label: Option<String>,
+    /// Variable of the `if` argument, the fragment isn't deferred when it's
+    /// false
+    pub condition: Option<Variable>,
+}
+
+/// A list marked with `@stream`. Only the first `initial_count` items are part
+/// of the payload that contains the list, the others are sent in a subsequent
+/// payload.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Stream {
+    pub label: Option<String>,
+    pub initial_count: usize,
+    /// Variable of the `if` argument, the list isn't streamed when it's false
+    pub condition: Option<Variable>,
+}
+
+/// Reads the variable of the `if` argument of `@defer` and `@stream`, which
+/// is true by default
+fn is_active<'json, Value: JsonLike<'json>>(
+    condition: Option<&Variable>,
+    variables: &Variables<Value>,
+) -> bool {
+    condition
+        .and_then(|condition| variables.get(condition.as_str()))
+        .and_then(|value| value.as_bool())
+        .unwrap_or(true)
+}
+
+impl Defer {
+    pub fn is_active<'json, Value: JsonLike<'json>>(&self, variables: &Variables<Value>) -> bool {
+        is_active(self.condition.as_ref(), variables)
+    }
+}
+
+impl Stream {
+    pub fn is_active<'json, Value: JsonLike<'json>>(&self, variables: &Variables<Value>) -> bool {
+        is_active(self.condition.as_ref(), variables)
+    }
 }
 
 pub struct DFS<'a, Input> {
@@ -236,6 +288,8 @@ impl<Input> Field<Input> {
                 .collect::<Result<_, _>>()?,
             is_enum: self.is_enum,
             scalar: self.scalar,
+            defer: self.defer,
+            stream: self.stream,
         })
     }
 }
@@ -264,6 +318,12 @@ impl<Input: Debug> Debug for Field<Input> {
             debug_struct.field("include", &self.include);
         }
         debug_struct.field("directives", &self.directives);
+        if self.defer.is_some() {
+            debug_struct.field("defer", &self.defer);
+        }
+        if self.stream.is_some() {
+            debug_struct.field("stream", &self.stream);
+        }
 
         debug_struct.finish()
     }
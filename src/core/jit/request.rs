@@ -8,7 +8,7 @@ use tailcall_valid::Validator;
 use super::{transform, Builder, OperationPlan, Result, Variables};
 use crate::core::blueprint::Blueprint;
 use crate::core::transform::TransformerOps;
-use crate::core::Transform;
+use crate::core::{persisted_query, Transform};
 
 #[derive(Debug, Deserialize, Clone)]
 pub struct Request<V> {
@@ -41,11 +41,22 @@ impl Request<ConstValue> {
         &self,
         blueprint: &Blueprint,
     ) -> Result<OperationPlan<async_graphql_value::Value>> {
-        let doc = async_graphql::parser::parse_query(&self.query)?;
+        let query = match &blueprint.server.trusted_documents {
+            Some(trusted_documents) => trusted_documents
+                .resolve(
+                    &self.query,
+                    self.operation_name.as_deref(),
+                    persisted_query::hash(&self.extensions),
+                )
+                .ok_or(super::Error::UntrustedDocument)?,
+            None => self.query.as_str(),
+        };
+
+        let doc = async_graphql::parser::parse_query(query)?;
         let builder = Builder::new(blueprint, doc);
         let plan = builder.build(self.operation_name.as_deref())?;
 
-        transform::CheckConst::new()
+        let plan = transform::CheckConst::new()
             .pipe(transform::CheckProtected::new())
             .pipe(transform::AuthPlanner::new())
             .pipe(transform::CheckDedupe::new())
@@ -55,7 +66,15 @@ impl Request<ConstValue> {
             // both transformers are infallible right now
             // but we can't just unwrap this in stable rust
             // so convert to the Unknown error
-            .map_err(|_| super::Error::Unknown)
+            .map_err(|_| super::Error::Unknown)?;
+
+        let limits = &blueprint.server.query_limits;
+        transform::CheckLimits::new(limits)
+            .when(!limits.is_empty())
+            .transform(plan)
+            .to_result()
+            // report the first limit that was exceeded
+            .map_err(|errors| super::Error::from(errors.as_vec()[0].message.clone()))
     }
 }
 
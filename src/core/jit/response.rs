@@ -4,7 +4,7 @@ use derive_setters::Setters;
 use serde::Serialize;
 
 use super::graphql_error::GraphQLError;
-use super::Positioned;
+use super::{PathSegment, Positioned};
 use crate::core::async_graphql_hyper::CacheControl;
 use crate::core::jit;
 use crate::core::json::{JsonLike, JsonObjectLike};
@@ -134,6 +134,64 @@ impl<V: Serialize> From<Response<V>> for AnyResponse<Vec<u8>> {
     }
 }
 
+/// The first payload of an operation with `@defer` or `@stream`, `has_next`
+/// tells the client if more payloads follow
+#[derive(Clone, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct InitialPayload<Value> {
+    #[serde(flatten)]
+    pub response: Response<Value>,
+    pub has_next: bool,
+}
+
+/// A payload that follows the initial one, with the data of deferred
+/// fragments and the remaining items of streamed lists
+#[derive(Clone, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct SubsequentPayload<Value> {
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub incremental: Vec<Incremental<Value>>,
+    pub has_next: bool,
+}
+
+/// Result of a deferred fragment, with `data`, or of a streamed list, with
+/// `items`, that is delivered at `path`
+#[derive(Clone, Serialize, Debug)]
+pub struct Incremental<Value> {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub data: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub items: Option<Vec<Value>>,
+    pub path: Vec<PathSegment<'static>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub errors: Vec<GraphQLError>,
+}
+
+impl<V: Serialize> From<InitialPayload<V>> for AnyResponse<Vec<u8>> {
+    fn from(payload: InitialPayload<V>) -> Self {
+        Self {
+            cache_control: payload.response.cache_control.clone(),
+            is_ok: payload.response.errors.is_empty(),
+            body: Arc::new(serde_json::to_vec(&payload).unwrap_or_default()),
+        }
+    }
+}
+
+impl<V: Serialize> From<SubsequentPayload<V>> for AnyResponse<Vec<u8>> {
+    fn from(payload: SubsequentPayload<V>) -> Self {
+        Self {
+            cache_control: Default::default(),
+            is_ok: payload
+                .incremental
+                .iter()
+                .all(|incremental| incremental.errors.is_empty()),
+            body: Arc::new(serde_json::to_vec(&payload).unwrap_or_default()),
+        }
+    }
+}
+
 pub enum BatchResponse<Body> {
     Single(AnyResponse<Body>),
     Batch(Vec<AnyResponse<Body>>),
@@ -295,4 +353,42 @@ mod test {
         let merged_resp = resp2.merge_with(&resp1);
         insta::assert_json_snapshot!(merged_resp);
     }
+
+    #[test]
+    fn test_incremental_payloads() {
+        use std::borrow::Cow;
+
+        use super::{Incremental, InitialPayload, SubsequentPayload};
+        use crate::core::jit::PathSegment;
+
+        let data = ConstValue::from_json(serde_json::json!({ "product": { "id": 1 } })).unwrap();
+        let initial = InitialPayload { response: Response::new(Ok(data)), has_next: true };
+
+        assert_eq!(
+            serde_json::to_value(&initial).unwrap(),
+            serde_json::json!({ "data": { "product": { "id": 1 } }, "hasNext": true })
+        );
+
+        let reviews = ConstValue::from_json(serde_json::json!({ "reviews": [] })).unwrap();
+        let subsequent = SubsequentPayload {
+            incremental: vec![Incremental {
+                data: Some(reviews),
+                items: None,
+                path: vec![PathSegment::Field(Cow::Owned("product".to_string()))],
+                label: Some("reviews".to_string()),
+                errors: vec![],
+            }],
+            has_next: false,
+        };
+
+        assert_eq!(
+            serde_json::to_value(&subsequent).unwrap(),
+            serde_json::json!({
+                "incremental": [
+                    { "data": { "reviews": [] }, "path": ["product"], "label": "reviews" }
+                ],
+                "hasNext": false
+            })
+        );
+    }
 }
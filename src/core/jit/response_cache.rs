@@ -0,0 +1,270 @@
+use std::hash::{Hash, Hasher};
+use std::num::NonZeroU64;
+
+use async_graphql_value::ConstValue;
+use http::HeaderMap;
+use tailcall_hasher::TailcallHasher;
+
+use super::{AnyResponse, OPHash};
+use crate::core::cache::InMemoryCache;
+use crate::core::http::RequestContext;
+use crate::core::{config, Cache};
+
+/// Hashes a value the same way whatever the order of the fields of its
+/// objects
+fn hash_normalized<H: Hasher>(value: &ConstValue, state: &mut H) {
+    match value {
+        ConstValue::Object(object) => {
+            let mut fields = object.iter().collect::<Vec<_>>();
+            fields.sort_by(|(a, _), (b, _)| a.cmp(b));
+            for (name, value) in fields {
+                name.hash(state);
+                hash_normalized(value, state);
+            }
+        }
+        ConstValue::List(list) => {
+            list.len().hash(state);
+            for value in list {
+                hash_normalized(value, state);
+            }
+        }
+        value => value.to_string().hash(state),
+    }
+}
+
+/// A cached response, along with the smallest max-age of the upstream
+/// responses it was built from
+#[derive(Clone)]
+struct CachedResponse {
+    response: AnyResponse<Vec<u8>>,
+    max_age: Option<i32>,
+}
+
+/// Caches the responses of whole queries, so that the popular ones are
+/// answered without planning them nor making any IO.
+pub struct ResponseCache {
+    cache: InMemoryCache<OPHash, CachedResponse>,
+}
+
+impl Default for ResponseCache {
+    fn default() -> Self {
+        Self { cache: InMemoryCache::new(10000) }
+    }
+}
+
+impl ResponseCache {
+    /// Hashes the values the cached responses vary with: the configured
+    /// headers, the headers forwarded to the upstreams, the API key of the
+    /// client and its certificate
+    pub fn vary(
+        headers: &HeaderMap,
+        req_ctx: &RequestContext,
+        config: &config::ResponseCache,
+    ) -> u64 {
+        let mut hasher = TailcallHasher::default();
+        for name in &config.vary_headers {
+            name.to_lowercase().hash(&mut hasher);
+            for value in headers.get_all(name.as_str()) {
+                value.as_bytes().hash(&mut hasher);
+            }
+        }
+
+        for headers in [&req_ctx.allowed_headers, &req_ctx.auth_headers] {
+            let mut forwarded = headers.iter().collect::<Vec<_>>();
+            forwarded.sort_by(|(a, _), (b, _)| a.as_str().cmp(b.as_str()));
+            for (name, value) in forwarded {
+                name.as_str().hash(&mut hasher);
+                value.as_bytes().hash(&mut hasher);
+            }
+        }
+        let params = req_ctx.query.as_deref().unwrap_or_default();
+        for (name, value) in url::form_urlencoded::parse(params.as_bytes()) {
+            if req_ctx.server.auth_params.contains(name.as_ref()) {
+                name.hash(&mut hasher);
+                value.hash(&mut hasher);
+            }
+        }
+        req_ctx
+            .client_cert
+            .as_ref()
+            .map(|cert| &cert.subject)
+            .hash(&mut hasher);
+
+        hasher.finish()
+    }
+
+    /// The key of the response of an operation, derived from its document,
+    /// its name, its variables and the headers it varies with
+    pub fn key(hash: &OPHash, request: &async_graphql::Request, vary: u64) -> OPHash {
+        let mut hasher = TailcallHasher::default();
+        hash.hash(&mut hasher);
+        request.operation_name.hash(&mut hasher);
+        for (name, value) in request.variables.iter() {
+            name.hash(&mut hasher);
+            hash_normalized(value, &mut hasher);
+        }
+        vary.hash(&mut hasher);
+        OPHash::new(hasher.finish())
+    }
+
+    /// Returns a cached response. The max-age of the upstream responses is
+    /// replayed on the request, less the age of the cached response.
+    pub async fn get(
+        &self,
+        key: &OPHash,
+        req_ctx: &RequestContext,
+    ) -> Option<AnyResponse<Vec<u8>>> {
+        let (cached, age) = self.cache.get_entry(key).await.ok()??;
+        if let Some(max_age) = cached.max_age {
+            let age = i32::try_from(age / 1000).unwrap_or(i32::MAX);
+            req_ctx.set_min_max_age(max_age.saturating_sub(age).max(0));
+        }
+        Some(cached.response)
+    }
+
+    /// Caches the response of a query for `ttl`, unless it failed or is
+    /// private
+    pub async fn set(
+        &self,
+        key: OPHash,
+        response: &AnyResponse<Vec<u8>>,
+        ttl: NonZeroU64,
+        req_ctx: &RequestContext,
+    ) {
+        if !response.is_ok || req_ctx.is_cache_public() == Some(false) {
+            return;
+        }
+
+        let cached = CachedResponse {
+            response: response.clone(),
+            max_age: req_ctx.get_min_max_age(),
+        };
+        // the cache is in memory, so it can't fail
+        let _ = self.cache.set(key, cached, ttl).await;
+    }
+
+    pub fn hit_rate(&self) -> Option<f64> {
+        self.cache.hit_rate()
+    }
+
+    pub async fn clear(&self) {
+        // the cache is in memory, so it can't fail
+        let _ = self.cache.clear().await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use serde_json::json;
+
+    use super::*;
+
+    fn request(variables: serde_json::Value) -> async_graphql::Request {
+        async_graphql::Request::new("query ($filter: Filter) { posts(filter: $filter) { id } }")
+            .variables(async_graphql::Variables::from_json(variables))
+    }
+
+    fn key(variables: serde_json::Value) -> OPHash {
+        ResponseCache::key(&OPHash::new(1), &request(variables), 0)
+    }
+
+    fn response() -> AnyResponse<Vec<u8>> {
+        AnyResponse {
+            body: Arc::new(b"{\"data\":{}}".to_vec()),
+            is_ok: true,
+            ..Default::default()
+        }
+    }
+
+    fn ttl() -> NonZeroU64 {
+        NonZeroU64::new(60000).unwrap()
+    }
+
+    #[test]
+    fn test_key_normalizes_variables() {
+        assert_eq!(
+            key(json!({"filter": {"user": 1, "tag": "rust"}})),
+            key(json!({"filter": {"tag": "rust", "user": 1}}))
+        );
+        assert_ne!(
+            key(json!({"filter": {"user": 1}})),
+            key(json!({"filter": {"user": 2}}))
+        );
+    }
+
+    fn headers(tenant: &str, token: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert("x-tenant", tenant.parse().unwrap());
+        headers.insert("authorization", token.parse().unwrap());
+        headers
+    }
+
+    #[test]
+    fn test_vary() {
+        let config = config::ResponseCache { vary_headers: vec!["X-Tenant".to_string()] };
+        let req_ctx = RequestContext::default();
+        let vary = |tenant: &str, token: &str| {
+            ResponseCache::vary(&headers(tenant, token), &req_ctx, &config)
+        };
+
+        assert_eq!(vary("acme", "Bearer a"), vary("acme", "Bearer b"));
+        assert_ne!(vary("acme", "Bearer a"), vary("globex", "Bearer a"));
+    }
+
+    #[test]
+    fn test_vary_forwarded_headers() {
+        let config = config::ResponseCache::default();
+        let vary = |token: &str| {
+            let headers = headers("acme", token);
+            let req_ctx = RequestContext::default().allowed_headers(headers.clone());
+            ResponseCache::vary(&headers, &req_ctx, &config)
+        };
+
+        assert_ne!(vary("Bearer a"), vary("Bearer b"));
+    }
+
+    #[test]
+    fn test_vary_api_key() {
+        let config = config::ResponseCache::default();
+        let mut server = crate::core::blueprint::Server::default();
+        server.auth_params.insert("api_key".to_string());
+        let vary = |query: &str| {
+            let req_ctx = RequestContext::default()
+                .server(server.clone())
+                .query(Some(query.to_string()));
+            ResponseCache::vary(&HeaderMap::new(), &req_ctx, &config)
+        };
+
+        assert_ne!(vary("api_key=a"), vary("api_key=b"));
+        assert_eq!(vary("api_key=a&page=1"), vary("api_key=a&page=2"));
+    }
+
+    #[tokio::test]
+    async fn test_set_get() {
+        let cache = ResponseCache::default();
+        let req_ctx = RequestContext::default();
+        req_ctx.set_min_max_age(60);
+        let key = key(json!({}));
+
+        cache.set(key.clone(), &response(), ttl(), &req_ctx).await;
+
+        let req_ctx = RequestContext::default();
+        let actual = cache.get(&key, &req_ctx).await.unwrap();
+        assert_eq!(actual.body, response().body);
+        assert_eq!(req_ctx.get_min_max_age(), Some(60));
+    }
+
+    #[tokio::test]
+    async fn test_private_response() {
+        let cache = ResponseCache::default();
+        let req_ctx = RequestContext::default();
+        req_ctx.set_cache_public_false();
+        let key = key(json!({}));
+
+        cache.set(key.clone(), &response(), ttl(), &req_ctx).await;
+
+        assert!(cache.get(&key, &req_ctx).await.is_none());
+    }
+}
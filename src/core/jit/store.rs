@@ -34,7 +34,7 @@ impl DataPath {
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Store<Data> {
     data: HashMap<usize, Data>,
 }
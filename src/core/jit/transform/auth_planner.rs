@@ -78,5 +78,8 @@ pub fn update_ir(ir: &mut IR, vec: &mut Vec<Auth>) {
         IR::Discriminate(_, ir) => {
             update_ir(ir, vec);
         }
+        IR::RateLimit(_, ir) | IR::Invalidate(_, ir) => {
+            update_ir(ir, vec);
+        }
     }
 }
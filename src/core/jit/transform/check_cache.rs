@@ -8,7 +8,7 @@ use crate::core::jit::OperationPlan;
 use crate::core::Transform;
 
 /// A transformer that sets the minimum cache TTL for the operation plan based
-/// on the IR.
+/// on the IR of all its fields, nested ones included.
 pub struct CheckCache<A>(std::marker::PhantomData<A>);
 impl<A> CheckCache<A> {
     pub fn new() -> Self {
@@ -23,6 +23,9 @@ fn check_cache(ir: &IR) -> Option<NonZeroU64> {
         IR::Cache(cache) => Some(cache.max_age),
         IR::Path(ir, _) => check_cache(ir),
         IR::Protect(_, ir) => check_cache(ir),
+        IR::RateLimit(_, ir) => check_cache(ir),
+        // the responses of mutations aren't cached
+        IR::Invalidate(_, _) => None,
         IR::Pipe(ir, ir1) => match (check_cache(ir), check_cache(ir1)) {
             (Some(age1), Some(age2)) => Some(age1.min(age2)),
             _ => None,
@@ -46,7 +49,7 @@ impl<A> Transform for CheckCache<A> {
     fn transform(&self, mut plan: Self::Value) -> Valid<Self::Value, Self::Error> {
         let mut ttl = Some(NonZeroU64::MAX);
 
-        for field in plan.selection.iter() {
+        for field in plan.iter_dfs() {
             if let Some(ir) = field.ir.as_ref() {
                 ttl = std::cmp::min(ttl, check_cache(ir));
             }
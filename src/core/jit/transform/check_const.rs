@@ -23,6 +23,9 @@ pub fn is_const(ir: &IR) -> bool {
         IR::Path(ir, _) => is_const(ir),
         IR::ContextPath(_) => false,
         IR::Protect(_, ir) => is_const(ir),
+        // every call has to be counted by the limiter
+        IR::RateLimit(_, _) => false,
+        IR::Invalidate(_, _) => false,
         IR::Map(map) => is_const(&map.input),
         IR::Pipe(ir, ir1) => is_const(ir) && is_const(ir1),
         IR::Discriminate(_, ir) => is_const(ir),
@@ -20,6 +20,9 @@ fn check_dedupe(ir: &IR) -> bool {
         IR::Cache(cache) => cache.io.dedupe(),
         IR::Path(ir, _) => check_dedupe(ir),
         IR::Protect(_, ir) => check_dedupe(ir),
+        // sharing the response would let requests skip the limiter
+        IR::RateLimit(_, _) => false,
+        IR::Invalidate(_, _) => false,
         IR::Pipe(ir, ir1) => check_dedupe(ir) && check_dedupe(ir1),
         IR::Discriminate(_, ir) => check_dedupe(ir),
         IR::Entity(hash_map) => hash_map.values().all(check_dedupe),
@@ -0,0 +1,195 @@
+use std::marker::PhantomData;
+
+use tailcall_valid::{Valid, Validator};
+
+use crate::core::blueprint::QueryField;
+use crate::core::config::QueryLimits;
+use crate::core::ir::model::IR;
+use crate::core::jit::{Field, LimitError, OperationPlan};
+use crate::core::Transform;
+
+/// Cost of a field that is not set with `@cost` and doesn't make IO calls
+const DEFAULT_COST: usize = 1;
+
+/// Cost of a field that is not set with `@cost` and makes IO calls
+const DEFAULT_IO_COST: usize = 10;
+
+/// Rejects the operations that exceed the query limits. The introspection
+/// fields aren't part of the plan, so they aren't counted.
+pub struct CheckLimits<'a, A> {
+    limits: &'a QueryLimits,
+    _marker: PhantomData<A>,
+}
+
+impl<'a, A> CheckLimits<'a, A> {
+    pub fn new(limits: &'a QueryLimits) -> Self {
+        Self { limits, _marker: PhantomData }
+    }
+}
+
+/// Checks if evaluating the IR involves any IO
+fn has_io(ir: &IR) -> bool {
+    match ir {
+        IR::Dynamic(_) => false,
+        IR::IO(_) => true,
+        IR::Cache(_) => true,
+        IR::Path(ir, _) => has_io(ir),
+        IR::ContextPath(_) => false,
+        IR::Protect(_, ir) => has_io(ir),
+        IR::RateLimit(_, ir) => has_io(ir),
+        IR::Invalidate(_, ir) => has_io(ir),
+        IR::Map(map) => has_io(&map.input),
+        IR::Pipe(ir, ir1) => has_io(ir) || has_io(ir1),
+        IR::Discriminate(_, ir) => has_io(ir),
+        IR::Entity(hash_map) => hash_map.values().any(has_io),
+        IR::Service(_) => false,
+    }
+}
+
+fn depth<A>(field: &Field<A>) -> usize {
+    1 + field.selection.iter().map(depth).max().unwrap_or(0)
+}
+
+fn cost<A>(plan: &OperationPlan<A>, field: &Field<A>) -> usize {
+    let cost = field
+        .type_condition
+        .as_ref()
+        .and_then(|type_name| plan.index.get_field(type_name, &field.name))
+        .and_then(|field| match field {
+            QueryField::Field((definition, _)) => definition.cost,
+            QueryField::InputField(_) => None,
+        });
+
+    cost.unwrap_or_else(|| match field.ir {
+        Some(ref ir) if has_io(ir) => DEFAULT_IO_COST,
+        _ => DEFAULT_COST,
+    })
+}
+
+fn check(
+    limit: Option<usize>,
+    actual: impl FnOnce() -> usize,
+    error: impl FnOnce(usize, usize) -> LimitError,
+) -> Valid<(), LimitError> {
+    match limit {
+        Some(max) => {
+            let actual = actual();
+            if actual > max {
+                Valid::fail(error(actual, max))
+            } else {
+                Valid::succeed(())
+            }
+        }
+        None => Valid::succeed(()),
+    }
+}
+
+impl<A> Transform for CheckLimits<'_, A> {
+    type Value = OperationPlan<A>;
+    type Error = LimitError;
+
+    fn transform(&self, plan: Self::Value) -> Valid<Self::Value, Self::Error> {
+        let limits = self.limits;
+
+        check(
+            limits.depth,
+            || plan.selection.iter().map(depth).max().unwrap_or(0),
+            |actual, max| LimitError::Depth { actual, max },
+        )
+        .and(check(
+            limits.fields,
+            || plan.size(),
+            |actual, max| LimitError::Fields { actual, max },
+        ))
+        .and(check(
+            limits.aliases,
+            || {
+                plan.iter_dfs()
+                    .filter(|field| field.output_name != field.name)
+                    .count()
+            },
+            |actual, max| LimitError::Aliases { actual, max },
+        ))
+        .and(check(
+            limits.complexity,
+            || plan.iter_dfs().map(|field| cost(&plan, field)).sum(),
+            |actual, max| LimitError::Complexity { actual, max },
+        ))
+        .map(|_| plan)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::blueprint::Blueprint;
+    use crate::core::config::Config;
+    use crate::core::jit::Builder;
+
+    const CONFIG: &str = include_str!("../fixtures/jsonplaceholder-mutation.graphql");
+
+    fn plan(query: &str) -> OperationPlan<async_graphql_value::Value> {
+        let config = Config::from_sdl(CONFIG).to_result().unwrap();
+        let blueprint = Blueprint::try_from(&config.into()).unwrap();
+        let document = async_graphql::parser::parse_query(query).unwrap();
+
+        Builder::new(&blueprint, document).build(None).unwrap()
+    }
+
+    fn check_limits(limits: QueryLimits, query: &str) -> Result<(), LimitError> {
+        CheckLimits::new(&limits)
+            .transform(plan(query))
+            .to_result()
+            .map(|_| ())
+            .map_err(|errors| errors.as_vec()[0].message.clone())
+    }
+
+    const QUERY: &str = "{ posts { id title user { id name } } }";
+
+    #[test]
+    fn test_depth() {
+        let limits = QueryLimits { depth: Some(2), ..Default::default() };
+        let actual = check_limits(limits, QUERY);
+
+        assert_eq!(actual, Err(LimitError::Depth { actual: 3, max: 2 }));
+    }
+
+    #[test]
+    fn test_fields() {
+        let limits = QueryLimits { fields: Some(6), ..Default::default() };
+        assert!(check_limits(limits.clone(), QUERY).is_ok());
+
+        let limits = QueryLimits { fields: Some(5), ..limits };
+        let actual = check_limits(limits, QUERY);
+
+        assert_eq!(actual, Err(LimitError::Fields { actual: 6, max: 5 }));
+    }
+
+    #[test]
+    fn test_aliases() {
+        let limits = QueryLimits { aliases: Some(1), ..Default::default() };
+        let actual = check_limits(limits, "{ a: posts { id } b: posts { id } }");
+
+        assert_eq!(actual, Err(LimitError::Aliases { actual: 2, max: 1 }));
+    }
+
+    #[test]
+    fn test_complexity() {
+        // posts and user make IO calls, every other field costs 1
+        let limits = QueryLimits { complexity: Some(24), ..Default::default() };
+        assert!(check_limits(limits.clone(), QUERY).is_ok());
+
+        let limits = QueryLimits { complexity: Some(23), ..limits };
+        let actual = check_limits(limits, QUERY);
+
+        assert_eq!(actual, Err(LimitError::Complexity { actual: 24, max: 23 }));
+    }
+
+    #[test]
+    fn test_introspection_is_exempt() {
+        let limits = QueryLimits { depth: Some(1), fields: Some(1), ..Default::default() };
+        let actual = check_limits(limits, "{ __schema { types { name fields { name } } } }");
+
+        assert!(actual.is_ok());
+    }
+}
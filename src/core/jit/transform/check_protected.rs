@@ -23,6 +23,8 @@ pub fn is_protected(ir: &IR) -> bool {
         IR::Path(ir, _) => is_protected(ir),
         IR::ContextPath(_) => false,
         IR::Protect(_, _) => true,
+        IR::RateLimit(_, ir) => is_protected(ir),
+        IR::Invalidate(_, ir) => is_protected(ir),
         IR::Map(map) => is_protected(&map.input),
         IR::Pipe(ir, ir1) => is_protected(ir) || is_protected(ir1),
         IR::Discriminate(_, ir) => is_protected(ir),
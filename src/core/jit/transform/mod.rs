@@ -2,6 +2,7 @@ mod auth_planner;
 mod check_cache;
 mod check_const;
 mod check_dedupe;
+mod check_limits;
 mod check_protected;
 mod input_resolver;
 mod skip;
@@ -10,6 +11,7 @@ pub use auth_planner::*;
 pub use check_cache::*;
 pub use check_const::*;
 pub use check_dedupe::*;
+pub use check_limits::*;
 pub use check_protected::*;
 pub use input_resolver::*;
 pub use skip::*;
@@ -6,6 +6,7 @@ pub mod async_graphql_hyper;
 mod auth;
 pub mod blueprint;
 pub mod cache;
+pub mod circuit_breaker;
 pub mod config;
 mod counter;
 pub mod data_loader;
@@ -27,11 +28,14 @@ mod lift;
 pub mod merge_right;
 pub mod mustache;
 pub mod path;
+pub mod persisted_query;
 pub mod primitive;
 pub mod print_schema;
 pub mod proto_reader;
+pub mod rate_limit;
 pub mod resource_reader;
 pub mod rest;
+pub mod retry;
 pub mod runtime;
 pub mod scalar;
 pub mod schema_extension;
@@ -47,11 +51,15 @@ pub mod wrapping_type;
 use std::borrow::Cow;
 use std::hash::Hash;
 use std::num::NonZeroU64;
+use std::sync::Arc;
 
 use async_graphql::{Pos, Positioned};
 use async_graphql_value::ConstValue;
+use blueprint::Tls;
 pub use errata::Errata;
 pub use error::{Error, Result};
+use futures_util::stream::BoxStream;
+use futures_util::StreamExt;
 use http::Response;
 use ir::model::IoId;
 pub use mustache::Mustache;
@@ -74,6 +82,34 @@ pub trait HttpIO: Sync + Send + 'static {
         &self,
         request: reqwest::Request,
     ) -> anyhow::Result<Response<hyper::body::Bytes>>;
+
+    /// Executes the request and returns the body as a stream of chunks as they
+    /// arrive. Implementations that can't stream yield the whole body at once.
+    async fn execute_stream(
+        &self,
+        request: reqwest::Request,
+    ) -> anyhow::Result<BoxStream<'static, anyhow::Result<hyper::body::Bytes>>> {
+        let response = self.execute(request).await?;
+        Ok(futures_util::stream::once(async move { Ok(response.body) }).boxed())
+    }
+
+    /// Whether the client can be configured with client certificates and
+    /// certificate authorities. Configurations with TLS settings for the
+    /// upstreams are rejected on runtimes that can't.
+    fn supports_tls(&self) -> bool {
+        false
+    }
+
+    /// Returns the client to use for the upstreams with their own TLS
+    /// settings. Runtimes that can't configure TLS return `None`.
+    fn with_tls(&self, _tls: &Tls) -> Option<Arc<dyn HttpIO>> {
+        None
+    }
+
+    /// Removes the responses kept by the HTTP cache of the client, if any
+    async fn clear_cache(&self) -> anyhow::Result<()> {
+        Ok(())
+    }
 }
 
 #[async_trait::async_trait]
@@ -94,11 +130,31 @@ pub trait Cache: Send + Sync {
     ) -> Result<(), cache::Error>;
     async fn get<'a>(&'a self, key: &'a Self::Key) -> Result<Option<Self::Value>, cache::Error>;
 
+    /// Returns a value along with its age, the milliseconds elapsed since it
+    /// was set. Caches that don't keep track of it report the values as just
+    /// set.
+    async fn get_entry<'a>(
+        &'a self,
+        key: &'a Self::Key,
+    ) -> Result<Option<(Self::Value, u64)>, cache::Error> {
+        Ok(self.get(key).await?.map(|value| (value, 0)))
+    }
+
     fn hit_rate(&self) -> Option<f64>;
+
+    /// Removes all the values of the cache. Caches that can't be cleared
+    /// report it as an error.
+    async fn clear<'a>(&'a self) -> Result<(), cache::Error> {
+        Err(cache::Error::Kv("The cache can't be cleared".to_string()))
+    }
 }
 
 pub type EntityCache = dyn Cache<Key = IoId, Value = ConstValue>;
 
+/// Stores the documents registered through Automatic Persisted Queries by their
+/// sha256 hash
+pub type PersistedQueryCache = dyn Cache<Key = String, Value = String>;
+
 #[async_trait::async_trait]
 pub trait WorkerIO<In, Out>: Send + Sync + 'static {
     /// Calls a global JS function
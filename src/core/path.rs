@@ -79,6 +79,7 @@ impl<Ctx: ResolverContextLike> EvalContext<'_, Ctx> {
                 "vars" => Some(ValueString::String(Cow::Owned(
                     json!(ctx.vars()).to_string(),
                 ))),
+                "claims" => Some(ValueString::String(ctx.claims::<T>(&[])?)),
                 _ => None,
             };
         }
@@ -94,6 +95,8 @@ impl<Ctx: ResolverContextLike> EvalContext<'_, Ctx> {
                     ctx.var(tail[0].as_ref())?,
                 ))),
                 "env" => Some(ValueString::String(ctx.env_var(tail[0].as_ref())?)),
+                "clientCert" => Some(ValueString::String(ctx.client_cert(tail[0].as_ref())?)),
+                "claims" => Some(ValueString::String(ctx.claims(tail)?)),
                 _ => None,
             })
     }
@@ -139,8 +142,9 @@ mod tests {
         use http::header::{HeaderMap, HeaderValue};
         use indexmap::IndexMap;
         use once_cell::sync::Lazy;
+        use serde_json::json;
 
-        use crate::core::http::RequestContext;
+        use crate::core::http::{ClientCertificate, RequestContext};
         use crate::core::ir::{EvalContext, ResolverContextLike, SelectionField};
         use crate::core::path::{PathGraphql, PathString, PathValue, ValueString};
         use crate::core::EnvIO;
@@ -240,10 +244,17 @@ mod tests {
         }
 
         static REQ_CTX: Lazy<RequestContext> = Lazy::new(|| {
-            let mut req_ctx = RequestContext::default().allowed_headers(TEST_HEADERS.clone());
+            let mut req_ctx = RequestContext::default()
+                .allowed_headers(TEST_HEADERS.clone())
+                .client_cert(Some(ClientCertificate {
+                    subject: "CN=orders".to_owned(),
+                    san: vec!["orders.svc".to_owned(), "orders.local".to_owned()],
+                    issuers: vec![],
+                }));
 
             req_ctx.server.vars = TEST_VARS.clone();
             req_ctx.runtime.env = Arc::new(Env::init(TEST_ENV_VARS.clone()));
+            *req_ctx.claims.lock().unwrap() = Some(json!({"sub": "partner", "org": {"id": 1}}));
 
             req_ctx
         });
@@ -450,6 +461,28 @@ mod tests {
             );
             assert_eq!(EVAL_CTX.path_string(&["env", "x-missing"]), None);
 
+            // client certificate
+            assert_eq!(
+                EVAL_CTX.path_string(&["clientCert", "subject"]),
+                Some(Cow::Borrowed("CN=orders"))
+            );
+            assert_eq!(
+                EVAL_CTX.path_string(&["clientCert", "san"]),
+                Some(Cow::Borrowed("orders.svc,orders.local"))
+            );
+            assert_eq!(EVAL_CTX.path_string(&["clientCert", "issuer"]), None);
+
+            // claims
+            assert_eq!(
+                EVAL_CTX.path_string(&["claims", "sub"]),
+                Some(Cow::Borrowed("partner"))
+            );
+            assert_eq!(
+                EVAL_CTX.path_string(&["claims", "org", "id"]),
+                Some(Cow::Borrowed("1"))
+            );
+            assert_eq!(EVAL_CTX.path_string(&["claims", "missing"]), None);
+
             // other value types
             assert_eq!(EVAL_CTX.path_string(&["foo", "key"]), None);
             assert_eq!(EVAL_CTX.path_string(&["bar", "key"]), None);
@@ -0,0 +1,208 @@
+use std::collections::HashMap;
+use std::num::NonZeroU64;
+
+use async_graphql::{ErrorExtensionValues, ServerError};
+use async_graphql_value::ConstValue;
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+
+use crate::core::PersistedQueryCache;
+
+/// Time for which a registered document is kept in the cache, in milliseconds
+const PERSISTED_QUERY_TTL: NonZeroU64 = match NonZeroU64::new(24 * 60 * 60 * 1000) {
+    Some(ttl) => ttl,
+    None => unreachable!(),
+};
+
+/// Maximum number of documents kept by the in-memory store
+pub const PERSISTED_QUERY_CAPACITY: usize = 10000;
+
+/// The only version of the protocol that is supported
+const PERSISTED_QUERY_VERSION: u32 = 1;
+
+/// The `persistedQuery` extension of an
+/// [Automatic Persisted Queries](https://www.apollographql.com/docs/apollo-server/performance/apq)
+/// request
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PersistedQuery {
+    version: u32,
+    sha256_hash: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum Error {
+    #[error("PersistedQueryNotFound")]
+    NotFound,
+
+    #[error("PersistedQueryNotSupported")]
+    NotSupported,
+
+    #[error("provided sha does not match query")]
+    HashMismatch,
+}
+
+impl Error {
+    fn code(&self) -> &'static str {
+        match self {
+            Error::NotFound => "PERSISTED_QUERY_NOT_FOUND",
+            Error::NotSupported => "PERSISTED_QUERY_NOT_SUPPORTED",
+            Error::HashMismatch => "INVALID_SHA256_HASH",
+        }
+    }
+}
+
+impl From<Error> for ServerError {
+    fn from(error: Error) -> Self {
+        let mut extensions = ErrorExtensionValues::default();
+        extensions.set("code", error.code());
+
+        let mut server_error = ServerError::new(error.to_string(), None);
+        server_error.extensions = Some(extensions);
+        server_error
+    }
+}
+
+/// Hex encoded sha256 of a document, as used to identify persisted documents
+pub fn sha256(query: &str) -> String {
+    format!("{:x}", Sha256::digest(query.as_bytes()))
+}
+
+/// Returns the hash sent in the `persistedQuery` extension of a request, if
+/// any.
+pub fn hash(extensions: &HashMap<String, ConstValue>) -> Option<&str> {
+    match extensions.get("persistedQuery")? {
+        ConstValue::Object(extension) => match extension.get("sha256Hash")? {
+            ConstValue::String(hash) => Some(hash.as_str()),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Resolves the document of a request that uses Automatic Persisted Queries.
+/// Requests that only carry the hash get the document from the cache, while
+/// requests carrying both the document and its hash register the document.
+/// Requests without the `persistedQuery` extension are left untouched.
+pub async fn resolve(
+    request: &mut async_graphql::Request,
+    cache: &PersistedQueryCache,
+) -> Result<(), Error> {
+    let Some(extension) = request.extensions.0.get("persistedQuery") else {
+        return Ok(());
+    };
+
+    let persisted_query = async_graphql::from_value::<PersistedQuery>(extension.clone())
+        .map_err(|_| Error::NotSupported)?;
+
+    if persisted_query.version != PERSISTED_QUERY_VERSION {
+        return Err(Error::NotSupported);
+    }
+
+    let hash = persisted_query.sha256_hash.to_lowercase();
+
+    if request.query.is_empty() {
+        // a failing store is treated like a miss, so that the client retries
+        // with the full document
+        let query = cache.get(&hash).await.ok().flatten();
+        request.query = query.ok_or(Error::NotFound)?;
+    } else {
+        if sha256(&request.query) != hash {
+            return Err(Error::HashMismatch);
+        }
+
+        if let Err(err) = cache
+            .set(hash, request.query.clone(), PERSISTED_QUERY_TTL)
+            .await
+        {
+            tracing::warn!("Failed to register the persisted query: {}", err);
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use async_graphql::{Name, Value};
+    use indexmap::IndexMap;
+
+    use super::*;
+    use crate::core::cache::InMemoryCache;
+
+    const QUERY: &str = "{ posts { id } }";
+
+    fn request(query: &str, hash: &str) -> async_graphql::Request {
+        let mut extension = IndexMap::new();
+        extension.insert(Name::new("version"), Value::from(1));
+        extension.insert(Name::new("sha256Hash"), Value::from(hash));
+
+        let mut request = async_graphql::Request::new(query);
+        request
+            .extensions
+            .0
+            .insert("persistedQuery".to_string(), Value::Object(extension));
+        request
+    }
+
+    #[tokio::test]
+    async fn test_not_found() {
+        let cache: InMemoryCache<String, String> = InMemoryCache::new(10);
+        let mut request = request("", &sha256(QUERY));
+
+        let actual = resolve(&mut request, &cache).await;
+
+        assert_eq!(actual, Err(Error::NotFound));
+    }
+
+    #[tokio::test]
+    async fn test_register_and_resolve() {
+        let cache: InMemoryCache<String, String> = InMemoryCache::new(10);
+        let mut register = request(QUERY, &sha256(QUERY));
+        resolve(&mut register, &cache).await.unwrap();
+
+        let mut request = request("", &sha256(QUERY));
+        resolve(&mut request, &cache).await.unwrap();
+
+        assert_eq!(request.query, QUERY);
+    }
+
+    #[tokio::test]
+    async fn test_hash_mismatch() {
+        let cache: InMemoryCache<String, String> = InMemoryCache::new(10);
+        let mut request = request(QUERY, &sha256("{ users { id } }"));
+
+        let actual = resolve(&mut request, &cache).await;
+
+        assert_eq!(actual, Err(Error::HashMismatch));
+    }
+
+    #[tokio::test]
+    async fn test_without_extension() {
+        let cache: InMemoryCache<String, String> = InMemoryCache::new(10);
+        let mut request = async_graphql::Request::new(QUERY);
+
+        resolve(&mut request, &cache).await.unwrap();
+
+        assert_eq!(request.query, QUERY);
+    }
+
+    #[test]
+    fn test_hash() {
+        let request = request("", "ABC");
+
+        assert_eq!(hash(&request.extensions.0), Some("ABC"));
+        assert_eq!(hash(&HashMap::new()), None);
+    }
+
+    #[test]
+    fn test_server_error() {
+        let error = ServerError::from(Error::NotFound);
+
+        assert_eq!(error.message, "PersistedQueryNotFound");
+        assert_eq!(
+            error.extensions.unwrap().get("code"),
+            Some(&Value::from("PERSISTED_QUERY_NOT_FOUND"))
+        );
+    }
+}
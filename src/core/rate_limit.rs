@@ -0,0 +1,372 @@
+use std::num::{NonZeroU64, NonZeroUsize};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use crate::core::config::{self, RateLimitAlgorithm};
+use crate::core::{cache, Cache};
+
+/// Maximum number of keys kept by the in-memory limiter, the least recently
+/// used ones being evicted beyond it
+pub const RATE_LIMIT_CAPACITY: usize = 10000;
+
+/// The number of calls allowed for a key over a window and how they are
+/// counted.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RateLimitPolicy {
+    pub requests: NonZeroU64,
+    /// Duration of the window, in milliseconds
+    pub window: NonZeroU64,
+    pub algorithm: RateLimitAlgorithm,
+}
+
+impl From<&config::RateLimit> for RateLimitPolicy {
+    fn from(rate_limit: &config::RateLimit) -> Self {
+        Self {
+            requests: rate_limit.requests,
+            window: rate_limit.window,
+            algorithm: rate_limit.algorithm.clone(),
+        }
+    }
+}
+
+/// Outcome of acquiring a call from a limiter
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Decision {
+    Allowed,
+    Limited { retry_after: Duration },
+}
+
+/// What is stored for every key, the timestamps are in milliseconds since the
+/// unix epoch.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "algorithm", rename_all = "camelCase")]
+pub enum RateLimitState {
+    #[serde(rename_all = "camelCase")]
+    SlidingWindow {
+        window_start: u64,
+        current: u64,
+        previous: u64,
+    },
+    #[serde(rename_all = "camelCase")]
+    TokenBucket { tokens: f64, updated_at: u64 },
+}
+
+impl RateLimitPolicy {
+    /// Time for which the state of a key has to be kept around. Both
+    /// algorithms forget about a key once it was idle for two windows.
+    pub fn ttl(&self) -> NonZeroU64 {
+        self.window.saturating_mul(NonZeroU64::new(2).unwrap())
+    }
+
+    /// Records a call made at `now` against the current state of a key,
+    /// returning the updated state and whether the call is allowed.
+    pub fn acquire(&self, state: Option<RateLimitState>, now: u64) -> (RateLimitState, Decision) {
+        match self.algorithm {
+            RateLimitAlgorithm::SlidingWindow => self.sliding_window(state, now),
+            RateLimitAlgorithm::TokenBucket => self.token_bucket(state, now),
+        }
+    }
+
+    fn sliding_window(
+        &self,
+        state: Option<RateLimitState>,
+        now: u64,
+    ) -> (RateLimitState, Decision) {
+        let limit = self.requests.get();
+        let window = self.window.get();
+        let window_start = now - now % window;
+
+        let (mut current, previous) = match state {
+            Some(RateLimitState::SlidingWindow { window_start: start, current, previous }) => {
+                if start == window_start {
+                    (current, previous)
+                } else if start + window == window_start {
+                    (0, current)
+                } else {
+                    (0, 0)
+                }
+            }
+            _ => (0, 0),
+        };
+
+        let elapsed = now - window_start;
+        // the part of the previous window that still overlaps with the last
+        // `window` milliseconds
+        let overlap = (window - elapsed) as f64 / window as f64;
+        let estimate = previous as f64 * overlap + current as f64;
+
+        let decision = if estimate + 1.0 <= limit as f64 {
+            current += 1;
+            Decision::Allowed
+        } else if current < limit && previous > 0 {
+            // wait until enough of the previous window has slid out
+            let overlap = (limit - current - 1) as f64 / previous as f64;
+            let wait = window as f64 * (1.0 - overlap) - elapsed as f64;
+            Decision::Limited {
+                retry_after: Duration::from_millis(wait.round().max(1.0) as u64),
+            }
+        } else {
+            Decision::Limited { retry_after: Duration::from_millis(window - elapsed) }
+        };
+
+        (
+            RateLimitState::SlidingWindow { window_start, current, previous },
+            decision,
+        )
+    }
+
+    fn token_bucket(&self, state: Option<RateLimitState>, now: u64) -> (RateLimitState, Decision) {
+        let capacity = self.requests.get() as f64;
+        let window = self.window.get() as f64;
+
+        let mut tokens = match state {
+            Some(RateLimitState::TokenBucket { tokens, updated_at }) => {
+                // `capacity` tokens are refilled every window
+                let refilled = now.saturating_sub(updated_at) as f64 * capacity / window;
+                (tokens + refilled).min(capacity)
+            }
+            _ => capacity,
+        };
+
+        let decision = if tokens >= 1.0 {
+            tokens -= 1.0;
+            Decision::Allowed
+        } else {
+            let wait = (1.0 - tokens) * window / capacity;
+            Decision::Limited { retry_after: Duration::from_millis(wait.round() as u64) }
+        };
+
+        (
+            RateLimitState::TokenBucket { tokens, updated_at: now },
+            decision,
+        )
+    }
+}
+
+/// Milliseconds since the unix epoch. Uses chrono so that it also works on
+/// wasm targets.
+fn now() -> u64 {
+    chrono::Utc::now().timestamp_millis() as u64
+}
+
+/// Storage for the state of the rate limits. Implementations decide where the
+/// state lives and how much it is shared between the instances of the server.
+#[async_trait::async_trait]
+pub trait RateLimiter: Send + Sync {
+    /// Records a call for the key and returns whether it is allowed by the
+    /// policy.
+    async fn acquire(&self, key: &str, policy: &RateLimitPolicy) -> cache::Result<Decision>;
+}
+
+/// Keeps the state of the rate limits in the memory of the process, which makes
+/// the limits local to every instance of the server. At most `capacity` keys
+/// are kept, the least recently used ones being forgotten first.
+pub struct InMemoryRateLimiter {
+    states: Mutex<lru::LruCache<String, (RateLimitState, u64)>>,
+}
+
+impl InMemoryRateLimiter {
+    pub fn new(capacity: usize) -> Self {
+        let capacity = NonZeroUsize::new(capacity).unwrap_or(NonZeroUsize::MIN);
+        Self { states: Mutex::new(lru::LruCache::new(capacity)) }
+    }
+}
+
+impl Default for InMemoryRateLimiter {
+    fn default() -> Self {
+        Self::new(RATE_LIMIT_CAPACITY)
+    }
+}
+
+#[async_trait::async_trait]
+impl RateLimiter for InMemoryRateLimiter {
+    async fn acquire(&self, key: &str, policy: &RateLimitPolicy) -> cache::Result<Decision> {
+        let now = now();
+        let mut states = self.states.lock().unwrap();
+
+        let state = states
+            .pop(key)
+            .filter(|(_, expires_at)| *expires_at > now)
+            .map(|(state, _)| state);
+        let (state, decision) = policy.acquire(state, now);
+        states.put(key.to_string(), (state, now + policy.ttl().get()));
+
+        Ok(decision)
+    }
+}
+
+/// Keeps the state of the rate limits in a [Cache], so that runtimes with a
+/// shared store, like Cloudflare KV, can enforce the limits across instances.
+/// The limits are best-effort: the [Cache] has no atomic increment, so the
+/// state is read and then written back, and concurrent calls with the same key
+/// can all read the same state and all be allowed.
+pub struct CacheRateLimiter {
+    cache: Arc<dyn Cache<Key = String, Value = String>>,
+}
+
+impl CacheRateLimiter {
+    pub fn new(cache: Arc<dyn Cache<Key = String, Value = String>>) -> Self {
+        Self { cache }
+    }
+}
+
+#[async_trait::async_trait]
+impl RateLimiter for CacheRateLimiter {
+    async fn acquire(&self, key: &str, policy: &RateLimitPolicy) -> cache::Result<Decision> {
+        let key = key.to_string();
+        let state = match self.cache.get(&key).await? {
+            Some(state) => serde_json::from_str(&state).ok(),
+            None => None,
+        };
+
+        let (state, decision) = policy.acquire(state, now());
+        self.cache
+            .set(key, serde_json::to_string(&state)?, policy.ttl())
+            .await?;
+
+        Ok(decision)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::cache::InMemoryCache;
+
+    fn policy(requests: u64, window: u64, algorithm: RateLimitAlgorithm) -> RateLimitPolicy {
+        RateLimitPolicy {
+            requests: NonZeroU64::new(requests).unwrap(),
+            window: NonZeroU64::new(window).unwrap(),
+            algorithm,
+        }
+    }
+
+    /// Acquires a call at every timestamp and returns the decisions
+    fn run(policy: &RateLimitPolicy, timestamps: &[u64]) -> Vec<Decision> {
+        let mut state = None;
+        timestamps
+            .iter()
+            .map(|now| {
+                let (next, decision) = policy.acquire(state.take(), *now);
+                state = Some(next);
+                decision
+            })
+            .collect()
+    }
+
+    fn limited(millis: u64) -> Decision {
+        Decision::Limited { retry_after: Duration::from_millis(millis) }
+    }
+
+    #[test]
+    fn test_sliding_window() {
+        let policy = policy(2, 1000, RateLimitAlgorithm::SlidingWindow);
+        let actual = run(&policy, &[1000, 1100, 1200, 2100, 2500, 2600]);
+
+        assert_eq!(
+            actual,
+            vec![
+                Decision::Allowed,
+                Decision::Allowed,
+                limited(800),
+                // the previous window still weighs 0.9 * 2
+                limited(400),
+                Decision::Allowed,
+                // 0.4 * 2 + 1 leaves no room until the window ends
+                limited(400),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_sliding_window_forgets_old_windows() {
+        let policy = policy(1, 1000, RateLimitAlgorithm::SlidingWindow);
+        let actual = run(&policy, &[1000, 3000]);
+
+        assert_eq!(actual, vec![Decision::Allowed, Decision::Allowed]);
+    }
+
+    #[test]
+    fn test_token_bucket() {
+        let policy = policy(2, 1000, RateLimitAlgorithm::TokenBucket);
+        let actual = run(&policy, &[0, 0, 0, 500, 600, 2000, 2000, 2000]);
+
+        assert_eq!(
+            actual,
+            vec![
+                Decision::Allowed,
+                Decision::Allowed,
+                limited(500),
+                Decision::Allowed,
+                limited(400),
+                Decision::Allowed,
+                Decision::Allowed,
+                limited(500),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_state_of_other_algorithm_is_ignored() {
+        let policy = policy(1, 1000, RateLimitAlgorithm::TokenBucket);
+        let state = RateLimitState::SlidingWindow { window_start: 0, current: 1, previous: 0 };
+        let (_, decision) = policy.acquire(Some(state), 0);
+
+        assert_eq!(decision, Decision::Allowed);
+    }
+
+    #[tokio::test]
+    async fn test_in_memory_limiter() {
+        let limiter = InMemoryRateLimiter::default();
+        let policy = policy(1, 60_000, RateLimitAlgorithm::TokenBucket);
+
+        assert_eq!(
+            limiter.acquire("a", &policy).await.unwrap(),
+            Decision::Allowed
+        );
+        assert_ne!(
+            limiter.acquire("a", &policy).await.unwrap(),
+            Decision::Allowed
+        );
+        assert_eq!(
+            limiter.acquire("b", &policy).await.unwrap(),
+            Decision::Allowed
+        );
+    }
+
+    #[tokio::test]
+    async fn test_in_memory_limiter_capacity() {
+        let limiter = InMemoryRateLimiter::new(2);
+        let policy = policy(1, 60_000, RateLimitAlgorithm::TokenBucket);
+
+        for key in ["a", "b", "c"] {
+            limiter.acquire(key, &policy).await.unwrap();
+        }
+
+        let states = limiter.states.lock().unwrap();
+        assert_eq!(states.len(), 2);
+        assert!(!states.contains("a"));
+    }
+
+    #[tokio::test]
+    async fn test_cache_limiter() {
+        let cache: InMemoryCache<String, String> = InMemoryCache::new(10);
+        let limiter = CacheRateLimiter::new(Arc::new(cache));
+        let policy = policy(1, 60_000, RateLimitAlgorithm::SlidingWindow);
+
+        assert_eq!(
+            limiter.acquire("a", &policy).await.unwrap(),
+            Decision::Allowed
+        );
+        assert_ne!(
+            limiter.acquire("a", &policy).await.unwrap(),
+            Decision::Allowed
+        );
+        assert_eq!(
+            limiter.acquire("b", &policy).await.unwrap(),
+            Decision::Allowed
+        );
+    }
+}
@@ -1,4 +1,4 @@
-use std::collections::HashMap;
+use std::collections::{BTreeSet, HashMap};
 use std::hash::{Hash, Hasher};
 use std::sync::{Arc, Mutex};
 
@@ -23,6 +23,17 @@ pub enum Resource {
 }
 
 impl Resource {
+    /// Returns the path if the resource is a file on the local filesystem
+    pub fn local_path(&self) -> Option<&str> {
+        match self {
+            Resource::RawPath(path) => match Url::parse(path) {
+                Ok(url) if url.scheme().starts_with("http") => None,
+                _ => Some(path),
+            },
+            Resource::Request(_) => None,
+        }
+    }
+
     pub fn calculate_hash(&self) -> u64 {
         let mut hasher = TailcallHasher::default();
         self.hash(&mut hasher);
@@ -103,6 +114,11 @@ impl ResourceReader<Cached> {
     pub fn cached(runtime: TargetRuntime) -> Self {
         ResourceReader(Cached::init(runtime))
     }
+
+    /// Paths of all the files read from the local filesystem so far
+    pub fn local_files(&self) -> Vec<String> {
+        self.0.local_files.lock().unwrap().iter().cloned().collect()
+    }
 }
 
 impl std::fmt::Display for Resource {
@@ -172,11 +188,17 @@ pub struct Cached {
     direct: Direct,
     // Cache file content, path -> content
     cache: Arc<Mutex<HashMap<String, String>>>,
+    // Paths of the files read from the local filesystem
+    local_files: Arc<Mutex<BTreeSet<String>>>,
 }
 
 impl Cached {
     pub fn init(runtime: TargetRuntime) -> Self {
-        Self { direct: Direct::init(runtime), cache: Default::default() }
+        Self {
+            direct: Direct::init(runtime),
+            cache: Default::default(),
+            local_files: Default::default(),
+        }
     }
 }
 
@@ -197,6 +219,9 @@ impl Reader for Cached {
         let content = if let Some(content) = content {
             content.to_owned()
         } else {
+            if let Some(path) = resource.local_path() {
+                self.local_files.lock().unwrap().insert(path.to_owned());
+            }
             let file_read = self.direct.read(resource).await?;
             self.cache
                 .as_ref()
@@ -244,6 +269,15 @@ mod test {
         assert_eq!(actual.url(), expected.url());
     }
 
+    #[test]
+    fn test_local_path() {
+        let resource: Resource = "./config.graphql".into();
+        assert_eq!(resource.local_path(), Some("./config.graphql"));
+
+        let resource: Resource = "https://tailcall.run/config.graphql".into();
+        assert_eq!(resource.local_path(), None);
+    }
+
     #[test]
     fn test_from_str() {
         let path = "https://tailcall.run";
@@ -34,11 +34,13 @@ impl From<Endpoint> for EndpointSet<Unchecked> {
     }
 }
 
-impl EndpointSet<Unchecked> {
+impl<Status> EndpointSet<Status> {
     pub fn get_endpoints(&self) -> &Vec<Endpoint> {
         &self.endpoints
     }
+}
 
+impl EndpointSet<Unchecked> {
     pub fn add_endpoint(&mut self, endpoint: Endpoint) {
         self.endpoints.push(endpoint);
     }
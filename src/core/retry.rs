@@ -0,0 +1,279 @@
+use std::collections::BTreeSet;
+use std::num::NonZeroU64;
+use std::time::Duration;
+
+use futures_timer::Delay;
+use hyper::body::Bytes;
+use rand::Rng;
+use reqwest::{Method, Request};
+
+use crate::core::http::{reqwest_error, Response};
+use crate::core::{config, HttpIO};
+
+const DEFAULT_MAX_ATTEMPTS: u64 = 3;
+const DEFAULT_INITIAL_BACKOFF: u64 = 100;
+const DEFAULT_MAX_BACKOFF: u64 = 5000;
+const DEFAULT_STATUS_CODES: [u16; 4] = [429, 502, 503, 504];
+
+/// How failed requests to an upstream are retried.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// Maximum number of attempts, including the first one
+    pub max_attempts: NonZeroU64,
+    pub initial_backoff: Duration,
+    pub max_backoff: Duration,
+    pub jitter: bool,
+    pub status_codes: BTreeSet<u16>,
+    pub idempotent_only: bool,
+}
+
+impl From<&config::Retry> for RetryPolicy {
+    fn from(retry: &config::Retry) -> Self {
+        Self {
+            max_attempts: retry
+                .max_attempts
+                .unwrap_or(NonZeroU64::new(DEFAULT_MAX_ATTEMPTS).unwrap()),
+            initial_backoff: Duration::from_millis(
+                retry.initial_backoff.unwrap_or(DEFAULT_INITIAL_BACKOFF),
+            ),
+            max_backoff: Duration::from_millis(retry.max_backoff.unwrap_or(DEFAULT_MAX_BACKOFF)),
+            jitter: retry.jitter.unwrap_or(true),
+            status_codes: retry
+                .status_codes
+                .clone()
+                .unwrap_or_else(|| DEFAULT_STATUS_CODES.into_iter().collect()),
+            idempotent_only: retry.idempotent_only.unwrap_or(true),
+        }
+    }
+}
+
+/// Checks if sending a request with the method more than once has the same
+/// effect as sending it once.
+pub fn is_idempotent(method: &Method) -> bool {
+    matches!(
+        *method,
+        Method::GET | Method::HEAD | Method::OPTIONS | Method::PUT | Method::DELETE | Method::TRACE
+    )
+}
+
+impl RetryPolicy {
+    /// The time to wait after the given number of failed attempts
+    fn backoff(&self, attempt: u64) -> Duration {
+        let factor = 2u32.saturating_pow((attempt - 1).min(31) as u32);
+        let backoff = self
+            .initial_backoff
+            .saturating_mul(factor)
+            .min(self.max_backoff);
+
+        if self.jitter {
+            let jitter = (backoff / 2).mul_f64(rand::thread_rng().gen::<f64>());
+            backoff - jitter
+        } else {
+            backoff
+        }
+    }
+
+    fn is_retryable(&self, result: &anyhow::Result<Response<Bytes>>) -> bool {
+        let error = match result {
+            Ok(response) => return self.status_codes.contains(&response.status.as_u16()),
+            Err(error) => reqwest_error(error),
+        };
+
+        match error {
+            Some(error) => match error.status() {
+                Some(status) => self.status_codes.contains(&status.as_u16()),
+                None => error.is_connect() || error.is_timeout(),
+            },
+            None => false,
+        }
+    }
+
+    /// Executes the request, sending it again after a backoff as long as it
+    /// fails with a retryable error and attempts are left.
+    pub async fn execute(
+        &self,
+        http: &dyn HttpIO,
+        mut request: Request,
+        idempotent: bool,
+    ) -> anyhow::Result<Response<Bytes>> {
+        if self.idempotent_only && !idempotent {
+            return http.execute(request).await;
+        }
+
+        let mut attempt = 1;
+        loop {
+            // requests with a streaming body can't be cloned and are sent once
+            let next = (attempt < self.max_attempts.get())
+                .then(|| request.try_clone())
+                .flatten();
+            let result = http.execute(request).await;
+
+            match next {
+                Some(next) if self.is_retryable(&result) => {
+                    tracing::warn!(
+                        "Retrying {} {} after {} failed attempt(s)",
+                        next.method(),
+                        next.url(),
+                        attempt
+                    );
+                    Delay::new(self.backoff(attempt)).await;
+                    request = next;
+                    attempt += 1;
+                }
+                _ => return result,
+            }
+        }
+    }
+}
+
+/// Executes the request with the retry policy, if any.
+pub async fn execute(
+    http: &dyn HttpIO,
+    request: Request,
+    retry: Option<&RetryPolicy>,
+    idempotent: bool,
+) -> anyhow::Result<Response<Bytes>> {
+    match retry {
+        Some(retry) => retry.execute(http, request, idempotent).await,
+        None => http.execute(request).await,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use reqwest::StatusCode;
+
+    use super::*;
+
+    struct TestHttp {
+        statuses: Mutex<Vec<u16>>,
+        attempts: Mutex<usize>,
+    }
+
+    impl TestHttp {
+        fn new(statuses: &[u16]) -> Self {
+            Self {
+                statuses: Mutex::new(statuses.iter().rev().copied().collect()),
+                attempts: Mutex::new(0),
+            }
+        }
+
+        fn attempts(&self) -> usize {
+            *self.attempts.lock().unwrap()
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl HttpIO for TestHttp {
+        async fn execute(&self, _request: Request) -> anyhow::Result<Response<Bytes>> {
+            *self.attempts.lock().unwrap() += 1;
+            let status = self.statuses.lock().unwrap().pop().unwrap_or(200);
+
+            Ok(Response { status: StatusCode::from_u16(status)?, ..Default::default() })
+        }
+    }
+
+    fn policy() -> RetryPolicy {
+        RetryPolicy {
+            initial_backoff: Duration::ZERO,
+            ..RetryPolicy::from(&config::Retry::default())
+        }
+    }
+
+    fn request(method: Method) -> Request {
+        Request::new(method, "http://localhost/users".parse().unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_retries_until_success() {
+        let http = TestHttp::new(&[503, 502, 200]);
+        let response = policy()
+            .execute(&http, request(Method::GET), true)
+            .await
+            .unwrap();
+
+        assert_eq!(response.status, StatusCode::OK);
+        assert_eq!(http.attempts(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_gives_up_after_max_attempts() {
+        let http = TestHttp::new(&[503, 503, 503, 200]);
+        let response = policy()
+            .execute(&http, request(Method::GET), true)
+            .await
+            .unwrap();
+
+        assert_eq!(response.status, StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(http.attempts(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_does_not_retry_other_status_codes() {
+        let http = TestHttp::new(&[500, 200]);
+        let response = policy()
+            .execute(&http, request(Method::GET), true)
+            .await
+            .unwrap();
+
+        assert_eq!(response.status, StatusCode::INTERNAL_SERVER_ERROR);
+        assert_eq!(http.attempts(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_idempotent_only() {
+        let http = TestHttp::new(&[503, 200]);
+        policy()
+            .execute(&http, request(Method::POST), false)
+            .await
+            .unwrap();
+
+        assert_eq!(http.attempts(), 1);
+
+        let http = TestHttp::new(&[503, 200]);
+        let policy = RetryPolicy { idempotent_only: false, ..policy() };
+        policy
+            .execute(&http, request(Method::POST), false)
+            .await
+            .unwrap();
+
+        assert_eq!(http.attempts(), 2);
+    }
+
+    #[test]
+    fn test_is_idempotent() {
+        assert!(is_idempotent(&Method::GET));
+        assert!(is_idempotent(&Method::PUT));
+        assert!(!is_idempotent(&Method::POST));
+        assert!(!is_idempotent(&Method::PATCH));
+    }
+
+    #[test]
+    fn test_backoff() {
+        let policy = RetryPolicy {
+            initial_backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_millis(300),
+            jitter: false,
+            ..policy()
+        };
+
+        let actual = (1..=4)
+            .map(|attempt| policy.backoff(attempt))
+            .collect::<Vec<_>>();
+        let expected = [100, 200, 300, 300].map(Duration::from_millis).to_vec();
+
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_backoff_with_jitter() {
+        let policy = RetryPolicy { initial_backoff: Duration::from_millis(100), ..policy() };
+
+        for _ in 0..10 {
+            let backoff = policy.backoff(2);
+            assert!(backoff >= Duration::from_millis(100) && backoff <= Duration::from_millis(200));
+        }
+    }
+}
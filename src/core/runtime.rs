@@ -1,11 +1,14 @@
+use std::borrow::Cow;
 use std::sync::Arc;
 
 use async_graphql_value::ConstValue;
 
 use super::ir::model::IoId;
+use crate::core::blueprint::Tls;
+use crate::core::rate_limit::RateLimiter;
 use crate::core::schema_extension::SchemaExtension;
 use crate::core::worker::{Command, Event};
-use crate::core::{Cache, EnvIO, FileIO, HttpIO, WorkerIO};
+use crate::core::{Cache, EnvIO, FileIO, HttpIO, PersistedQueryCache, WorkerIO};
 
 /// The TargetRuntime struct unifies the available runtime-specific
 /// IO implementations. This is used to reduce piping IO structs all
@@ -25,6 +28,11 @@ pub struct TargetRuntime {
     /// Cache for storing and retrieving entity data, improving performance and
     /// reducing external calls.
     pub cache: Arc<dyn Cache<Key = IoId, Value = ConstValue>>,
+    /// Store for the documents registered through Automatic Persisted Queries,
+    /// shared by all the instances of the server where the runtime allows it.
+    pub persisted_queries: Arc<PersistedQueryCache>,
+    /// Storage for the state of the limits set with `@rateLimit`.
+    pub rate_limiter: Arc<dyn RateLimiter>,
     /// A list of extensions that can be used to extend the runtime's
     /// functionality or integrate additional features.
     pub extensions: Arc<Vec<SchemaExtension>>,
@@ -38,6 +46,31 @@ impl TargetRuntime {
     pub fn add_extensions(&mut self, extensions: Vec<SchemaExtension>) {
         self.extensions = Arc::new(extensions);
     }
+
+    /// The HTTP client for a resolver with its own TLS settings, if any.
+    pub fn http_with_tls(&self, tls: Option<&Tls>) -> Arc<dyn HttpIO> {
+        tls.and_then(|tls| self.http.with_tls(tls))
+            .unwrap_or_else(|| self.http.clone())
+    }
+
+    /// The HTTP/2 client for a resolver with its own TLS settings, if any.
+    pub fn http2_only_with_tls(&self, tls: Option<&Tls>) -> Arc<dyn HttpIO> {
+        tls.and_then(|tls| self.http2_only.with_tls(tls))
+            .unwrap_or_else(|| self.http2_only.clone())
+    }
+
+    /// The runtime whose HTTP clients use the TLS settings of a resolver, if
+    /// any.
+    pub fn with_tls(&self, tls: Option<&Tls>) -> Cow<'_, TargetRuntime> {
+        match tls {
+            Some(_) => Cow::Owned(Self {
+                http: self.http_with_tls(tls),
+                http2_only: self.http2_only_with_tls(tls),
+                ..self.clone()
+            }),
+            None => Cow::Borrowed(self),
+        }
+    }
 }
 
 #[cfg(test)]
@@ -60,6 +93,7 @@ pub mod test {
     use crate::core::blueprint::Upstream;
     use crate::core::cache::InMemoryCache;
     use crate::core::http::Response;
+    use crate::core::rate_limit::InMemoryRateLimiter;
     use crate::core::runtime::TargetRuntime;
     use crate::core::worker::{Command, Event};
     use crate::core::{blueprint, EnvIO, FileIO, HttpIO};
@@ -187,6 +221,8 @@ pub mod test {
             env: Arc::new(env),
             file: Arc::new(file),
             cache: Arc::new(InMemoryCache::default()),
+            persisted_queries: Arc::new(InMemoryCache::default()),
+            rate_limiter: Arc::new(InMemoryRateLimiter::default()),
             extensions: Arc::new(vec![]),
             cmd_worker: match &script {
                 Some(script) => Some(init_worker_io::<Event, Command>(script.to_owned())),
@@ -73,6 +73,9 @@ pub enum Scalar {
     /// Field whose value is a sequence of bytes.
     #[gen_doc(ty = "String")]
     Bytes,
+    /// Field whose value is a file uploaded with a GraphQL multipart request as specified in https://github.com/jaydenseric/graphql-multipart-request-spec.
+    #[gen_doc(ty = "Object")]
+    Upload,
 }
 
 fn eval_str<'a, Value: JsonLike<'a>, F: Fn(&str) -> bool>(val: &'a Value, fxn: F) -> bool {
@@ -128,6 +131,9 @@ impl Scalar {
             }
             Scalar::Url => eval_str(value, |s| url::Url::parse(s).is_ok()),
             Scalar::Bytes => value.as_str().is_some(),
+            Scalar::Upload => ["filename", "content"]
+                .iter()
+                .all(|key| value.get_key(key).and_then(|v| v.as_str()).is_some()),
 
             Scalar::Int64 => eval_str(value, |s| s.parse::<i64>().is_ok()),
             Scalar::UInt64 => eval_str(value, |s| s.parse::<u64>().is_ok()),
@@ -228,6 +234,25 @@ mod test {
         }
     }
 
+    mod upload {
+        use super::{ConstValue, Scalar};
+
+        test_scalar_valid! {
+            Scalar::Upload,
+            ConstValue::from_json(serde_json::json!({
+                "filename": "a.txt",
+                "contentType": "text/plain",
+                "content": "aGVsbG8="
+            })).unwrap()
+        }
+        test_scalar_invalid! {
+            Scalar::Upload,
+            ConstValue::Null,
+            ConstValue::String("a.txt".to_string()),
+            ConstValue::from_json(serde_json::json!({ "filename": "a.txt" })).unwrap()
+        }
+    }
+
     mod date {
         use super::{ConstValue, Scalar};
         test_scalar_valid! {
@@ -3,8 +3,10 @@ use std::sync::Arc;
 
 use anyhow::anyhow;
 use tailcall::core::cache::InMemoryCache;
+use tailcall::core::persisted_query::PERSISTED_QUERY_CAPACITY;
+use tailcall::core::rate_limit::InMemoryRateLimiter;
 use tailcall::core::runtime::TargetRuntime;
-use tailcall::core::{EntityCache, EnvIO, FileIO};
+use tailcall::core::{EntityCache, EnvIO, FileIO, PersistedQueryCache};
 use tokio::io::AsyncReadExt;
 
 use crate::http::init_http;
@@ -51,6 +53,10 @@ pub fn init_cache() -> Arc<EntityCache> {
     Arc::new(InMemoryCache::default())
 }
 
+pub fn init_persisted_queries() -> Arc<PersistedQueryCache> {
+    Arc::new(InMemoryCache::new(PERSISTED_QUERY_CAPACITY))
+}
+
 pub fn init_runtime() -> TargetRuntime {
     let http = init_http();
     TargetRuntime {
@@ -59,6 +65,8 @@ pub fn init_runtime() -> TargetRuntime {
         file: init_file(),
         env: init_env(),
         cache: init_cache(),
+        persisted_queries: init_persisted_queries(),
+        rate_limiter: Arc::new(InMemoryRateLimiter::default()),
         extensions: Arc::new(vec![]),
         cmd_worker: None,
         worker: None,
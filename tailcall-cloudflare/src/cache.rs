@@ -68,3 +68,76 @@ impl Cache for CloudflareChronoCache {
         None
     }
 }
+
+/// Stores strings in KV under keys starting with `prefix`, so that they are
+/// shared between all the instances of the worker. Used for the documents
+/// registered through Automatic Persisted Queries and the state of the rate
+/// limits.
+pub struct CloudflareKvCache {
+    env: Rc<worker::Env>,
+    prefix: &'static str,
+}
+
+unsafe impl Send for CloudflareKvCache {}
+
+unsafe impl Sync for CloudflareKvCache {}
+
+impl CloudflareKvCache {
+    pub fn init(env: Rc<worker::Env>, prefix: &'static str) -> Self {
+        Self { env, prefix }
+    }
+
+    fn get_kv(&self) -> Result<KvStore, cache::Error> {
+        self.env
+            .kv("TMP_KV")
+            .map_err(|e| cache::Error::Kv(e.to_string()))
+    }
+
+    fn key(&self, key: &str) -> String {
+        format!("{}:{}", self.prefix, key)
+    }
+}
+
+#[async_trait::async_trait]
+impl Cache for CloudflareKvCache {
+    type Key = String;
+    type Value = String;
+    async fn set<'a>(
+        &'a self,
+        key: String,
+        value: String,
+        ttl: NonZeroU64,
+    ) -> Result<(), cache::Error> {
+        let kv_store = self.get_kv()?;
+        // KV expects the ttl in seconds and requires at least a minute
+        let ttl = (ttl.get() / 1000).max(60);
+        let key = self.key(&key);
+        async_std::task::spawn_local(async move {
+            kv_store
+                .put(&key, value)
+                .map_err(|e| cache::Error::Kv(e.to_string()))?
+                .expiration_ttl(ttl)
+                .execute()
+                .await
+                .map_err(|e| cache::Error::Kv(e.to_string()))
+        })
+        .await
+    }
+
+    async fn get<'a>(&'a self, key: &'a String) -> Result<Option<Self::Value>, cache::Error> {
+        let kv_store = self.get_kv()?;
+        let key = self.key(key);
+        async_std::task::spawn_local(async move {
+            kv_store
+                .get(&key)
+                .text()
+                .await
+                .map_err(|e| cache::Error::Kv(e.to_string()))
+        })
+        .await
+    }
+
+    fn hit_rate(&self) -> Option<f64> {
+        None
+    }
+}
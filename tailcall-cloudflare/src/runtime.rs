@@ -4,8 +4,9 @@ use std::sync::Arc;
 use anyhow::anyhow;
 use async_graphql_value::ConstValue;
 use tailcall::core::ir::model::IoId;
+use tailcall::core::rate_limit::{CacheRateLimiter, RateLimiter};
 use tailcall::core::runtime::TargetRuntime;
-use tailcall::core::{EnvIO, FileIO, HttpIO};
+use tailcall::core::{EnvIO, FileIO, HttpIO, PersistedQueryCache};
 
 use crate::{cache, env, file, http};
 
@@ -27,6 +28,16 @@ fn init_cache(
     Arc::new(cache::CloudflareChronoCache::init(env))
 }
 
+fn init_persisted_queries(env: Rc<worker::Env>) -> Arc<PersistedQueryCache> {
+    Arc::new(cache::CloudflareKvCache::init(env, "apq"))
+}
+
+fn init_rate_limiter(env: Rc<worker::Env>) -> Arc<dyn RateLimiter> {
+    Arc::new(CacheRateLimiter::new(Arc::new(
+        cache::CloudflareKvCache::init(env, "ratelimit"),
+    )))
+}
+
 pub fn init(env: Rc<worker::Env>) -> anyhow::Result<TargetRuntime> {
     let http = init_http();
     let env_io = init_env(env.clone());
@@ -39,7 +50,9 @@ pub fn init(env: Rc<worker::Env>) -> anyhow::Result<TargetRuntime> {
         http2_only: http.clone(),
         env: init_env(env.clone()),
         file: init_file(env.clone(), &bucket_id)?,
-        cache: init_cache(env),
+        cache: init_cache(env.clone()),
+        persisted_queries: init_persisted_queries(env.clone()),
+        rate_limiter: init_rate_limiter(env),
         extensions: Arc::new(vec![]),
         cmd_worker: None,
         worker: None,
@@ -8,8 +8,20 @@ use std::sync::Arc;
 use moka::future::Cache;
 use moka::policy::EvictionPolicy;
 
+/// A storage outliving the process where the entries are written along with
+/// the cache in memory, so that they can be read back after a restart.
+#[async_trait::async_trait]
+pub trait Persistence: Send + Sync {
+    async fn load(&self, key: &str) -> Result<Option<Vec<u8>>>;
+    async fn store(&self, key: &str, value: Vec<u8>) -> Result<()>;
+    async fn remove(&self, key: &str) -> Result<()>;
+    async fn clear(&self) -> Result<()>;
+}
+
+#[derive(Clone)]
 pub struct HttpCacheManager {
     pub cache: Arc<Cache<String, Store>>,
+    persistence: Option<Arc<dyn Persistence>>,
 }
 
 impl Default for HttpCacheManager {
@@ -30,14 +42,47 @@ impl HttpCacheManager {
             .eviction_policy(EvictionPolicy::lru())
             .max_capacity(cache_size)
             .build();
-        Self { cache: Arc::new(cache) }
+        Self { cache: Arc::new(cache), persistence: None }
+    }
+
+    pub fn with_persistence(self, persistence: Arc<dyn Persistence>) -> Self {
+        Self { persistence: Some(persistence), ..self }
     }
 
     pub async fn clear(&self) -> Result<()> {
         self.cache.invalidate_all();
         self.cache.run_pending_tasks().await;
+        if let Some(persistence) = &self.persistence {
+            persistence.clear().await?;
+        }
         Ok(())
     }
+
+    /// Reads an entry missing from the memory, keeping it there for the
+    /// following requests. The entries that can't be read are missed, so that
+    /// a failing storage doesn't fail the requests.
+    async fn load(&self, cache_key: &str) -> Option<Store> {
+        let persistence = self.persistence.as_ref()?;
+        let data = match persistence.load(cache_key).await {
+            Ok(data) => data?,
+            Err(e) => {
+                tracing::warn!("Failed to read the cached response {}: {}", cache_key, e);
+                return None;
+            }
+        };
+
+        let store: Store = match serde_json::from_slice(&data) {
+            Ok(store) => store,
+            Err(e) => {
+                tracing::warn!("Ignored the corrupted cached response {}: {}", cache_key, e);
+                return None;
+            }
+        };
+        self.cache
+            .insert(cache_key.to_string(), store.clone())
+            .await;
+        Some(store)
+    }
 }
 
 #[async_trait::async_trait]
@@ -45,7 +90,10 @@ impl CacheManager for HttpCacheManager {
     async fn get(&self, cache_key: &str) -> Result<Option<(HttpResponse, CachePolicy)>> {
         let store: Store = match self.cache.get(cache_key).await {
             Some(d) => d,
-            None => return Ok(None),
+            None => match self.load(cache_key).await {
+                Some(d) => d,
+                None => return Ok(None),
+            },
         };
         Ok(Some((store.response, store.policy)))
     }
@@ -57,6 +105,15 @@ impl CacheManager for HttpCacheManager {
         policy: CachePolicy,
     ) -> Result<HttpResponse> {
         let data = Store { response: response.clone(), policy };
+        // the response is still cached in memory when it can't be written
+        if let Some(persistence) = &self.persistence {
+            if let Err(e) = persistence
+                .store(&cache_key, serde_json::to_vec(&data)?)
+                .await
+            {
+                tracing::warn!("Failed to write the cached response {}: {}", cache_key, e);
+            }
+        }
         self.cache.insert(cache_key, data).await;
         self.cache.run_pending_tasks().await;
         Ok(response)
@@ -65,6 +122,9 @@ impl CacheManager for HttpCacheManager {
     async fn delete(&self, cache_key: &str) -> Result<()> {
         self.cache.invalidate(cache_key).await;
         self.cache.run_pending_tasks().await;
+        if let Some(persistence) = &self.persistence {
+            persistence.remove(cache_key).await?;
+        }
         Ok(())
     }
 }
@@ -155,6 +215,63 @@ mod tests {
         assert!(manager.cache.iter().count() as i32 == 0);
     }
 
+    #[derive(Default)]
+    struct InMemoryPersistence {
+        data: std::sync::Mutex<HashMap<String, Vec<u8>>>,
+    }
+
+    #[async_trait::async_trait]
+    impl Persistence for InMemoryPersistence {
+        async fn load(&self, key: &str) -> Result<Option<Vec<u8>>> {
+            Ok(self.data.lock().unwrap().get(key).cloned())
+        }
+
+        async fn store(&self, key: &str, value: Vec<u8>) -> Result<()> {
+            self.data.lock().unwrap().insert(key.to_string(), value);
+            Ok(())
+        }
+
+        async fn remove(&self, key: &str) -> Result<()> {
+            self.data.lock().unwrap().remove(key);
+            Ok(())
+        }
+
+        async fn clear(&self) -> Result<()> {
+            self.data.lock().unwrap().clear();
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_get_from_persistence() {
+        let persistence = Arc::new(InMemoryPersistence::default());
+        let manager = HttpCacheManager::default().with_persistence(persistence.clone());
+        insert_key_into_cache(&manager, "test").await;
+
+        // a new manager, like the one of a restarted process
+        let manager = HttpCacheManager::default().with_persistence(persistence.clone());
+        let value = manager.get("test").await.unwrap();
+        assert!(value.is_some());
+        assert!(manager.cache.contains_key("test"));
+
+        let _ = manager.clear().await;
+        assert!(persistence.data.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_get_corrupted_from_persistence() {
+        let persistence = Arc::new(InMemoryPersistence::default());
+        persistence
+            .data
+            .lock()
+            .unwrap()
+            .insert("test".to_string(), b"{not json".to_vec());
+        let manager = HttpCacheManager::default().with_persistence(persistence);
+
+        let value = manager.get("test").await.unwrap();
+        assert!(value.is_none());
+    }
+
     #[tokio::test]
     async fn test_lru_eviction_policy() {
         let manager = HttpCacheManager::new(2);
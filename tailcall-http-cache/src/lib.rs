@@ -1,3 +1,3 @@
 mod cache;
 
-pub use cache::HttpCacheManager;
+pub use cache::{HttpCacheManager, Persistence, Result};
@@ -3,6 +3,8 @@ use std::sync::Arc;
 use async_graphql_value::ConstValue;
 use tailcall::core::cache::InMemoryCache;
 use tailcall::core::ir::model::IoId;
+use tailcall::core::persisted_query::PERSISTED_QUERY_CAPACITY;
+use tailcall::core::rate_limit::InMemoryRateLimiter;
 use tailcall::core::runtime::TargetRuntime;
 use tailcall::core::{EnvIO, FileIO, HttpIO};
 
@@ -26,18 +28,25 @@ fn init_cache() -> Arc<InMemoryCache<IoId, ConstValue>> {
     Arc::new(InMemoryCache::default())
 }
 
+fn init_persisted_queries() -> Arc<InMemoryCache<String, String>> {
+    Arc::new(InMemoryCache::new(PERSISTED_QUERY_CAPACITY))
+}
+
 pub fn init_rt() -> TargetRuntime {
     let http = init_http();
     let http2_only = init_http();
     let file = init_file();
     let env = init_env();
     let cache = init_cache();
+    let persisted_queries = init_persisted_queries();
     TargetRuntime {
         http,
         http2_only,
         env,
         file,
         cache,
+        persisted_queries,
+        rate_limiter: Arc::new(InMemoryRateLimiter::default()),
         extensions: Arc::new(vec![]),
         cmd_worker: None,
         worker: None,
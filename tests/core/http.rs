@@ -4,6 +4,7 @@ use std::path::Path;
 use std::str::FromStr;
 use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
+use std::time::Duration;
 
 use anyhow::anyhow;
 use http::header::{HeaderName, HeaderValue};
@@ -106,6 +107,10 @@ impl HttpIO for Http {
         // Clone the response from the mock to avoid borrowing issues.
         let mock_response = execution_mock.mock.response.clone();
 
+        if let Some(delay) = mock_response.0.delay {
+            tokio::time::sleep(Duration::from_millis(delay)).await;
+        }
+
         // Build the response with the status code from the mock.
         let status_code = reqwest::StatusCode::from_u16(mock_response.0.status)?;
 
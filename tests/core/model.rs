@@ -68,6 +68,9 @@ pub struct APIResponse {
     pub headers: BTreeMap<String, String>,
     #[serde(flatten, default)]
     pub body: Option<APIBody>,
+    /// Time in milliseconds the mock waits before responding
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub delay: Option<u64>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
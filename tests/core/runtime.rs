@@ -11,6 +11,7 @@ use tailcall::cli::javascript::init_worker_io;
 use tailcall::core::blueprint::Script;
 use tailcall::core::cache::InMemoryCache;
 use tailcall::core::config::Source;
+use tailcall::core::rate_limit::InMemoryRateLimiter;
 use tailcall::core::runtime::TargetRuntime;
 use tailcall::core::worker::{Command, Event};
 
@@ -80,6 +81,8 @@ pub fn create_runtime(
         env: Arc::new(env),
         file: Arc::new(file),
         cache: Arc::new(InMemoryCache::default()),
+        persisted_queries: Arc::new(InMemoryCache::default()),
+        rate_limiter: Arc::new(InMemoryRateLimiter::default()),
         extensions: Arc::new(vec![]),
         cmd_worker: match &script {
             Some(script) => Some(init_worker_io::<Event, Command>(script.to_owned())),
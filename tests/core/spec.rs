@@ -169,6 +169,7 @@ async fn run_query_tests_on_spec(
                     )
                     .unwrap_or_default(),
                 )),
+                delay: None,
             };
 
             let snapshot_name = format!("{}_{}", spec.safe_name, i);
@@ -15,6 +15,7 @@ pub mod test {
     use tailcall::core::blueprint::{Script, Upstream};
     use tailcall::core::cache::InMemoryCache;
     use tailcall::core::http::Response;
+    use tailcall::core::rate_limit::InMemoryRateLimiter;
     use tailcall::core::runtime::TargetRuntime;
     use tailcall::core::worker::{Command, Event};
     use tailcall::core::{EnvIO, FileIO, HttpIO};
@@ -144,6 +145,8 @@ pub mod test {
             env: Arc::new(env),
             file: Arc::new(file),
             cache: Arc::new(InMemoryCache::default()),
+            persisted_queries: Arc::new(InMemoryCache::default()),
+            rate_limiter: Arc::new(InMemoryRateLimiter::default()),
             extensions: Arc::new(vec![]),
             cmd_worker: match &script {
                 Some(script) => Some(init_worker_io::<Event, Command>(script.to_owned())),